@@ -0,0 +1,79 @@
+use orbclient;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendMode {
+    //NOTE(dustin): overwrites the destination, the only mode that ignores the source alpha
+    Replace,
+    //NOTE(dustin): src-over, src * src_alpha + dst * (1 - src_alpha)
+    AlphaBlend,
+    //NOTE(dustin): src * src_alpha + dst
+    Additive,
+    //NOTE(dustin): src * dst
+    Multiply,
+    //NOTE(dustin): src-over with the source color already multiplied by its alpha, src + dst * (1 - src_alpha)
+    Premultiplied
+}
+
+impl BlendMode {
+    pub fn blend(&self, src: orbclient::Color, dst: orbclient::Color) -> orbclient::Color {
+        if *self == BlendMode::Replace {
+            return src;
+        }
+
+        let src_alpha = (src.data >> 24) & 0xFF;
+        let dst_alpha = (dst.data >> 24) & 0xFF;
+
+        //NOTE(dustin): coverage accumulates like src-over for every mode except multiply
+        let alpha = match *self {
+            BlendMode::Multiply => (src_alpha * dst_alpha + 127) / 255,
+            _ => src_alpha + (dst_alpha * (255 - src_alpha) + 127) / 255,
+        };
+
+        let mut data = alpha << 24;
+        for &shift in [16_u32, 8, 0].iter() {
+            let s = (src.data >> shift) & 0xFF;
+            let d = (dst.data >> shift) & 0xFF;
+
+            let channel = match *self {
+                BlendMode::Replace => s,
+                BlendMode::AlphaBlend => (s * src_alpha + d * (255 - src_alpha) + 127) / 255,
+                BlendMode::Additive => ((s * src_alpha + 127) / 255 + d).min(255),
+                BlendMode::Multiply => (s * d + 127) / 255,
+                BlendMode::Premultiplied => (s + (d * (255 - src_alpha) + 127) / 255).min(255),
+            };
+
+            data |= channel << shift;
+        }
+
+        orbclient::Color { data }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct BlendState {
+    pub mode: BlendMode,
+    //NOTE(dustin): fragments with an alpha below the threshold are discarded before the depth write, cutouts for foliage
+    pub alpha_threshold: Option<u8>
+}
+
+impl BlendState {
+    pub fn new() -> BlendState {
+        BlendState {
+            mode: BlendMode::Replace,
+            alpha_threshold: None
+        }
+    }
+
+    pub fn is_discarded(&self, color: orbclient::Color) -> bool {
+        match self.alpha_threshold {
+            Some(threshold) => ((color.data >> 24) as u8) < threshold,
+            None => false,
+        }
+    }
+}
+
+impl Default for BlendState {
+    fn default() -> BlendState {
+        BlendState::new()
+    }
+}
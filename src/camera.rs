@@ -0,0 +1,119 @@
+use primitive::matrix::Matrix4f32;
+use primitive::quaternion::Quaternion;
+use primitive::vector::Vector4f32;
+
+#[derive(Debug, Copy, Clone)]
+pub enum Projection {
+    Perspective {
+        fov: f32,
+        aspect_ratio: f32,
+        z_near: f32,
+        z_far: f32
+    },
+    Orthographic {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        z_near: f32,
+        z_far: f32
+    }
+}
+
+impl Projection {
+    //NOTE(dustin): symmetric box around the view axis, half_height in world units
+    pub fn orthographic(half_height: f32, aspect_ratio: f32, z_near: f32, z_far: f32) -> Projection {
+        let half_width = half_height * aspect_ratio;
+        Projection::Orthographic {
+            left: -half_width,
+            right: half_width,
+            bottom: -half_height,
+            top: half_height,
+            z_near,
+            z_far
+        }
+    }
+
+    pub fn to_matrix(&self) -> Matrix4f32 {
+        match *self {
+            Projection::Perspective { fov, aspect_ratio, z_near, z_far } => Matrix4f32::new().init_perspective(fov, aspect_ratio, z_near, z_far),
+            Projection::Orthographic { left, right, bottom, top, z_near, z_far } => Matrix4f32::new().init_orthographic(left, right, bottom, top, z_near, z_far),
+        }
+    }
+}
+
+//NOTE(dustin): forward and up are normalized and perpendicular, look_at and set_orientation keep them that way
+#[derive(Debug, Copy, Clone)]
+pub struct Camera {
+    pub position: Vector4f32,
+    pub forward: Vector4f32,
+    pub up: Vector4f32,
+    pub projection: Projection
+}
+
+impl Camera {
+    pub fn new(_position: Vector4f32, _projection: Projection) -> Camera {
+        Camera {
+            position: _position,
+            forward: Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32),
+            up: Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32),
+            projection: _projection
+        }
+    }
+
+    pub fn look_at(&mut self, target: &Vector4f32, up: &Vector4f32) {
+        let forward = Vector4f32::new(target.x - self.position.x, target.y - self.position.y, target.z - self.position.z, 0_f32);
+        self.set_orientation(&forward, up);
+    }
+
+    pub fn set_orientation(&mut self, forward: &Vector4f32, up: &Vector4f32) {
+        self.forward = forward.normalized();
+        let right = up.cross(&self.forward).normalized();
+        self.up = self.forward.cross(&right);
+    }
+
+    pub fn get_right(&self) -> Vector4f32 {
+        self.up.cross(&self.forward)
+    }
+
+    pub fn move_by(&mut self, offset: &Vector4f32) {
+        self.position = Vector4f32::new(self.position.x + offset.x, self.position.y + offset.y, self.position.z + offset.z, self.position.w);
+    }
+
+    //NOTE(dustin): turns forward and up around a normalized world space axis
+    pub fn rotate(&mut self, axis: &Vector4f32, angle: f32) {
+        let rotation = Quaternion::from_axis_angle(axis, angle).mul(&self.get_rotation());
+        self.set_rotation(&rotation);
+    }
+
+    //NOTE(dustin): the rotation from the default orientation, looking down +z with +y up
+    pub fn get_rotation(&self) -> Quaternion {
+        let right = self.get_right();
+        let mut matrix = Matrix4f32::new();
+        matrix.m[0][0] = right.x;   matrix.m[0][1] = self.up.x;     matrix.m[0][2] = self.forward.x;
+        matrix.m[1][0] = right.y;   matrix.m[1][1] = self.up.y;     matrix.m[1][2] = self.forward.y;
+        matrix.m[2][0] = right.z;   matrix.m[2][1] = self.up.z;     matrix.m[2][2] = self.forward.z;
+        matrix.m[3][3] = 1_f32;
+
+        Quaternion::from_matrix(&matrix)
+    }
+
+    pub fn set_rotation(&mut self, rotation: &Quaternion) {
+        let forward = rotation.rotate(&Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32));
+        let up = rotation.rotate(&Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32));
+        self.set_orientation(&forward, &up);
+    }
+
+    pub fn get_view(&self) -> Matrix4f32 {
+        let target = self.position.add_v(&self.forward);
+        Matrix4f32::new().init_look_at(&self.position, &target, &self.up)
+    }
+
+    pub fn get_projection(&self) -> Matrix4f32 {
+        self.projection.to_matrix()
+    }
+
+    pub fn get_view_projection(&self) -> Matrix4f32 {
+        self.get_projection().mul(&self.get_view())
+    }
+}
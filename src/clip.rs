@@ -0,0 +1,50 @@
+use primitive::vertex::Vertex;
+
+//NOTE(dustin): Sutherland-Hodgman clipping in homogeneous clip space, -w <= x, y, z <= w
+pub fn clip_polygon(vertices: &mut Vec<Vertex>) -> bool {
+    let mut auxillary_list = Vec::with_capacity(vertices.len() + 6);
+
+    clip_polygon_axis(vertices, &mut auxillary_list, 0) &&
+    clip_polygon_axis(vertices, &mut auxillary_list, 1) &&
+    clip_polygon_axis(vertices, &mut auxillary_list, 2)
+}
+
+fn clip_polygon_axis(vertices: &mut Vec<Vertex>, auxillary_list: &mut Vec<Vertex>, component_index: usize) -> bool {
+    clip_polygon_component(vertices, component_index, 1_f32, auxillary_list);
+    vertices.clear();
+
+    if auxillary_list.is_empty() {
+        return false;
+    }
+
+    clip_polygon_component(auxillary_list, component_index, -1_f32, vertices);
+    auxillary_list.clear();
+
+    !vertices.is_empty()
+}
+
+fn clip_polygon_component(vertices: &[Vertex], component_index: usize, component_factor: f32, result: &mut Vec<Vertex>) {
+    let mut previous_vertex = &vertices[vertices.len() - 1];
+    let mut previous_component = previous_vertex.get(component_index) * component_factor;
+    let mut previous_inside = previous_component <= previous_vertex.pos.w;
+
+    for current_vertex in vertices.iter() {
+        let current_component = current_vertex.get(component_index) * component_factor;
+        let current_inside = current_component <= current_vertex.pos.w;
+
+        if current_inside ^ previous_inside {
+            let lerp_amt = (previous_vertex.pos.w - previous_component) /
+                ((previous_vertex.pos.w - previous_component) - (current_vertex.pos.w - current_component));
+
+            result.push(previous_vertex.lerp(current_vertex, lerp_amt));
+        }
+
+        if current_inside {
+            result.push(current_vertex.clone());
+        }
+
+        previous_vertex = current_vertex;
+        previous_component = current_component;
+        previous_inside = current_inside;
+    }
+}
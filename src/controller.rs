@@ -0,0 +1,253 @@
+use orbclient::{self, EventOption};
+use std::f32::consts::PI;
+
+use camera::Camera;
+use primitive::vector::Vector4f32;
+
+//NOTE(dustin): stops just short of straight up or down, look_at has no defined right vector there
+const MAX_PITCH: f32 = PI / 2_f32 - 0.01_f32;
+
+pub trait CameraController {
+    //NOTE(dustin): gets every window event, the ones a controller does not care about are ignored
+    fn handle_event(&mut self, event: &EventOption);
+
+    fn update(&mut self, camera: &mut Camera, delta_ms: f32);
+}
+
+fn world_up() -> Vector4f32 {
+    Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32)
+}
+
+//NOTE(dustin): +y up, yaw 0 and pitch 0 look down +z, positive yaw turns right and positive pitch looks up
+fn calc_forward(yaw: f32, pitch: f32) -> Vector4f32 {
+    Vector4f32::new(yaw.sin() * pitch.cos(), pitch.sin(), yaw.cos() * pitch.cos(), 0_f32)
+}
+
+fn calc_yaw_pitch(forward: &Vector4f32) -> (f32, f32) {
+    let forward = forward.normalized();
+    (forward.x.atan2(forward.z), forward.y.clamp(-1_f32, 1_f32).asin())
+}
+
+//NOTE(dustin): mouse deltas while the left button is held, orbclient only reports absolute positions
+struct MouseDrag {
+    dragging: bool,
+    last_pos: Option<(i32, i32)>,
+    delta_x: i32,
+    delta_y: i32
+}
+
+impl MouseDrag {
+    fn new() -> MouseDrag {
+        MouseDrag {
+            dragging: false,
+            last_pos: None,
+            delta_x: 0,
+            delta_y: 0
+        }
+    }
+
+    fn handle_event(&mut self, event: &EventOption) {
+        match *event {
+            EventOption::Button(button_event) => {
+                self.dragging = button_event.left;
+                if !self.dragging {
+                    self.last_pos = None;
+                }
+            },
+            EventOption::Mouse(mouse_event) if self.dragging => {
+                if let Some((last_x, last_y)) = self.last_pos {
+                    self.delta_x += mouse_event.x - last_x;
+                    self.delta_y += mouse_event.y - last_y;
+                }
+                self.last_pos = Some((mouse_event.x, mouse_event.y));
+            },
+            _ => (),
+        }
+    }
+
+    fn take_delta(&mut self) -> (f32, f32) {
+        let delta = (self.delta_x as f32, self.delta_y as f32);
+        self.delta_x = 0;
+        self.delta_y = 0;
+        delta
+    }
+}
+
+//NOTE(dustin): fps style, w/a/s/d move in the view direction, q/e down and up, dragging with the left button looks around
+pub struct FlyController {
+    pub speed: f32,
+    pub sensitivity: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+
+    move_forward: bool,
+    move_back: bool,
+    move_left: bool,
+    move_right: bool,
+    move_up: bool,
+    move_down: bool,
+    mouse: MouseDrag
+}
+
+impl FlyController {
+    pub fn new(camera: &Camera) -> FlyController {
+        let (yaw, pitch) = calc_yaw_pitch(&camera.forward);
+
+        FlyController {
+            speed: 2_f32,
+            sensitivity: 0.005_f32,
+            yaw,
+            pitch,
+
+            move_forward: false,
+            move_back: false,
+            move_left: false,
+            move_right: false,
+            move_up: false,
+            move_down: false,
+            mouse: MouseDrag::new()
+        }
+    }
+}
+
+impl CameraController for FlyController {
+    fn handle_event(&mut self, event: &EventOption) {
+        if let EventOption::Key(key_event) = *event {
+            match key_event.scancode {
+                orbclient::K_W => self.move_forward = key_event.pressed,
+                orbclient::K_S => self.move_back = key_event.pressed,
+                orbclient::K_A => self.move_left = key_event.pressed,
+                orbclient::K_D => self.move_right = key_event.pressed,
+                orbclient::K_E => self.move_up = key_event.pressed,
+                orbclient::K_Q => self.move_down = key_event.pressed,
+                _ => (),
+            }
+        }
+
+        self.mouse.handle_event(event);
+    }
+
+    fn update(&mut self, camera: &mut Camera, delta_ms: f32) {
+        let (delta_x, delta_y) = self.mouse.take_delta();
+        self.yaw += delta_x * self.sensitivity;
+        self.pitch = (self.pitch - delta_y * self.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+
+        let forward = calc_forward(self.yaw, self.pitch);
+        camera.set_orientation(&forward, &world_up());
+
+        let right = camera.get_right();
+        let distance = self.speed * delta_ms / 1000_f32;
+        let mut offset = Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
+        if self.move_forward {
+            offset = offset.add_v(&forward);
+        }
+        if self.move_back {
+            offset = offset.sub_v(&forward);
+        }
+        if self.move_right {
+            offset = offset.add_v(&right);
+        }
+        if self.move_left {
+            offset = offset.sub_v(&right);
+        }
+        if self.move_up {
+            offset = offset.add_v(&world_up());
+        }
+        if self.move_down {
+            offset = offset.sub_v(&world_up());
+        }
+
+        camera.move_by(&offset.mul_f(distance));
+    }
+}
+
+//NOTE(dustin): circles around target, dragging with the left button rotates and the scroll wheel zooms
+pub struct OrbitController {
+    pub target: Vector4f32,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub sensitivity: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+
+    zoom: i32,
+    mouse: MouseDrag
+}
+
+impl OrbitController {
+    pub fn new(camera: &Camera, _target: Vector4f32) -> OrbitController {
+        let to_target = _target.sub_v(&camera.position);
+        let to_target = Vector4f32::new(to_target.x, to_target.y, to_target.z, 0_f32);
+        let (yaw, pitch) = calc_yaw_pitch(&to_target);
+
+        OrbitController {
+            target: _target,
+            distance: to_target.length(),
+            min_distance: 0.1_f32,
+            sensitivity: 0.005_f32,
+            yaw,
+            pitch,
+
+            zoom: 0,
+            mouse: MouseDrag::new()
+        }
+    }
+}
+
+impl CameraController for OrbitController {
+    fn handle_event(&mut self, event: &EventOption) {
+        if let EventOption::Scroll(scroll_event) = *event {
+            self.zoom += scroll_event.y;
+        }
+
+        self.mouse.handle_event(event);
+    }
+
+    fn update(&mut self, camera: &mut Camera, _delta_ms: f32) {
+        let (delta_x, delta_y) = self.mouse.take_delta();
+        self.yaw += delta_x * self.sensitivity;
+        self.pitch = (self.pitch - delta_y * self.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+
+        self.distance = (self.distance * 0.9_f32.powi(self.zoom)).max(self.min_distance);
+        self.zoom = 0;
+
+        let forward = calc_forward(self.yaw, self.pitch);
+        camera.position = self.target.sub_v(&forward.mul_f(self.distance));
+        camera.set_orientation(&forward, &world_up());
+    }
+}
+
+//NOTE(dustin): fixed path around target at the given height, ignores input
+pub struct TurntableController {
+    pub target: Vector4f32,
+    pub distance: f32,
+    pub height: f32,
+    pub angle: f32,
+    //NOTE(dustin): radians per second
+    pub speed: f32
+}
+
+impl TurntableController {
+    pub fn new(_target: Vector4f32, _distance: f32, _height: f32, _speed: f32) -> TurntableController {
+        TurntableController {
+            target: _target,
+            distance: _distance,
+            height: _height,
+            angle: 0_f32,
+            speed: _speed
+        }
+    }
+}
+
+impl CameraController for TurntableController {
+    fn handle_event(&mut self, _event: &EventOption) {
+    }
+
+    fn update(&mut self, camera: &mut Camera, delta_ms: f32) {
+        self.angle += self.speed * delta_ms / 1000_f32;
+
+        let offset = Vector4f32::new(-self.angle.sin() * self.distance, self.height, -self.angle.cos() * self.distance, 0_f32);
+        camera.position = self.target.add_v(&offset);
+        camera.look_at(&self.target, &world_up());
+    }
+}
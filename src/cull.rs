@@ -0,0 +1,121 @@
+use primitive::matrix::Matrix4f32;
+use primitive::vector::Vector4f32;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    pub min: Vector4f32,
+    pub max: Vector4f32
+}
+
+impl Aabb {
+    //NOTE(dustin): an empty point list gives a box of size 0 around the origin
+    pub fn from_points<'a, I: Iterator<Item = &'a Vector4f32>>(points: I) -> Aabb {
+        let mut min = Vector4f32::new(f32::MAX, f32::MAX, f32::MAX, 1_f32);
+        let mut max = Vector4f32::new(f32::MIN, f32::MIN, f32::MIN, 1_f32);
+        let mut is_empty = true;
+
+        for point in points {
+            min = Vector4f32::new(min.x.min(point.x), min.y.min(point.y), min.z.min(point.z), 1_f32);
+            max = Vector4f32::new(max.x.max(point.x), max.y.max(point.y), max.z.max(point.z), 1_f32);
+            is_empty = false;
+        }
+
+        if is_empty {
+            let origin = Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32);
+            return Aabb { min: origin, max: origin };
+        }
+
+        Aabb { min, max }
+    }
+
+    pub fn get_center(&self) -> Vector4f32 {
+        self.min.lerp(&self.max, 0.5_f32)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct BoundingSphere {
+    pub center: Vector4f32,
+    pub radius: f32
+}
+
+impl BoundingSphere {
+    //NOTE(dustin): centered on the box, not the smallest possible sphere but close enough for culling
+    pub fn from_points<'a, I: Iterator<Item = &'a Vector4f32>>(points: I, aabb: &Aabb) -> BoundingSphere {
+        let center = aabb.get_center();
+        let radius = points.map(|point| Vector4f32::new(point.x - center.x, point.y - center.y, point.z - center.z, 0_f32).length())
+            .fold(0_f32, f32::max);
+
+        BoundingSphere { center, radius }
+    }
+}
+
+//NOTE(dustin): planes as (a, b, c, d) with a normalized normal pointing inside, a point p is inside if a*x + b*y + c*z + d >= 0.
+// taken from a model view projection matrix the planes are in model space, so the bounds of a mesh can be tested untransformed
+#[derive(Debug, Copy, Clone)]
+pub struct Frustum {
+    pub planes: [Vector4f32; 6]
+}
+
+impl Frustum {
+    //NOTE(dustin): -w <= x, y, z <= w like clip::clip_polygon, each plane is row 3 plus or minus row 0, 1 or 2
+    pub fn from_matrix(matrix: &Matrix4f32) -> Frustum {
+        let m = &matrix.m;
+        let row = |r_idx: usize, sign: f32| {
+            let plane = Vector4f32::new(m[3][0] + sign * m[r_idx][0], m[3][1] + sign * m[r_idx][1], m[3][2] + sign * m[r_idx][2], m[3][3] + sign * m[r_idx][3]);
+            let length = Vector4f32::new(plane.x, plane.y, plane.z, 0_f32).length();
+            plane.mul_f(1_f32 / length)
+        };
+
+        Frustum {
+            planes: [row(0, 1_f32), row(0, -1_f32), row(1, 1_f32), row(1, -1_f32), row(2, 1_f32), row(2, -1_f32)]
+        }
+    }
+
+    //NOTE(dustin): conservative, true can still mean the sphere is outside near a corner of the frustum
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        !self.planes.iter().any(|plane| Frustum::calc_distance(plane, &sphere.center) < -sphere.radius)
+    }
+
+    //NOTE(dustin): tests the corner furthest along each plane normal
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        !self.planes.iter().any(|plane| {
+            let corner = Vector4f32::new(
+                if plane.x >= 0_f32 { aabb.max.x } else { aabb.min.x },
+                if plane.y >= 0_f32 { aabb.max.y } else { aabb.min.y },
+                if plane.z >= 0_f32 { aabb.max.z } else { aabb.min.z },
+                1_f32);
+
+            Frustum::calc_distance(plane, &corner) < 0_f32
+        })
+    }
+
+    //NOTE(dustin): a degenerate plane gives NaN here, which never culls
+    fn calc_distance(plane: &Vector4f32, point: &Vector4f32) -> f32 {
+        plane.x * point.x + plane.y * point.y + plane.z * point.z + plane.w
+    }
+}
+
+//NOTE(dustin): counted by the RenderContext draw calls that know the transform of a mesh, reset by RenderContext::clear
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CullStats {
+    pub objects_drawn: usize,
+    pub objects_culled: usize,
+    pub triangles_culled: usize
+}
+
+impl CullStats {
+    pub fn new() -> CullStats {
+        CullStats {
+            objects_drawn: 0,
+            objects_culled: 0,
+            triangles_culled: 0
+        }
+    }
+}
+
+impl Default for CullStats {
+    fn default() -> CullStats {
+        CullStats::new()
+    }
+}
@@ -0,0 +1,59 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DepthFunc {
+    Less,
+    LessEqual,
+    Always,
+    Never
+}
+
+impl DepthFunc {
+    pub fn compare(&self, new_depth: f32, old_depth: f32) -> bool {
+        match *self {
+            DepthFunc::Less => new_depth < old_depth,
+            DepthFunc::LessEqual => new_depth <= old_depth,
+            DepthFunc::Always => true,
+            DepthFunc::Never => false,
+        }
+    }
+}
+
+//NOTE(dustin): stores z after the perspective divide per pixel, -1 at the near and 1 at the far plane, smaller is closer.
+// multisampled buffers store sample_count values per pixel next to each other
+pub struct DepthBuffer {
+    pub width: i32,
+    pub height: i32,
+    pub sample_count: usize,
+    pub data: Vec<f32>,
+
+    pub func: DepthFunc,
+    pub write: bool
+}
+
+impl DepthBuffer {
+    pub fn new(_width: i32, _height: i32) -> DepthBuffer {
+        DepthBuffer::new_multisampled(_width, _height, 1)
+    }
+
+    pub fn new_multisampled(_width: i32, _height: i32, _sample_count: usize) -> DepthBuffer {
+        DepthBuffer {
+            width: _width,
+            height: _height,
+            sample_count: _sample_count,
+            data: vec![f32::MAX; (_width * _height) as usize * _sample_count],
+
+            func: DepthFunc::Less,
+            write: true
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for depth in self.data.iter_mut() {
+            *depth = f32::MAX;
+        }
+    }
+
+    //NOTE(dustin): the first sample of a multisampled buffer
+    pub fn get_depth(&self, x: i32, y: i32) -> f32 {
+        self.data[(x + y * self.width) as usize * self.sample_count]
+    }
+}
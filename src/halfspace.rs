@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+use primitive::vertex::SUBPIXEL_SCALE;
+use raster::{Rasterizer, ScreenTriangle};
+use shader::{Fragment, FragmentShader};
+use target::multisample;
+
+pub const LANES: usize = 8;
+//NOTE(dustin): a row of a block is one Lanes
+pub const BLOCK_SIZE: i32 = LANES as i32;
+
+//NOTE(dustin): the edge function values of LANES neighbouring pixels. the operations are loops over a fixed size array
+// without branches, which the compiler turns into vector instructions, so a row of a block is tested at once
+#[derive(Debug, Copy, Clone)]
+pub struct Lanes {
+    pub values: [i32; LANES]
+}
+
+impl Lanes {
+    pub fn splat(value: i32) -> Lanes {
+        Lanes { values: [value; LANES] }
+    }
+
+    //NOTE(dustin): start, start + step, start + 2 * step, ...
+    pub fn ramp(start: i32, step: i32) -> Lanes {
+        let mut values = [0_i32; LANES];
+        for (idx, value) in values.iter_mut().enumerate() {
+            *value = start + step * idx as i32;
+        }
+        Lanes { values }
+    }
+
+    pub fn add(&self, other: &Lanes) -> Lanes {
+        let mut values = self.values;
+        for (value, other_value) in values.iter_mut().zip(other.values.iter()) {
+            *value += *other_value;
+        }
+        Lanes { values }
+    }
+
+    //NOTE(dustin): a lane of the result is negative if it is negative in either, so or-ing the values of all edges
+    // leaves a lane not negative only if the pixel is inside of every edge
+    pub fn or(&self, other: &Lanes) -> Lanes {
+        let mut values = self.values;
+        for (value, other_value) in values.iter_mut().zip(other.values.iter()) {
+            *value |= *other_value;
+        }
+        Lanes { values }
+    }
+
+    //NOTE(dustin): bit idx is set if lane idx is not negative
+    pub fn get_inside_mask(&self) -> u32 {
+        let mut bits = [0_u32; LANES];
+        for (idx, (bit, value)) in bits.iter_mut().zip(self.values.iter()).enumerate() {
+            *bit = !((*value >> 31) as u32) & (1 << idx);
+        }
+        bits.iter().fold(0, |mask, bit| mask | bit)
+    }
+}
+
+//NOTE(dustin): the lanes of a block start at the edge value of its corner clamped to this. the triangle is clipped to the
+// target, so the steps over a block are far smaller and a clamped value keeps its sign over the whole block
+const LANE_LIMIT: i64 = 1 << 30;
+
+//NOTE(dustin): a pixel is inside if value + x * step_x + y * step_y is not negative, the fill rule is folded into value
+struct EdgeFunction {
+    value: i64,
+    step_x: i64,
+    step_y: i64
+}
+
+impl EdgeFunction {
+    //NOTE(dustin): the same test as Edge::calc_pixel_x, pixels on a left edge are inside and on a right edge outside. min
+    // and max are the fixed point positions of the upper and the lower vertex
+    fn new(min: (i64, i64), max: (i64, i64), is_left: bool) -> EdgeFunction {
+        let dist_x = max.0 - min.0;
+        let dist_y = max.1 - min.1;
+        let value = min.1 * dist_x - min.0 * dist_y;
+
+        if is_left {
+            EdgeFunction { value, step_x: SUBPIXEL_SCALE * dist_y, step_y: -SUBPIXEL_SCALE * dist_x }
+        } else {
+            EdgeFunction { value: -value - 1, step_x: -SUBPIXEL_SCALE * dist_y, step_y: SUBPIXEL_SCALE * dist_x }
+        }
+    }
+
+    //NOTE(dustin): inside if the sample row is on or below fixed_y, the top edge of a triangle with a horizontal top
+    fn new_top(fixed_y: i64) -> EdgeFunction {
+        EdgeFunction { value: -fixed_y, step_x: 0, step_y: SUBPIXEL_SCALE }
+    }
+
+    //NOTE(dustin): inside if the sample row is above fixed_y, the bottom edge of a triangle with a horizontal bottom
+    fn new_bottom(fixed_y: i64) -> EdgeFunction {
+        EdgeFunction { value: fixed_y - 1, step_x: 0, step_y: -SUBPIXEL_SCALE }
+    }
+
+    fn calc(&self, x: i32, y: i32) -> i64 {
+        self.value + x as i64 * self.step_x + y as i64 * self.step_y
+    }
+
+    //NOTE(dustin): what a sample offset in 1/16 pixel from the pixel center adds to the value, the steps are whole
+    // multiples of SUBPIXEL_SCALE so this is exact
+    fn calc_sample_offset(&self, sample: (i32, i32)) -> i64 {
+        (sample.0 as i64 * self.step_x + sample.1 as i64 * self.step_y) / SUBPIXEL_SCALE
+    }
+}
+
+//NOTE(dustin): walks the bounding box in blocks of BLOCK_SIZE, blocks with all samples outside of one edge are
+// skipped and blocks with all samples inside of every edge are filled without testing, the rest is tested a
+// row of LANES pixels at a time for every sample
+pub fn scan_triangle<F: FragmentShader>(rasterizer: &mut Rasterizer, triangle: &ScreenTriangle, fragment: &mut Fragment, shader: &F) {
+    let samples = rasterizer.samples;
+    let reach = multisample::get_sample_reach(samples);
+    let all_samples = (1 << samples.len()) - 1;
+
+    let (start_y, end_y) = triangle.get_sample_rows(reach);
+    let start_y = start_y.max(rasterizer.min_y);
+    let end_y = end_y.min(rasterizer.max_y);
+
+    let (start_x, end_x) = triangle.get_sample_columns(reach);
+    let start_x = start_x.max(rasterizer.min_x);
+    let end_x = end_x.min(rasterizer.max_x);
+
+    //NOTE(dustin): the long edge is on the left unless side is set, horizontal edges only bound the rows like in Edge
+    let (min, mid, max) = (triangle.min_vert.get_fixed_pos(), triangle.mid_vert.get_fixed_pos(), triangle.max_vert.get_fixed_pos());
+    let long_edge = EdgeFunction::new(min, max, !triangle.side);
+    let top_edge = if min.1 == mid.1 { EdgeFunction::new_top(min.1) } else { EdgeFunction::new(min, mid, triangle.side) };
+    let bottom_edge = if mid.1 == max.1 { EdgeFunction::new_bottom(max.1) } else { EdgeFunction::new(mid, max, triangle.side) };
+    let edges = [long_edge, top_edge, bottom_edge];
+    let lane_offsets = edges.each_ref().map(|edge| Lanes::ramp(0, edge.step_x as i32));
+
+    //NOTE(dustin): the smallest and largest sample offset of every edge, the values of all samples of a pixel are between
+    // its value plus the one and plus the other
+    let sample_ranges = edges.each_ref().map(|edge| {
+        let offsets = samples.iter().map(|&sample| edge.calc_sample_offset(sample));
+        (offsets.clone().min().unwrap(), offsets.max().unwrap())
+    });
+
+    let mut block_y = start_y;
+    while block_y < end_y {
+        let block_end_y = (block_y + BLOCK_SIZE).min(end_y);
+
+        let mut block_x = start_x;
+        while block_x < end_x {
+            let block_end_x = (block_x + BLOCK_SIZE).min(end_x);
+
+            //NOTE(dustin): the edge functions are linear, so the corner pixels bound the values of all pixels of the block
+            let mut is_empty = false;
+            let mut is_full = true;
+            for (edge, &(min_offset, max_offset)) in edges.iter().zip(sample_ranges.iter()) {
+                let corner = edge.calc(block_x, block_y);
+                let span_x = edge.step_x * (block_end_x - block_x - 1) as i64;
+                let span_y = edge.step_y * (block_end_y - block_y - 1) as i64;
+                is_empty |= corner + span_x.max(0) + span_y.max(0) + max_offset < 0;
+                is_full &= corner + span_x.min(0) + span_y.min(0) + min_offset >= 0;
+            }
+
+            if is_full {
+                for idx_y in block_y..block_end_y {
+                    for idx_x in block_x..block_end_x {
+                        rasterizer.shade_pixel(fragment, idx_x, idx_y, all_samples, shader);
+                    }
+                }
+            } else if !is_empty {
+                scan_block(rasterizer, &edges, &lane_offsets, block_y..block_end_y, block_x..block_end_x, fragment, shader);
+            }
+
+            block_x = block_end_x;
+        }
+
+        block_y = block_end_y;
+    }
+}
+
+fn scan_block<F: FragmentShader>(rasterizer: &mut Rasterizer, edges: &[EdgeFunction; 3], lane_offsets: &[Lanes; 3], rows: Range<i32>, columns: Range<i32>, fragment: &mut Fragment, shader: &F) {
+    let samples = rasterizer.samples;
+    let is_pixel_center = samples == [(0, 0)];
+    let covered = calc_block_covered(edges, lane_offsets, samples, rows.clone(), columns.clone());
+
+    for (&row_covered, idx_y) in covered.iter().zip(rows) {
+        let mut remaining = row_covered;
+        while remaining != 0 {
+            let idx_x = columns.start + remaining.trailing_zeros() as i32;
+            let coverage = if is_pixel_center { 1 } else { calc_pixel_coverage(edges, samples, idx_x, idx_y) };
+            rasterizer.shade_pixel(fragment, idx_x, idx_y, coverage, shader);
+            remaining &= remaining - 1;
+        }
+    }
+}
+
+//NOTE(dustin): columns is at most LANES and rows at most BLOCK_SIZE long, bit idx of the mask of a row is set if any sample
+// of pixel columns.start + idx is inside. the edge values of a row are the ones of the row above plus step_y, so a row
+// costs a few vector adds and ors per sample. not generic over the shader, so it is compiled once and the lanes stay in
+// vector registers
+fn calc_block_covered(edges: &[EdgeFunction; 3], lane_offsets: &[Lanes; 3], samples: &[(i32, i32)], rows: Range<i32>, columns: Range<i32>) -> [u32; BLOCK_SIZE as usize] {
+    let column_mask = (1_u32 << (columns.end - columns.start)) - 1;
+    let row_steps = edges.each_ref().map(|edge| Lanes::splat(edge.step_y as i32));
+    let sample_offsets = |sample: (i32, i32)| edges.each_ref().map(|edge| Lanes::splat(edge.calc_sample_offset(sample) as i32));
+    let mut values = [0, 1, 2].map(|idx| {
+        let corner = edges[idx].calc(columns.start, rows.start).clamp(-LANE_LIMIT, LANE_LIMIT);
+        Lanes::splat(corner as i32).add(&lane_offsets[idx])
+    });
+
+    let mut covered = [0_u32; BLOCK_SIZE as usize];
+
+    //NOTE(dustin): a single sample at the pixel center needs no offsets
+    if samples == [(0, 0)] {
+        for row_covered in covered.iter_mut().take(rows.len()) {
+            *row_covered = values[0].or(&values[1]).or(&values[2]).get_inside_mask() & column_mask;
+            for (value, step) in values.iter_mut().zip(row_steps.iter()) {
+                *value = value.add(step);
+            }
+        }
+        return covered;
+    }
+
+    for row_covered in covered.iter_mut().take(rows.len()) {
+        for &sample in samples.iter() {
+            let offsets = sample_offsets(sample);
+            let inside = values[0].add(&offsets[0]).or(&values[1].add(&offsets[1])).or(&values[2].add(&offsets[2]));
+            *row_covered |= inside.get_inside_mask() & column_mask;
+        }
+
+        for (value, step) in values.iter_mut().zip(row_steps.iter()) {
+            *value = value.add(step);
+        }
+    }
+    covered
+}
+
+//NOTE(dustin): bit sample_idx is set if that sample of the pixel is inside of every edge
+fn calc_pixel_coverage(edges: &[EdgeFunction; 3], samples: &[(i32, i32)], x: i32, y: i32) -> u32 {
+    let mut coverage = 0;
+    for (sample_idx, &sample) in samples.iter().enumerate() {
+        if edges.iter().all(|edge| edge.calc(x, y) + edge.calc_sample_offset(sample) >= 0) {
+            coverage |= 1 << sample_idx;
+        }
+    }
+    coverage
+}
@@ -1,17 +1,24 @@
 use primitive::vertex::Vertex;
+use shader::MAX_VARYINGS;
 
 pub struct Interpolator {
-    pub tex_coords_x: [f32; 3],
-    pub tex_coords_y: [f32; 3],
+    pub varyings: [[f32; 3]; MAX_VARYINGS],
+    pub varying_count: usize,
     pub one_over_z: [f32; 3],
+    pub depth: [f32; 3],
 
-    pub tex_coords_step_xx: f32,
-    pub tex_coords_step_xy: f32,
-    pub tex_coords_step_yx: f32,
-    pub tex_coords_step_yy: f32,
+    pub varying_step_x: [f32; MAX_VARYINGS],
+    pub varying_step_y: [f32; MAX_VARYINGS],
 
     pub one_over_step_zx: f32,
-    pub one_over_step_zy: f32
+    pub one_over_step_zy: f32,
+
+    pub depth_step_x: f32,
+    pub depth_step_y: f32,
+
+    //NOTE(dustin): screen position of the first vertex, the values at index 0 belong to it
+    pub origin_x: f32,
+    pub origin_y: f32
 }
 
 impl Interpolator {
@@ -25,66 +32,62 @@ impl Interpolator {
         let one_over_dy = -one_over_dx;
 
         let mut _one_over_z = [0f32; 3];
-        let mut _tex_coords_x = [0f32; 3];
-        let mut _tex_coords_y = [0f32; 3];
-
-        let mut _tex_coords_step_xx = 0f32;
-        let mut _tex_coords_step_xy = 0f32;
-        let mut _tex_coords_step_yx = 0f32;
-        let mut _tex_coords_step_yy = 0f32;
+        let mut _varyings = [[0f32; 3]; MAX_VARYINGS];
+        let _varying_count = min_vert.varyings.count;
 
-        let mut _one_over_step_zx = 0f32;
-        let mut _one_over_step_zy = 0f32;
+        let mut _varying_step_x = [0f32; MAX_VARYINGS];
+        let mut _varying_step_y = [0f32; MAX_VARYINGS];
 
         _one_over_z[0] = 1.0f32/min_vert.pos.w;
         _one_over_z[1] = 1.0f32/mid_vert.pos.w;
         _one_over_z[2] = 1.0f32/max_vert.pos.w;
 
-        _tex_coords_x[0] = min_vert.tex_coords.x * _one_over_z[0];
-        _tex_coords_x[1] = mid_vert.tex_coords.x * _one_over_z[1];
-        _tex_coords_x[2] = max_vert.tex_coords.x * _one_over_z[2];
+        //NOTE(dustin): z after the perspective divide is linear in screen space, so it needs no correction and
+        // stays meaningful for orthographic projections where w is 1 everywhere
+        let _depth = [min_vert.pos.z, mid_vert.pos.z, max_vert.pos.z];
 
-        _tex_coords_y[0] = min_vert.tex_coords.y * _one_over_z[0];
-        _tex_coords_y[1] = mid_vert.tex_coords.y * _one_over_z[1];
-        _tex_coords_y[2] = max_vert.tex_coords.y * _one_over_z[2];
+        //NOTE(dustin): varyings are interpolated divided by w and multiplied back per pixel for perspective correctness
+        for idx in 0.._varying_count {
+            _varyings[idx][0] = min_vert.varyings.get(idx) * _one_over_z[0];
+            _varyings[idx][1] = mid_vert.varyings.get(idx) * _one_over_z[1];
+            _varyings[idx][2] = max_vert.varyings.get(idx) * _one_over_z[2];
+
+            _varying_step_x[idx] = Interpolator::calc_step_x(_varyings[idx], min_vert, mid_vert, max_vert, one_over_dx);
+            _varying_step_y[idx] = Interpolator::calc_step_y(_varyings[idx], min_vert, mid_vert, max_vert, one_over_dy);
+        }
 
-        _tex_coords_step_xx = Interpolator::calc_step_x(_tex_coords_x, min_vert, mid_vert, max_vert, one_over_dx);
-        _tex_coords_step_xy = Interpolator::calc_step_y(_tex_coords_x, min_vert, mid_vert, max_vert, one_over_dy);
-        _tex_coords_step_yx = Interpolator::calc_step_x(_tex_coords_y, min_vert, mid_vert, max_vert, one_over_dx);
-        _tex_coords_step_yy = Interpolator::calc_step_y(_tex_coords_y, min_vert, mid_vert, max_vert, one_over_dy);
-        _one_over_step_zx = Interpolator::calc_step_x(_one_over_z, min_vert, mid_vert, max_vert, one_over_dx);
-        _one_over_step_zy = Interpolator::calc_step_y(_one_over_z, min_vert, mid_vert, max_vert, one_over_dy);
+        let _one_over_step_zx = Interpolator::calc_step_x(_one_over_z, min_vert, mid_vert, max_vert, one_over_dx);
+        let _one_over_step_zy = Interpolator::calc_step_y(_one_over_z, min_vert, mid_vert, max_vert, one_over_dy);
+        let _depth_step_x = Interpolator::calc_step_x(_depth, min_vert, mid_vert, max_vert, one_over_dx);
+        let _depth_step_y = Interpolator::calc_step_y(_depth, min_vert, mid_vert, max_vert, one_over_dy);
 
         Interpolator{
-            tex_coords_x: _tex_coords_x,
-            tex_coords_y: _tex_coords_y,
+            varyings: _varyings,
+            varying_count: _varying_count,
             one_over_z: _one_over_z,
+            depth: _depth,
 
-            tex_coords_step_xx: _tex_coords_step_xx,
-            tex_coords_step_xy: _tex_coords_step_xy,
-            tex_coords_step_yx: _tex_coords_step_yx,
-            tex_coords_step_yy: _tex_coords_step_yy,
+            varying_step_x: _varying_step_x,
+            varying_step_y: _varying_step_y,
 
             one_over_step_zx: _one_over_step_zx,
-            one_over_step_zy: _one_over_step_zy
+            one_over_step_zy: _one_over_step_zy,
+
+            depth_step_x: _depth_step_x,
+            depth_step_y: _depth_step_y,
+
+            origin_x: min_vert.pos.x,
+            origin_y: min_vert.pos.y
         }
     }
 
     fn calc_step_x(values: [f32; 3], min_vert: &Vertex, mid_vert: &Vertex, max_vert: &Vertex, one_over_dx: f32) -> f32 {
-        let val =   (((values[1] - values[2]) *
-                    (min_vert.pos.y - max_vert.pos.y)) -
-                    ((values[0] - values[2]) *
-                    (mid_vert.pos.y - max_vert.pos.y))) * one_over_dx;
-
-        val
+        ((values[1] - values[2]) * (min_vert.pos.y - max_vert.pos.y) -
+            (values[0] - values[2]) * (mid_vert.pos.y - max_vert.pos.y)) * one_over_dx
     }
 
     fn calc_step_y(values: [f32; 3], min_vert: &Vertex, mid_vert: &Vertex, max_vert: &Vertex, one_over_dy: f32) -> f32 {
-        let val =   (((values[1] - values[2]) *
-                    (min_vert.pos.x - max_vert.pos.x)) -
-                    ((values[0] - values[2]) *
-                    (mid_vert.pos.x - max_vert.pos.x))) * one_over_dy;
-
-        val
+        ((values[1] - values[2]) * (min_vert.pos.x - max_vert.pos.x) -
+            (values[0] - values[2]) * (mid_vert.pos.x - max_vert.pos.x)) * one_over_dy
     }
 }
@@ -0,0 +1,23 @@
+extern crate orbclient;
+extern crate orbimage;
+
+pub mod blend;
+pub mod camera;
+pub mod clip;
+pub mod controller;
+pub mod cull;
+pub mod depth;
+pub mod halfspace;
+pub mod interpolate;
+pub mod light;
+pub mod line;
+pub mod model;
+pub mod postprocess;
+pub mod primitive;
+pub mod raster;
+pub mod render;
+pub mod scene;
+pub mod shader;
+pub mod target;
+pub mod texture;
+pub mod tile;
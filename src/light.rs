@@ -0,0 +1,386 @@
+use orbclient;
+
+use model::material::Material;
+use primitive::matrix::Matrix4f32;
+use primitive::vector::Vector4f32;
+use primitive::vertex::Vertex;
+use shader::{self, Fragment, FragmentShader, Varyings, VertexShader};
+use texture::bitmap::BitmapTexture;
+use texture::sampler::{Sampler, TexCoords};
+
+#[derive(Debug, Copy, Clone)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub exponent: f32
+}
+
+impl Attenuation {
+    pub fn new(_constant: f32, _linear: f32, _exponent: f32) -> Attenuation {
+        Attenuation {
+            constant: _constant,
+            linear: _linear,
+            exponent: _exponent
+        }
+    }
+
+    //NOTE(dustin): the light is divided by this, it is kept above 0 so an attenuation of (0, 0, 0) or a light at distance 0
+    // without a constant term gives a bright but finite color
+    pub fn calc(&self, distance: f32) -> f32 {
+        (self.constant + self.linear * distance + self.exponent * distance * distance).max(1e-6_f32)
+    }
+}
+
+//NOTE(dustin): colors are linear rgb in x, y, z, positions and directions are in world space
+#[derive(Debug, Copy, Clone)]
+pub enum Light {
+    Ambient {
+        color: Vector4f32
+    },
+    Directional {
+        direction: Vector4f32,
+        color: Vector4f32
+    },
+    Point {
+        position: Vector4f32,
+        color: Vector4f32,
+        attenuation: Attenuation
+    },
+    Spot {
+        position: Vector4f32,
+        direction: Vector4f32,
+        color: Vector4f32,
+        attenuation: Attenuation,
+        inner_angle: f32,
+        outer_angle: f32
+    }
+}
+
+impl Light {
+    //NOTE(dustin): moves a light from node space into world space, angles and colors are kept. directions are transformed
+    // with w = 0, so the translation of the node does not turn them
+    pub fn transform(&self, matrix: &Matrix4f32) -> Light {
+        let transform_direction = |direction: Vector4f32| matrix.transform(&Vector4f32::new(direction.x, direction.y, direction.z, 0_f32));
+
+        match *self {
+            Light::Ambient { color } => Light::Ambient { color },
+            Light::Directional { direction, color } => Light::Directional { direction: transform_direction(direction), color },
+            Light::Point { position, color, attenuation } => Light::Point { position: matrix.transform(&position), color, attenuation },
+            Light::Spot { position, direction, color, attenuation, inner_angle, outer_angle } => Light::Spot {
+                position: matrix.transform(&position),
+                direction: transform_direction(direction),
+                color,
+                attenuation,
+                inner_angle,
+                outer_angle
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShadingMode {
+    //NOTE(dustin): blinn-phong per vertex, the light is interpolated
+    Gouraud,
+    //NOTE(dustin): per pixel with reflected light vector
+    Phong,
+    //NOTE(dustin): per pixel with half vector
+    BlinnPhong
+}
+
+//NOTE(dustin): how a surface reflects light, ambient scales the ambient lights and specular the highlight of every direct light
+#[derive(Debug, Copy, Clone)]
+pub struct Reflectance {
+    pub ambient: Vector4f32,
+    pub specular: Vector4f32,
+    pub specular_power: f32
+}
+
+impl Reflectance {
+    pub fn new(_ambient: Vector4f32, _specular: Vector4f32, _specular_power: f32) -> Reflectance {
+        Reflectance {
+            ambient: _ambient,
+            specular: _specular,
+            specular_power: _specular_power
+        }
+    }
+
+    //NOTE(dustin): Ka, Ks and Ns of the mtl file, the highlight is off below illum 2
+    pub fn from_material(material: &Material) -> Reflectance {
+        let specular = if material.illum >= 2 { material.specular } else { Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32) };
+        Reflectance::new(material.ambient, specular, material.specular_exponent)
+    }
+}
+
+//NOTE(dustin): specular_intensity and specular_power are the reflectance of surfaces without a material
+pub struct Lighting {
+    pub lights: Vec<Light>,
+    pub eye_pos: Vector4f32,
+    pub specular_intensity: f32,
+    pub specular_power: f32
+}
+
+impl Lighting {
+    pub fn new(_eye_pos: Vector4f32) -> Lighting {
+        Lighting {
+            lights: Vec::new(),
+            eye_pos: _eye_pos,
+            specular_intensity: 0.5_f32,
+            specular_power: 16_f32
+        }
+    }
+
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    pub fn get_reflectance(&self) -> Reflectance {
+        let intensity = self.specular_intensity;
+        Reflectance::new(Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32), Vector4f32::new(intensity, intensity, intensity, 0_f32), self.specular_power)
+    }
+
+    pub fn calc_light(&self, pos: &Vector4f32, normal: &Vector4f32, blinn: bool, reflectance: &Reflectance) -> Vector4f32 {
+        let mut total = Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
+
+        for light in self.lights.iter() {
+            let contribution = match *light {
+                Light::Ambient { color } => color.mul_v(&reflectance.ambient),
+                Light::Directional { direction, color } => {
+                    let to_light = Vector4f32::new(-direction.x, -direction.y, -direction.z, 0_f32).normalized();
+                    self.calc_direct(&color, &to_light, pos, normal, blinn, reflectance)
+                },
+                Light::Point { position, color, attenuation } => {
+                    let (to_light, distance) = Lighting::calc_to_light(&position, pos);
+                    self.calc_direct(&color, &to_light, pos, normal, blinn, reflectance).mul_f(1_f32 / attenuation.calc(distance))
+                },
+                Light::Spot { position, direction, color, attenuation, inner_angle, outer_angle } => {
+                    let (to_light, distance) = Lighting::calc_to_light(&position, pos);
+                    let spot_direction = Vector4f32::new(direction.x, direction.y, direction.z, 0_f32).normalized();
+                    let cos_angle = -to_light.dot(&spot_direction);
+                    let cos_inner = inner_angle.cos();
+                    let cos_outer = outer_angle.cos();
+                    let spot_factor = ((cos_angle - cos_outer) / (cos_inner - cos_outer).max(1e-6_f32)).clamp(0_f32, 1_f32);
+
+                    self.calc_direct(&color, &to_light, pos, normal, blinn, reflectance).mul_f(spot_factor / attenuation.calc(distance))
+                },
+            };
+
+            total = total.add_v(&contribution);
+        }
+
+        total
+    }
+
+    fn calc_to_light(light_pos: &Vector4f32, pos: &Vector4f32) -> (Vector4f32, f32) {
+        let to_light = Vector4f32::new(light_pos.x - pos.x, light_pos.y - pos.y, light_pos.z - pos.z, 0_f32);
+        let distance = to_light.length();
+
+        (to_light.mul_f(1_f32 / distance), distance)
+    }
+
+    fn calc_direct(&self, color: &Vector4f32, to_light: &Vector4f32, pos: &Vector4f32, normal: &Vector4f32, blinn: bool, reflectance: &Reflectance) -> Vector4f32 {
+        let diffuse_factor = normal.dot(to_light);
+        if diffuse_factor <= 0_f32 {
+            return Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
+        }
+
+        let to_eye = Vector4f32::new(self.eye_pos.x - pos.x, self.eye_pos.y - pos.y, self.eye_pos.z - pos.z, 0_f32).normalized();
+        let specular_factor = if blinn {
+            let half = to_light.add_v(&to_eye).normalized();
+            normal.dot(&half)
+        } else {
+            let reflected = normal.mul_f(2_f32 * diffuse_factor).sub_v(to_light);
+            reflected.dot(&to_eye)
+        };
+
+        let highlight = if specular_factor > 0_f32 {
+            specular_factor.powf(reflectance.specular_power)
+        } else {
+            0_f32
+        };
+
+        let specular = &reflectance.specular;
+        Vector4f32::new(color.x * (diffuse_factor + highlight * specular.x), color.y * (diffuse_factor + highlight * specular.y),
+            color.z * (diffuse_factor + highlight * specular.z), color.w)
+    }
+}
+
+pub fn apply_light(color: orbclient::Color, light: &Vector4f32) -> orbclient::Color {
+    let r = (((color.data >> 16) & 0xFF) as f32 * light.x).min(255_f32) as u32;
+    let g = (((color.data >> 8) & 0xFF) as f32 * light.y).min(255_f32) as u32;
+    let b = ((color.data & 0xFF) as f32 * light.z).min(255_f32) as u32;
+
+    orbclient::Color { data: (color.data & 0xFF000000) | (r << 16) | (g << 8) | b }
+}
+
+//NOTE(dustin): varyings are u, v and the light color for gouraud, u, v, world position and normal otherwise
+pub struct LightingVertexShader<'a> {
+    pub model: &'a Matrix4f32,
+    pub model_view_projection: Matrix4f32,
+    pub normal_matrix: Matrix4f32,
+    pub lighting: &'a Lighting,
+    pub reflectance: Reflectance,
+    pub mode: ShadingMode
+}
+
+impl<'a> LightingVertexShader<'a> {
+    pub fn new(model: &'a Matrix4f32, view_projection: &Matrix4f32, lighting: &'a Lighting, mode: ShadingMode) -> LightingVertexShader<'a> {
+        LightingVertexShader {
+            model,
+            model_view_projection: view_projection.mul(model),
+            normal_matrix: model.normal_matrix(),
+            lighting,
+            reflectance: lighting.get_reflectance(),
+            mode
+        }
+    }
+
+    //NOTE(dustin): only used for gouraud, the other modes light in the fragment shader
+    pub fn init_reflectance(mut self, reflectance: Reflectance) -> LightingVertexShader<'a> {
+        self.reflectance = reflectance;
+        self
+    }
+}
+
+impl<'a> VertexShader for LightingVertexShader<'a> {
+    fn shade(&self, vertex: &Vertex, varyings: &mut Varyings) -> Vector4f32 {
+        let world_pos = self.model.transform(&vertex.pos);
+        let normal = self.normal_matrix.transform(&vertex.normal).normalized();
+
+        *varyings = match self.mode {
+            ShadingMode::Gouraud => {
+                let light = self.lighting.calc_light(&world_pos, &normal, true, &self.reflectance);
+                Varyings::from_slice(&[vertex.tex_coords.x, vertex.tex_coords.y, light.x, light.y, light.z])
+            },
+            ShadingMode::Phong | ShadingMode::BlinnPhong => {
+                Varyings::from_slice(&[vertex.tex_coords.x, vertex.tex_coords.y,
+                    world_pos.x, world_pos.y, world_pos.z,
+                    normal.x, normal.y, normal.z])
+            },
+        };
+
+        self.model_view_projection.transform(&vertex.pos)
+    }
+}
+
+pub struct LightingFragmentShader<'a> {
+    pub texture: &'a BitmapTexture,
+    pub sampler: Sampler,
+    pub lighting: &'a Lighting,
+    pub mode: ShadingMode
+}
+
+impl<'a> LightingFragmentShader<'a> {
+    pub fn new(texture: &'a BitmapTexture, sampler: Sampler, lighting: &'a Lighting, mode: ShadingMode) -> LightingFragmentShader<'a> {
+        LightingFragmentShader {
+            texture,
+            sampler,
+            lighting,
+            mode
+        }
+    }
+}
+
+impl<'a> FragmentShader for LightingFragmentShader<'a> {
+    fn shade(&self, fragment: &Fragment) -> Option<orbclient::Color> {
+        let color = shader::sample_texture(self.texture, &self.sampler, fragment, 0, 1);
+
+        Some(apply_light(color, &calc_fragment_light(self.lighting, &self.lighting.get_reflectance(), self.mode, fragment, None, &self.sampler)))
+    }
+}
+
+//NOTE(dustin): LightingFragmentShader for a material instead of a plain texture, used by draw_scene. the reflectance
+// comes from the material and not from the Lighting, the bump or normal map bends the normal of the per pixel modes
+pub struct LightingMaterialShader<'a> {
+    pub material: &'a Material,
+    pub sampler: Sampler,
+    pub lighting: &'a Lighting,
+    pub reflectance: Reflectance,
+    pub mode: ShadingMode
+}
+
+impl<'a> LightingMaterialShader<'a> {
+    pub fn new(material: &'a Material, sampler: Sampler, lighting: &'a Lighting, mode: ShadingMode) -> LightingMaterialShader<'a> {
+        LightingMaterialShader {
+            material,
+            sampler,
+            lighting,
+            reflectance: Reflectance::from_material(material),
+            mode
+        }
+    }
+}
+
+impl<'a> FragmentShader for LightingMaterialShader<'a> {
+    fn shade(&self, fragment: &Fragment) -> Option<orbclient::Color> {
+        let color = shader::sample_material(self.material, &self.sampler, fragment);
+        if self.material.illum == 0 {
+            return Some(color);
+        }
+
+        Some(apply_light(color, &calc_fragment_light(self.lighting, &self.reflectance, self.mode, fragment, Some(self.material), &self.sampler)))
+    }
+}
+
+//NOTE(dustin): the light at a fragment from the varyings written by LightingVertexShader
+fn calc_fragment_light(lighting: &Lighting, reflectance: &Reflectance, mode: ShadingMode, fragment: &Fragment, material: Option<&Material>, sampler: &Sampler) -> Vector4f32 {
+    let varyings = &fragment.varyings;
+
+    match mode {
+        ShadingMode::Gouraud => Vector4f32::new(varyings.get(2), varyings.get(3), varyings.get(4), 0_f32),
+        ShadingMode::Phong | ShadingMode::BlinnPhong => {
+            let pos = Vector4f32::new(varyings.get(2), varyings.get(3), varyings.get(4), 1_f32);
+            let normal = Vector4f32::new(varyings.get(5), varyings.get(6), varyings.get(7), 0_f32).normalized();
+            //NOTE(dustin): the back side of a double sided surface is lit like the other side of a sheet
+            let normal = if fragment.front_facing { normal } else { normal.mul_f(-1_f32) };
+            let normal = match material {
+                Some(material) => calc_mapped_normal(material, sampler, fragment, &normal),
+                None => normal,
+            };
+            lighting.calc_light(&pos, &normal, mode == ShadingMode::BlinnPhong, reflectance)
+        },
+    }
+}
+
+//NOTE(dustin): the surface frame comes from the screen space derivatives of the world position and the texture
+// coordinates, so meshes need no tangents. the bump map follows Mikkelsen, bump mapping unparametrized surfaces on the gpu,
+// with the height in the red channel times bump_multiplier. the normal map is in tangent space with green along +v
+fn calc_mapped_normal(material: &Material, sampler: &Sampler, fragment: &Fragment, normal: &Vector4f32) -> Vector4f32 {
+    let pos_dx = Vector4f32::new(fragment.ddx(2), fragment.ddx(3), fragment.ddx(4), 0_f32);
+    let pos_dy = Vector4f32::new(fragment.ddy(2), fragment.ddy(3), fragment.ddy(4), 0_f32);
+    let pos_dy_perp = pos_dy.cross(normal);
+    let pos_dx_perp = normal.cross(&pos_dx);
+    let det = pos_dx.dot(&pos_dy_perp);
+    if det == 0_f32 {
+        return *normal;
+    }
+
+    let coords = TexCoords::new(fragment.varyings.get(0), fragment.varyings.get(1))
+        .init_derivatives(fragment.ddx(0), fragment.ddx(1), fragment.ddy(0), fragment.ddy(1));
+
+    if let Some(ref texture) = material.bump_texture {
+        let height_at = |du: f32, dv: f32| {
+            let texel = sampler.sample_grad(texture, &TexCoords { u: coords.u + du, v: coords.v + dv, ..coords });
+            ((texel.data >> 16) & 0xFF) as f32 / 255_f32 * material.bump_multiplier
+        };
+
+        let height = height_at(0_f32, 0_f32);
+        let height_dx = height_at(coords.du_dx, coords.dv_dx) - height;
+        let height_dy = height_at(coords.du_dy, coords.dv_dy) - height;
+        let gradient = pos_dy_perp.mul_f(height_dx).add_v(&pos_dx_perp.mul_f(height_dy)).mul_f(det.signum());
+        return normal.mul_f(det.abs()).sub_v(&gradient).normalized();
+    }
+
+    if let Some(ref texture) = material.normal_texture {
+        let tangent = pos_dy_perp.mul_f(coords.du_dx).add_v(&pos_dx_perp.mul_f(coords.du_dy));
+        let bitangent = pos_dy_perp.mul_f(coords.dv_dx).add_v(&pos_dx_perp.mul_f(coords.dv_dy));
+        let scale = 1_f32 / tangent.dot(&tangent).max(bitangent.dot(&bitangent)).sqrt().max(1e-20_f32);
+
+        let texel = sampler.sample_grad(texture, &coords).data;
+        let channel = |shift: u32| ((texel >> shift) & 0xFF) as f32 / 127.5_f32 - 1_f32;
+        //NOTE(dustin): v is flipped when the obj is loaded, so the bitangent points against +v of the map
+        return tangent.mul_f(scale * channel(16)).sub_v(&bitangent.mul_f(scale * channel(8))).add_v(&normal.mul_f(channel(0))).normalized();
+    }
+
+    *normal
+}
@@ -0,0 +1,160 @@
+use orbclient;
+
+use blend::BlendState;
+use primitive::vector::Vector4f32;
+use target::framebuffer::Framebuffer;
+
+//NOTE(dustin): how the triangles of a mesh are drawn, Line and Point ignore the fragment shader and use the wire color
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PolygonMode {
+    Fill,
+    Line,
+    Point
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum LineColor {
+    Constant(orbclient::Color),
+    //NOTE(dustin): from the color at the start to the color at the end, perspective correct
+    Interpolated(orbclient::Color, orbclient::Color)
+}
+
+impl LineColor {
+    pub fn get_colors(&self) -> (orbclient::Color, orbclient::Color) {
+        match *self {
+            LineColor::Constant(color) => (color, color),
+            LineColor::Interpolated(from, to) => (from, to),
+        }
+    }
+}
+
+//NOTE(dustin): after the perspective divide and the screen space transform, one_over_w is kept for perspective correct colors
+#[derive(Debug, Copy, Clone)]
+pub struct ScreenPoint {
+    pub x: f32,
+    pub y: f32,
+    pub depth: f32,
+    pub one_over_w: f32
+}
+
+impl ScreenPoint {
+    //NOTE(dustin): pos is already transformed to screen space but not divided
+    pub fn from_screen_space(pos: &Vector4f32) -> ScreenPoint {
+        ScreenPoint {
+            x: pos.x / pos.w,
+            y: pos.y / pos.w,
+            depth: pos.z / pos.w,
+            one_over_w: 1_f32 / pos.w
+        }
+    }
+}
+
+//NOTE(dustin): Liang-Barsky in homogeneous clip space against -w <= x, y, z <= w like clip::clip_polygon,
+// returns the part of the line that is left as factors between from and to
+pub fn clip_line(from: &Vector4f32, to: &Vector4f32) -> Option<(f32, f32)> {
+    let mut start = 0_f32;
+    let mut end = 1_f32;
+
+    let from_components = [from.x, from.y, from.z];
+    let to_components = [to.x, to.y, to.z];
+    for idx in 0..3 {
+        for &sign in [1_f32, -1_f32].iter() {
+            let from_dist = from.w - sign * from_components[idx];
+            let to_dist = to.w - sign * to_components[idx];
+
+            if from_dist < 0_f32 && to_dist < 0_f32 {
+                return None;
+            }
+            if from_dist < 0_f32 {
+                start = start.max(from_dist / (from_dist - to_dist));
+            } else if to_dist < 0_f32 {
+                end = end.min(from_dist / (from_dist - to_dist));
+            }
+        }
+    }
+
+    if start > end {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+pub fn is_point_inside(pos: &Vector4f32) -> bool {
+    pos.x.abs() <= pos.w && pos.y.abs() <= pos.w && pos.z.abs() <= pos.w
+}
+
+//NOTE(dustin): writes pixels like the triangle rasterizer, with depth_test false the depth buffer is neither read nor written
+pub struct LineRasterizer<'a> {
+    pub target: &'a mut Framebuffer,
+    pub depth_test: bool,
+    pub blend: BlendState
+}
+
+impl<'a> LineRasterizer<'a> {
+    //NOTE(dustin): DDA that steps one pixel along the major axis, both end points are drawn
+    pub fn draw_line(&mut self, from: &ScreenPoint, to: &ScreenPoint, from_color: orbclient::Color, to_color: orbclient::Color) {
+        let dist_x = to.x - from.x;
+        let dist_y = to.y - from.y;
+        let steps = dist_x.abs().max(dist_y.abs()).round().max(1_f32) as i32;
+
+        for idx in 0..steps + 1 {
+            let lerp_factor = idx as f32 / steps as f32;
+            let one_over_w = from.one_over_w + (to.one_over_w - from.one_over_w) * lerp_factor;
+            let color_factor = lerp_factor * to.one_over_w / one_over_w;
+
+            self.draw_pixel(
+                (from.x + dist_x * lerp_factor).round() as i32,
+                (from.y + dist_y * lerp_factor).round() as i32,
+                from.depth + (to.depth - from.depth) * lerp_factor,
+                lerp_color(from_color, to_color, color_factor));
+        }
+    }
+
+    //NOTE(dustin): a square of size pixels centered on the point
+    pub fn draw_point(&mut self, point: &ScreenPoint, color: orbclient::Color, size: i32) {
+        let center_x = point.x.round() as i32;
+        let center_y = point.y.round() as i32;
+        let offset = (size - 1) / 2;
+
+        for idx_y in 0..size {
+            for idx_x in 0..size {
+                self.draw_pixel(center_x - offset + idx_x, center_y - offset + idx_y, point.depth, color);
+            }
+        }
+    }
+
+    fn draw_pixel(&mut self, x: i32, y: i32, depth: f32, color: orbclient::Color) {
+        if x < 0 || y < 0 || x >= self.target.width || y >= self.target.height {
+            return;
+        }
+
+        if self.blend.is_discarded(color) {
+            return;
+        }
+
+        //NOTE(dustin): lines and points cover every sample of a pixel
+        let sample_count = self.target.samples.len();
+        let pixel_idx = (x + y * self.target.width) as usize * sample_count;
+        for data_idx in pixel_idx..pixel_idx + sample_count {
+            if self.depth_test && !self.target.depth.func.compare(depth, self.target.depth.data[data_idx]) {
+                continue;
+            }
+
+            if self.depth_test && self.target.depth.write {
+                self.target.depth.data[data_idx] = depth;
+            }
+            self.target.color[data_idx] = self.blend.mode.blend(color, self.target.color[data_idx]);
+        }
+    }
+}
+
+pub fn lerp_color(from: orbclient::Color, to: orbclient::Color, lerp_factor: f32) -> orbclient::Color {
+    let channel = |shift: u32| {
+        let from_channel = ((from.data >> shift) & 0xFF) as f32;
+        let to_channel = ((to.data >> shift) & 0xFF) as f32;
+        ((from_channel + (to_channel - from_channel) * lerp_factor + 0.5_f32) as u32).min(255) << shift
+    };
+
+    orbclient::Color { data: channel(24) | channel(16) | channel(8) | channel(0) }
+}
@@ -1,7 +1,6 @@
-#![feature(step_by)]
-
 extern crate orbclient;
 extern crate orbimage;
+extern crate pixelcannon;
 
 use orbclient::EventOption;
 use orbimage::Image;
@@ -9,146 +8,255 @@ use orbimage::Image;
 use std::time::Instant;
 use std::thread;
 
-use model::mesh::Mesh;
-use primitive::matrix::Matrix4f32;
-use render::RenderContext;
-use texture::bitmap::BitmapTexture;
-
-pub mod interpolate;
-pub mod model;
-pub mod primitive;
-pub mod render;
-pub mod texture;
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::controller::{CameraController, FlyController, OrbitController, TurntableController};
+use pixelcannon::light::{Attenuation, Light, ShadingMode};
+use pixelcannon::line::{LineColor, PolygonMode};
+use pixelcannon::model::material::Material;
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::postprocess::color::Vignette;
+use pixelcannon::postprocess::outline::Outline;
+use pixelcannon::primitive::quaternion::Quaternion;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::raster::RasterAlgorithm;
+use pixelcannon::render::RenderContext;
+use pixelcannon::scene::{Node, Scene, Transform};
+use pixelcannon::target::multisample::{Antialiasing, SampleCount};
+use pixelcannon::target::presenter::{Presenter, WindowPresenter};
+use pixelcannon::texture::bitmap::BitmapTexture;
+use pixelcannon::texture::sampler::{Filter, Sampler};
 
 fn main() {
-    let mut render_context = RenderContext::new(800, 600, "pixelcannon");
+    let mut window = WindowPresenter::new(800, 600, "pixelcannon");
+    let mut render_context = RenderContext::with_antialiasing(window.get_width(), window.get_height(), Antialiasing::Msaa(SampleCount::X4));
+    render_context.set_worker_count(thread::available_parallelism().map(|count| count.get()).unwrap_or(1));
     let mut start = Instant::now();
 
-    let projection = Matrix4f32::new().init_perspective(70.0_f32.to_radians(), render_context.get_width() as f32 / render_context.get_height() as f32, 0.1_f32, 1000_f32);
+    let fov = 70.0_f32.to_radians();
+    let aspect_ratio = render_context.get_width() as f32 / render_context.get_height() as f32;
+    let projection = Projection::Perspective { fov, aspect_ratio, z_near: 0.1_f32, z_far: 1000_f32 };
+    let origin = Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32);
+    let mut camera = Camera::new(Vector4f32::new(0_f32, 0_f32, -4_f32, 1_f32), projection);
+
+    //NOTE(dustin): c switches between fly, orbit and turntable, each one starts from the current camera
+    let mut fly = FlyController::new(&camera);
+    let mut orbit = OrbitController::new(&camera, origin);
+    let mut turntable = TurntableController::new(origin, 4_f32, 1_f32, 0.5_f32);
+    let mut controller_idx = 0;
+
+    //NOTE(dustin): i/k, j/l and u/o turn the sphere around its own x, y and z axis, accumulated as a quaternion so there is no gimbal lock
+    let mut model_rotation = Quaternion::from_euler(0_f32, -0.5_f32, 0_f32);
+    let mut turn_x = 0_f32;
+    let mut turn_y = 0_f32;
+    let mut turn_z = 0_f32;
 
     let mut basepath = "";
     if cfg!(target_os = "redox") {
         basepath = "/apps/pixelcannon/";
     }
 
-    let mesh = Mesh::from_path(basepath.to_string() + "assets/sphere.obj").unwrap();
-
-    let image = Image::from_path(basepath.to_string() + "assets/img2.png").unwrap();
-    let texture = BitmapTexture::from_orbimage(&image);
+    //NOTE(dustin): a sphere in the middle and three monkeys on a pivot that spins around it, each monkey also spins on its own
+    let mut scene = Scene::new();
+    scene.shading = None;
+    let sphere_mesh = scene.add_mesh(Mesh::from_path(basepath.to_string() + "assets/sphere.obj").unwrap());
+    let monkey_mesh = scene.add_mesh(Mesh::from_path(basepath.to_string() + "assets/monkey2.obj").unwrap());
+    let sphere_material = scene.add_material(load_material("sphere", basepath.to_string() + "assets/img2.png"));
+    let monkey_material = scene.add_material(load_material("monkey", basepath.to_string() + "assets/img.png"));
 
-    let mut trans_x = 0_f32;
-    let mut trans_y = 0_f32;
-    let mut trans_z = 4.0_f32;
-
-    let mut rot_x = 0_f32;
-    let mut rot_y = -0.5_f32;
-    let mut rot_z = 0_f32;
+    let sphere = scene.add_node(Node::new("sphere").init_mesh(sphere_mesh).init_material(sphere_material), None);
+    let pivot = scene.add_node(Node::new("pivot"), None);
+    let mut monkeys = Vec::new();
+    for monkey_idx in 0..3 {
+        let angle = monkey_idx as f32 * 2_f32 * std::f32::consts::PI / 3_f32;
+        let orbit = scene.add_node(Node::new("orbit").init_transform(Transform::new()
+            .init_rotation(Quaternion::from_axis_angle(&Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32), angle))), Some(pivot));
+        monkeys.push(scene.add_node(Node::new("monkey").init_mesh(monkey_mesh).init_material(monkey_material).init_transform(Transform::new()
+            .init_translation(0_f32, 0_f32, -2.5_f32)
+            .init_scale(0.5_f32, 0.5_f32, 0.5_f32)), Some(orbit)));
+    }
 
-    let mut move_forward = false;
-    let mut move_back = false;
-    let mut move_left = false;
-    let mut move_right = false;
-    let mut move_up = false;
-    let mut move_down = false;
+    scene.add_node(Node::new("ambient").init_light(Light::Ambient { color: Vector4f32::new(0.2_f32, 0.2_f32, 0.2_f32, 0_f32) }), None);
+    scene.add_node(Node::new("sun").init_light(Light::Directional { direction: Vector4f32::new(1_f32, -1_f32, 1_f32, 0_f32), color: Vector4f32::new(0.8_f32, 0.8_f32, 0.7_f32, 0_f32) }), None);
+    scene.add_node(Node::new("blue").init_light(Light::Point { position: Vector4f32::new(0_f32, 1_f32, 0_f32, 1_f32), color: Vector4f32::new(0_f32, 0.4_f32, 1_f32, 0_f32), attenuation: Attenuation::new(0_f32, 0_f32, 0.5_f32) })
+        .init_transform(Transform::new().init_translation(0_f32, 0_f32, -2.5_f32)), Some(pivot));
+    let mut time = 0_f32;
 
-    let mut turn_forward = false;
-    let mut turn_back = false;
-    let mut turn_left = false;
-    let mut turn_right = false;
-    let mut turn_up = false;
-    let mut turn_down = false;
+    //NOTE(dustin): m switches between fill, line and point, b shows the bounding boxes and the world axes,
+    // r switches between the scanline and the half-space rasterizer to compare their speed, v toggles the post passes
+    let mut show_bounds = false;
 
     let mut frame_cnt = 0_f32;
     let mut counter_duration = 0_f32;
 
     'event: loop {
-        for orbital_event in render_context.events() {
-            match orbital_event.to_option() {
+        for orbital_event in window.events() {
+            let event = orbital_event.to_option();
+            match event {
                 EventOption::Key(key_event) => {
+                    let turn = if key_event.pressed { 1_f32 } else { 0_f32 };
                     match key_event.scancode {
-                        //Translation
-                        orbclient::K_W => move_forward = key_event.pressed,
-                        orbclient::K_S => move_back = key_event.pressed,
-                        orbclient::K_A => move_left = key_event.pressed,
-                        orbclient::K_D => move_right = key_event.pressed,
-                        orbclient::K_Q => move_down = key_event.pressed,
-                        orbclient::K_E => move_up = key_event.pressed,
-
                         //Rotation
-                        orbclient::K_I => turn_forward = key_event.pressed,
-                        orbclient::K_K => turn_back = key_event.pressed,
-                        orbclient::K_J => turn_left = key_event.pressed,
-                        orbclient::K_L => turn_right = key_event.pressed,
-                        orbclient::K_U => turn_down = key_event.pressed,
-                        orbclient::K_O => turn_up = key_event.pressed,
+                        orbclient::K_I => turn_x = turn,
+                        orbclient::K_K => turn_x = -turn,
+                        orbclient::K_J => turn_y = -turn,
+                        orbclient::K_L => turn_y = turn,
+                        orbclient::K_U => turn_z = turn,
+                        orbclient::K_O => turn_z = -turn,
+
+                        //Shading
+                        orbclient::K_1 if key_event.pressed => scene.shading = None,
+                        orbclient::K_2 if key_event.pressed => scene.shading = Some(ShadingMode::Gouraud),
+                        orbclient::K_3 if key_event.pressed => scene.shading = Some(ShadingMode::Phong),
+                        orbclient::K_4 if key_event.pressed => scene.shading = Some(ShadingMode::BlinnPhong),
+
+                        //Debug
+                        orbclient::K_M if key_event.pressed => {
+                            let mode = match render_context.get_polygon_mode() {
+                                PolygonMode::Fill => PolygonMode::Line,
+                                PolygonMode::Line => PolygonMode::Point,
+                                PolygonMode::Point => PolygonMode::Fill,
+                            };
+                            println!("Polygon mode: {:?}", mode);
+                            render_context.set_polygon_mode(mode);
+                        },
+                        orbclient::K_B if key_event.pressed => show_bounds = !show_bounds,
+                        orbclient::K_R if key_event.pressed => {
+                            let algorithm = match render_context.get_raster_algorithm() {
+                                RasterAlgorithm::Scanline => RasterAlgorithm::HalfSpace,
+                                RasterAlgorithm::HalfSpace => RasterAlgorithm::Scanline,
+                            };
+                            println!("Raster algorithm: {:?}", algorithm);
+                            render_context.set_raster_algorithm(algorithm);
+                        },
+                        orbclient::K_V if key_event.pressed => {
+                            if render_context.get_post_pass_count() == 0 {
+                                render_context.add_post_pass(Outline::new(orbclient::Color { data: 0xFF000000 }));
+                                render_context.add_post_pass(Vignette::new());
+                            } else {
+                                render_context.clear_post_passes();
+                            }
+                            println!("Post passes: {}", render_context.get_post_pass_count());
+                        },
+
+                        //Filtering
+                        orbclient::K_F if key_event.pressed => {
+                            let filter = match render_context.get_sampler().filter {
+                                Filter::Nearest => Filter::Bilinear,
+                                Filter::Bilinear => Filter::Trilinear,
+                                Filter::Trilinear => Filter::Nearest,
+                            };
+                            println!("Filter: {:?}", filter);
+                            render_context.set_sampler(Sampler::with_filter(filter));
+                        },
+
+                        //Camera
+                        orbclient::K_P if key_event.pressed => {
+                            //NOTE(dustin): the orthographic box matches the perspective view at the distance of the origin
+                            camera.projection = match camera.projection {
+                                Projection::Perspective { .. } => {
+                                    let distance = origin.sub_v(&camera.position).length();
+                                    Projection::orthographic(distance * (fov / 2_f32).tan(), aspect_ratio, 0.1_f32, 1000_f32)
+                                },
+                                Projection::Orthographic { .. } => projection,
+                            };
+                        },
+                        orbclient::K_C if key_event.pressed => {
+                            controller_idx = (controller_idx + 1) % 3;
+                            match controller_idx {
+                                0 => fly = FlyController::new(&camera),
+                                1 => orbit = OrbitController::new(&camera, origin),
+                                _ => turntable = TurntableController::new(origin, 4_f32, 1_f32, 0.5_f32),
+                            }
+                        },
                         _ => ()
                     }
                 },
                 EventOption::Quit(_quit_event) => break 'event,
                 _ => (),
             };
+
+            match controller_idx {
+                0 => fly.handle_event(&event),
+                1 => orbit.handle_event(&event),
+                _ => turntable.handle_event(&event),
+            }
         }
 
         let end = Instant::now();
         let delta = end.duration_since(start);
-        let delta_ms = delta.as_secs() as f32 * 1000_f32 + (delta.subsec_nanos() as f32)/1000000 as f32;
+        let delta_ms = delta.as_secs_f32() * 1000_f32;
         start = end;
 
-        let speed = delta_ms / 500_f32;
-
-        if move_forward {
-            trans_z = 2_f32.max(trans_z - speed);
-        }
-        if move_back {
-            trans_z += speed;
-        }
-        if move_left {
-            trans_x += speed;
-        }
-        if move_right {
-            trans_x -= speed;
-        }
-        if move_up {
-            trans_y -= speed;
+        match controller_idx {
+            0 => fly.update(&mut camera, delta_ms),
+            1 => orbit.update(&mut camera, delta_ms),
+            _ => turntable.update(&mut camera, delta_ms),
         }
-        if move_down {
-            trans_y += speed;
+        let speed = delta_ms / 500_f32;
+        for &(axis, turn) in [(Vector4f32::new(1_f32, 0_f32, 0_f32, 0_f32), turn_x),
+                              (Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32), turn_y),
+                              (Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32), turn_z)].iter() {
+            if turn != 0_f32 {
+                model_rotation = model_rotation.mul(&Quaternion::from_axis_angle(&axis, turn * speed)).normalized();
+            }
         }
+        scene.get_node_mut(sphere).transform.rotation = model_rotation;
 
-        if turn_forward {
-            rot_x += speed;
-        }
-        if turn_back {
-            rot_x -= speed;
-        }
-        if turn_left {
-            rot_y -= speed;
-        }
-        if turn_right {
-            rot_y += speed;
-        }
-        if turn_up {
-            rot_z -= speed;
+        time += delta_ms / 1000_f32;
+        let up = Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32);
+        scene.get_node_mut(pivot).transform.rotation = Quaternion::from_axis_angle(&up, time * 0.5_f32);
+        for &monkey in monkeys.iter() {
+            scene.get_node_mut(monkey).transform.rotation = Quaternion::from_axis_angle(&up, time * 2_f32);
         }
-        if turn_down {
-            rot_z += speed;
-        }
-
-        let translation = Matrix4f32::new().init_translation(trans_x, trans_y, trans_z);
-        let rotation = Matrix4f32::new().init_rotation(rot_x, rot_y, rot_z);
-        let transform = &projection.mul(&translation.mul(&rotation));
 
         render_context.clear();
-        render_context.draw_mesh(&mesh, &transform, &texture);
-        render_context.sync();
+        render_context.draw_scene(&scene, &camera);
+        if show_bounds {
+            draw_bounds(&mut render_context, &scene, &camera);
+        }
+        render_context.finish_frame();
+        window.present(render_context.get_output());
 
         frame_cnt += 1_f32;
         counter_duration += delta_ms;
         if counter_duration > 1000_f32 {
-            println!("FPS: {}", frame_cnt / counter_duration * 1000_f32);
+            let cull_stats = render_context.get_cull_stats();
+            println!("FPS: {} drawn: {} culled: {} ({} triangles)", frame_cnt / counter_duration * 1000_f32,
+                cull_stats.objects_drawn, cull_stats.objects_culled, cull_stats.triangles_culled);
             frame_cnt = 0_f32;
             counter_duration = 0_f32;
         }
         thread::yield_now();
     }
 }
+
+fn draw_bounds(render_context: &mut RenderContext, scene: &Scene, camera: &Camera) {
+    let view_projection = camera.get_view_projection();
+    let world = scene.calc_world_matrices();
+    let bounds_color = orbclient::Color { data: 0xFFFFFF00 };
+
+    for (node_idx, node) in scene.nodes.iter().enumerate() {
+        if let Some(mesh) = scene.get_mesh(node) {
+            render_context.draw_aabb(&mesh.aabb, &view_projection.mul(&world[node_idx]), bounds_color, true);
+        }
+    }
+
+    let origin = Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32);
+    for &(axis, color) in [(Vector4f32::new(1_f32, 0_f32, 0_f32, 1_f32), 0xFFFF0000),
+                           (Vector4f32::new(0_f32, 1_f32, 0_f32, 1_f32), 0xFF00FF00),
+                           (Vector4f32::new(0_f32, 0_f32, 1_f32, 1_f32), 0xFF0000FF)].iter() {
+        render_context.draw_line(&origin, &axis, &view_projection, LineColor::Interpolated(orbclient::Color { data: 0xFFFFFFFF }, orbclient::Color { data: color }), false);
+    }
+}
+
+fn load_material(name: &str, image_path: String) -> Material {
+    let image = Image::from_path(image_path).unwrap();
+    let mut texture = BitmapTexture::from_orbimage(&image);
+    texture.generate_mipmaps();
+
+    let mut material = Material::new(name);
+    material.diffuse_texture = Some(texture);
+    material.specular = Vector4f32::new(0.5_f32, 0.5_f32, 0.5_f32, 0_f32);
+    material.specular_exponent = 16_f32;
+    material
+}
@@ -22,9 +22,9 @@ impl IndexedModel {
     pub fn calc_tangents(&mut self) {
         //TODO(dustin): use idiomatic iterators and combinators
         for idx in (0..self.indices.len()).step_by(3) {
-            let i0 = self.indices[idx as usize];
-            let i1 = self.indices[(idx + 1) as usize];
-            let i2 = self.indices[(idx + 2) as usize];
+            let i0 = self.indices[idx];
+            let i1 = self.indices[idx + 1];
+            let i2 = self.indices[idx + 2];
 
             let edge1 = self.positions[i1 as usize].sub_v(&self.positions[i0 as usize]);
             let edge2 = self.positions[i2 as usize].sub_v(&self.positions[i0 as usize]);
@@ -40,11 +40,15 @@ impl IndexedModel {
             let x = f * (delta_v2 * edge1.x - delta_v1 * edge2.x);
             let y = f * (delta_v2 * edge1.y - delta_v1 * edge2.y);
             let z = f * (delta_v2 * edge1.z - delta_v1 * edge2.z);
+            let tangent = Vector4f32::new(x, y, z, 0_f32);
+
+            self.tangents[i0 as usize] = self.tangents[i0 as usize].add_v(&tangent);
+            self.tangents[i1 as usize] = self.tangents[i1 as usize].add_v(&tangent);
+            self.tangents[i2 as usize] = self.tangents[i2 as usize].add_v(&tangent);
         }
 
-        //TODO(dustin): use idiomatic iterators
-        for idx in 0..self.normals.len() {
-            self.tangents[idx as usize] = self.tangents[idx as usize].normalized();
+        for tangent in self.tangents.iter_mut() {
+            *tangent = tangent.normalized();
         }
     }
 
@@ -52,9 +56,9 @@ impl IndexedModel {
         //TODO(dustin): use idiomatic iterators and combinators
         for idx in (0..self.indices.len()).step_by(3) {
 
-            let i0 = self.indices[idx as usize];
-            let i1 = self.indices[(idx + 1) as usize];
-            let i2 = self.indices[(idx + 2) as usize];
+            let i0 = self.indices[idx];
+            let i1 = self.indices[idx + 1];
+            let i2 = self.indices[idx + 2];
 
             let v1 = self.positions[i1 as usize].sub_v(&self.positions[i0 as usize]);
             let v2 = self.positions[i2 as usize].sub_v(&self.positions[i0 as usize]);
@@ -66,9 +70,14 @@ impl IndexedModel {
             self.normals[i2 as usize] = self.normals[i2 as usize].add_v(&normal);
         }
 
-        //TODO(dustin): use idiomatic iterators
-        for idx in 0..self.normals.len() {
-            self.normals[idx as usize] = self.normals[idx as usize].normalized();
+        for normal in self.normals.iter_mut() {
+            *normal = normal.normalized();
         }
     }
 }
+
+impl Default for IndexedModel {
+    fn default() -> IndexedModel {
+        IndexedModel::new()
+    }
+}
@@ -0,0 +1,211 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use model::obj::{OBJError, OBJErrorKind, OBJWarning, Statement};
+use primitive::vector::Vector4f32;
+use raster::RasterState;
+use texture::bitmap::BitmapTexture;
+
+//NOTE(dustin): ambient and specular only matter for lit draws. illum 0 is the plain color, 1 adds ambient and diffuse
+// light and 2 highlights. the bump map is a height map scaled by bump_multiplier, the normal map is in tangent space
+pub struct Material {
+    pub name: String,
+    pub ambient: Vector4f32,
+    pub diffuse: Vector4f32,
+    pub specular: Vector4f32,
+    pub specular_exponent: f32,
+    pub dissolve: f32,
+    pub illum: i32,
+    pub bump_multiplier: f32,
+
+    pub diffuse_map: Option<String>,
+    pub bump_map: Option<String>,
+    pub normal_map: Option<String>,
+    pub alpha_map: Option<String>,
+
+    pub diffuse_texture: Option<BitmapTexture>,
+    pub bump_texture: Option<BitmapTexture>,
+    pub normal_texture: Option<BitmapTexture>,
+    pub alpha_texture: Option<BitmapTexture>,
+
+    //NOTE(dustin): replaces the raster state of the RenderContext for draws with this material, e.g. CullMode::None for double sided
+    pub raster_state: Option<RasterState>
+}
+
+impl Material {
+    pub fn new(_name: &str) -> Material {
+        Material {
+            name: _name.to_string(),
+            ambient: Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32),
+            diffuse: Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32),
+            specular: Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32),
+            specular_exponent: 1_f32,
+            dissolve: 1_f32,
+            illum: 2,
+            bump_multiplier: 1_f32,
+
+            diffuse_map: None,
+            bump_map: None,
+            normal_map: None,
+            alpha_map: None,
+
+            diffuse_texture: None,
+            bump_texture: None,
+            normal_texture: None,
+            alpha_texture: None,
+
+            raster_state: None
+        }
+    }
+}
+
+pub struct MTLLibrary {
+    pub materials: Vec<Material>,
+    pub warnings: Vec<OBJWarning>
+}
+
+impl MTLLibrary {
+    pub fn new() -> MTLLibrary {
+        MTLLibrary {
+            materials: Vec::new(),
+            warnings: Vec::new()
+        }
+    }
+
+    //NOTE(dustin): map paths are relative to the directory of the mtl file
+    pub fn from_path<P: AsRef<Path>>(file_path: P) -> Result<MTLLibrary, OBJError> {
+        let file_path = file_path.as_ref();
+        let file_name = file_path.to_string_lossy();
+        let mut file = File::open(file_path).map_err(|err| OBJError::new(&file_name, 0, 0, OBJErrorKind::Io(err)))?;
+        let mut buffer = String::new();
+        file.read_to_string(&mut buffer).map_err(|err| OBJError::new(&file_name, 0, 0, OBJErrorKind::Io(err)))?;
+
+        MTLLibrary::parse_with_maps(&buffer, &file_name, Some(file_path.parent().unwrap_or(Path::new(""))))
+    }
+
+    //NOTE(dustin): file_name is only used for errors and warnings, the maps are not loaded
+    pub fn parse(source: &str, file_name: &str) -> Result<MTLLibrary, OBJError> {
+        MTLLibrary::parse_with_maps(source, file_name, None)
+    }
+
+    fn parse_with_maps(source: &str, file_name: &str, base_dir: Option<&Path>) -> Result<MTLLibrary, OBJError> {
+        let mut library = MTLLibrary::new();
+
+        for (line_idx, line) in source.lines().enumerate() {
+            let lines = [(line_idx + 1, line.to_string())];
+            let statement = Statement::new(&lines);
+
+            if statement.tokens.is_empty() || statement.tokens[0].2.starts_with('#') {
+                continue;
+            }
+
+            if statement.tokens[0].2 == "newmtl" {
+                statement.expect(file_name, 1)?;
+                library.materials.push(Material::new(&statement.rest()));
+                continue;
+            }
+
+            let material = match library.materials.last_mut() {
+                Some(material) => material,
+                None => return Err(statement.error(file_name, 0, OBJErrorKind::Material(format!("'{}' before newmtl", statement.tokens[0].2)))),
+            };
+
+            let warnings = &mut library.warnings;
+            match statement.tokens[0].2 {
+                "Ka" => material.ambient = MTLLibrary::parse_color(&statement, file_name)?,
+                "Kd" => material.diffuse = MTLLibrary::parse_color(&statement, file_name)?,
+                "Ks" => material.specular = MTLLibrary::parse_color(&statement, file_name)?,
+                "Ns" => material.specular_exponent = MTLLibrary::parse_value(&statement, file_name)?,
+                "d" => material.dissolve = MTLLibrary::parse_value(&statement, file_name)?,
+                "Tr" => material.dissolve = 1_f32 - MTLLibrary::parse_value(&statement, file_name)?,
+                "illum" => {
+                    statement.expect(file_name, 1)?;
+                    let value = statement.tokens[1].2;
+                    material.illum = value.parse().map_err(|_| statement.error(file_name, 1, OBJErrorKind::InvalidNumber(value.to_string())))?;
+                },
+                "map_Kd" => {
+                    let (map, texture) = MTLLibrary::load_map(&statement, file_name, base_dir, warnings)?;
+                    material.diffuse_map = Some(map);
+                    material.diffuse_texture = texture;
+                },
+                "map_Bump" | "map_bump" | "bump" => {
+                    //NOTE(dustin): the value of -bm has to come before the file name, which is the last token
+                    if let Some(token) = statement.tokens.iter().position(|token| token.2 == "-bm") {
+                        if token + 2 >= statement.tokens.len() {
+                            return Err(statement.error(file_name, token, OBJErrorKind::MissingValues { statement: "-bm".to_string(), expected: 1, found: 0 }));
+                        }
+                        material.bump_multiplier = statement.parse_float(file_name, token + 1)?;
+                    }
+                    let (map, texture) = MTLLibrary::load_map(&statement, file_name, base_dir, warnings)?;
+                    material.bump_map = Some(map);
+                    material.bump_texture = texture;
+                },
+                "norm" => {
+                    let (map, texture) = MTLLibrary::load_map(&statement, file_name, base_dir, warnings)?;
+                    material.normal_map = Some(map);
+                    material.normal_texture = texture;
+                },
+                "map_d" => {
+                    let (map, texture) = MTLLibrary::load_map(&statement, file_name, base_dir, warnings)?;
+                    material.alpha_map = Some(map);
+                    material.alpha_texture = texture;
+                },
+                unsupported => {
+                    warnings.push(OBJWarning {
+                        file: file_name.to_string(),
+                        line: statement.line,
+                        message: format!("unsupported statement '{}' ignored", unsupported)
+                    });
+                },
+            }
+        }
+
+        Ok(library)
+    }
+
+    fn parse_value(statement: &Statement, file_name: &str) -> Result<f32, OBJError> {
+        statement.expect(file_name, 1)?;
+        statement.parse_float(file_name, 1)
+    }
+
+    fn parse_color(statement: &Statement, file_name: &str) -> Result<Vector4f32, OBJError> {
+        let r = MTLLibrary::parse_value(statement, file_name)?;
+        //NOTE(dustin): a single value sets all three channels
+        let g = if statement.tokens.len() > 2 { statement.parse_float(file_name, 2)? } else { r };
+        let b = if statement.tokens.len() > 3 { statement.parse_float(file_name, 3)? } else { r };
+
+        Ok(Vector4f32::new(r, g, b, 0_f32))
+    }
+
+    //NOTE(dustin): map options like -bm or -s come before the file name, so the last token is the file. without base_dir
+    // only the name is kept. a missing file is a warning like a missing mtllib, the material is drawn without the map
+    fn load_map(statement: &Statement, file_name: &str, base_dir: Option<&Path>, warnings: &mut Vec<OBJWarning>) -> Result<(String, Option<BitmapTexture>), OBJError> {
+        statement.expect(file_name, 1)?;
+        let token = statement.tokens.len() - 1;
+        let map = statement.tokens[token].2.to_string();
+
+        let map_path = match base_dir {
+            Some(base_dir) => base_dir.join(&map),
+            None => return Ok((map, None)),
+        };
+
+        if !map_path.exists() {
+            warnings.push(OBJWarning {
+                file: file_name.to_string(),
+                line: statement.line,
+                message: format!("texture '{}' not found, the material is drawn without it", map)
+            });
+            return Ok((map, None));
+        }
+
+        let texture = BitmapTexture::from_path(map_path).map_err(|err| statement.error(file_name, token, OBJErrorKind::Material(err)))?;
+        Ok((map, Some(texture)))
+    }
+}
+
+impl Default for MTLLibrary {
+    fn default() -> MTLLibrary {
+        MTLLibrary::new()
+    }
+}
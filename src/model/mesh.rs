@@ -1,25 +1,56 @@
-use model::obj::OBJModel;
+use cull::{Aabb, BoundingSphere};
+use model::indexed::IndexedModel;
+use model::obj::{OBJError, OBJModel, OBJWarning};
+use primitive::vector::Vector4f32;
 use primitive::vertex::Vertex;
 
+//NOTE(dustin): the bounds are in model space, call update_bounds after changing the vertices. warnings are the ones of
+// the obj file the mesh was loaded from
 pub struct Mesh {
     pub vertices: Vec<Vertex>,
-    pub indices: Vec<i32>
+    pub indices: Vec<i32>,
+    pub aabb: Aabb,
+    pub bounding_sphere: BoundingSphere,
+    pub warnings: Vec<OBJWarning>
 }
 
 impl Mesh {
-    pub fn from_path(file_path: String) -> Result<Mesh, String> {
-        let model = try!(OBJModel::new().init_from_path(file_path)).to_indexed_model();
+    pub fn from_path(file_path: String) -> Result<Mesh, OBJError> {
+        let obj_model = OBJModel::from_path(file_path)?;
 
+        let mut mesh = Mesh::from_indexed_model(obj_model.to_indexed_model());
+        mesh.warnings = obj_model.warnings;
+        Ok(mesh)
+    }
+
+    //NOTE(dustin): a model without tex coords or normals gets zero vectors for them
+    pub fn from_indexed_model(model: IndexedModel) -> Mesh {
+        let zero = Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
         let mut vertices: Vec<Vertex> = Vec::new();
         for idx in 0..model.positions.len() {
-            vertices.push(Vertex::new_with_pos_and_texcoords(model.positions[idx as usize], model.tex_coords[idx as usize]));
+            let tex_coords = model.tex_coords.get(idx).cloned().unwrap_or(zero);
+            let normal = model.normals.get(idx).cloned().unwrap_or(zero);
+            vertices.push(Vertex::new(model.positions[idx], tex_coords, normal));
         }
 
-        let mesh = Mesh{
-            vertices: vertices,
-            indices: model.indices
-        };
+        let aabb = Aabb::from_points(vertices.iter().map(|vertex| &vertex.pos));
+        let bounding_sphere = BoundingSphere::from_points(vertices.iter().map(|vertex| &vertex.pos), &aabb);
 
-        Ok(mesh)
+        Mesh{
+            vertices,
+            indices: model.indices,
+            aabb,
+            bounding_sphere,
+            warnings: Vec::new()
+        }
+    }
+
+    pub fn update_bounds(&mut self) {
+        self.aabb = Aabb::from_points(self.vertices.iter().map(|vertex| &vertex.pos));
+        self.bounding_sphere = BoundingSphere::from_points(self.vertices.iter().map(|vertex| &vertex.pos), &self.aabb);
+    }
+
+    pub fn get_triangle_count(&self) -> usize {
+        self.indices.len() / 3
     }
 }
@@ -1,3 +1,5 @@
 pub mod indexed;
+pub mod material;
 pub mod mesh;
+pub mod multimesh;
 pub mod obj;
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use model::material::{Material, MTLLibrary};
+use model::mesh::Mesh;
+use model::obj::{OBJError, OBJModel, OBJWarning};
+
+pub struct SubMesh {
+    pub object: String,
+    pub group: String,
+    pub smoothing_group: i32,
+    pub material: Option<usize>,
+    pub mesh: Mesh
+}
+
+//NOTE(dustin): an obj file split into one sub mesh per material/object/group run
+pub struct MultiMesh {
+    pub sub_meshes: Vec<SubMesh>,
+    pub materials: Vec<Material>,
+    pub warnings: Vec<OBJWarning>
+}
+
+impl MultiMesh {
+    pub fn from_path(file_path: String) -> Result<MultiMesh, OBJError> {
+        let model = OBJModel::from_path(&file_path)?;
+        let base_dir = Path::new(&file_path).parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let mut materials: Vec<Material> = Vec::new();
+        let mut warnings = model.warnings.clone();
+        for &(line, ref lib) in model.material_libs.iter() {
+            let lib_path = base_dir.join(lib);
+
+            //NOTE(dustin): a missing library is not fatal, the faces fall back to the default material
+            if !lib_path.exists() {
+                warnings.push(OBJWarning {
+                    file: file_path.clone(),
+                    line,
+                    message: format!("material library '{}' not found, its faces are drawn with the default material", lib)
+                });
+                continue;
+            }
+
+            let library = MTLLibrary::from_path(lib_path)?;
+            materials.extend(library.materials);
+            warnings.extend(library.warnings);
+        }
+
+        let mut sub_meshes = Vec::new();
+        for group in model.groups.iter() {
+            let material = match group.material {
+                Some(ref name) => materials.iter().position(|material| &material.name == name),
+                None => None,
+            };
+
+            sub_meshes.push(SubMesh {
+                object: group.object.clone(),
+                group: group.group.clone(),
+                smoothing_group: group.smoothing_group,
+                material,
+                mesh: Mesh::from_indexed_model(model.to_indexed_model_range(group.start, group.end))
+            });
+        }
+
+        Ok(MultiMesh {
+            sub_meshes,
+            materials,
+            warnings
+        })
+    }
+
+    pub fn get_material(&self, sub_mesh: &SubMesh) -> Option<&Material> {
+        sub_mesh.material.map(|idx| &self.materials[idx])
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
 
 use model::indexed::IndexedModel;
 use primitive::vector::Vector4f32;
@@ -13,21 +16,177 @@ pub struct OBJIndex {
 }
 
 impl OBJIndex {
+    //NOTE(dustin): -1 marks a missing tex coord or normal reference
     pub fn new() -> OBJIndex {
         OBJIndex {
             vertex_index: 0,
-            tex_coord_index: 0,
-            normal_index: 0
+            tex_coord_index: -1,
+            normal_index: -1
         }
     }
 }
 
+impl Default for OBJIndex {
+    fn default() -> OBJIndex {
+        OBJIndex::new()
+    }
+}
+
+//NOTE(dustin): faces between usemtl, o, g and s statements, start and end index into OBJModel::indices
+#[derive(Debug, Clone)]
+pub struct OBJGroup {
+    pub object: String,
+    pub group: String,
+    pub smoothing_group: i32,
+    pub material: Option<String>,
+    pub start: usize,
+    pub end: usize
+}
+
+impl OBJGroup {
+    pub fn new(start: usize) -> OBJGroup {
+        OBJGroup {
+            object: String::new(),
+            group: String::new(),
+            smoothing_group: 0,
+            material: None,
+            start,
+            end: start
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum OBJErrorKind {
+    Io(io::Error),
+    MissingValues { statement: String, expected: usize, found: usize },
+    InvalidNumber(String),
+    InvalidIndex(String),
+    IndexOutOfRange { index: i64, count: usize },
+    Material(String)
+}
+
+//NOTE(dustin): line and column are 1 based, 0 if the error is not tied to a position in the file
+#[derive(Debug)]
+pub struct OBJError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub kind: OBJErrorKind
+}
+
+impl OBJError {
+    pub fn new(file: &str, line: usize, column: usize, kind: OBJErrorKind) -> OBJError {
+        OBJError {
+            file: file.to_string(),
+            line,
+            column,
+            kind
+        }
+    }
+}
+
+impl fmt::Display for OBJErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OBJErrorKind::Io(ref err) => write!(f, "io error: {}", err),
+            OBJErrorKind::MissingValues { ref statement, expected, found } => write!(f, "'{}' expects at least {} values, found {}", statement, expected, found),
+            OBJErrorKind::InvalidNumber(ref token) => write!(f, "invalid number '{}'", token),
+            OBJErrorKind::InvalidIndex(ref token) => write!(f, "invalid face index '{}'", token),
+            OBJErrorKind::IndexOutOfRange { index, count } => write!(f, "index {} out of range, {} elements defined so far", index, count),
+            OBJErrorKind::Material(ref message) => write!(f, "material error: {}", message),
+        }
+    }
+}
+
+impl fmt::Display for OBJError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file, self.line, self.column, self.kind)
+    }
+}
+
+impl Error for OBJError {}
+
+#[derive(Debug, Clone)]
+pub struct OBJWarning {
+    pub file: String,
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for OBJWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: warning: {}", self.file, self.line, self.message)
+    }
+}
+
+//NOTE(dustin): a statement with its tokens and their 1 based lines and columns. a statement continued with a backslash
+// spans several lines, line is the first of them. also used for mtl files
+pub struct Statement<'a> {
+    pub line: usize,
+    pub tokens: Vec<(usize, usize, &'a str)>
+}
+
+impl<'a> Statement<'a> {
+    pub fn new(lines: &'a [(usize, String)]) -> Statement<'a> {
+        let mut tokens = Vec::new();
+
+        for &(line, ref source) in lines.iter() {
+            let mut start: Option<usize> = None;
+            for (idx, character) in source.char_indices() {
+                match (character.is_whitespace(), start) {
+                    (true, Some(token_start)) => {
+                        tokens.push((line, token_start + 1, &source[token_start..idx]));
+                        start = None;
+                    },
+                    (false, None) => start = Some(idx),
+                    _ => (),
+                }
+            }
+            if let Some(token_start) = start {
+                tokens.push((line, token_start + 1, &source[token_start..]));
+            }
+        }
+
+        Statement {
+            line: lines[0].0,
+            tokens
+        }
+    }
+
+    pub fn error(&self, file: &str, token: usize, kind: OBJErrorKind) -> OBJError {
+        let (line, column) = self.tokens.get(token).map(|token| (token.0, token.1)).unwrap_or((self.line, 0));
+        OBJError::new(file, line, column, kind)
+    }
+
+    pub fn expect(&self, file: &str, count: usize) -> Result<(), OBJError> {
+        if self.tokens.len() - 1 < count {
+            return Err(self.error(file, 0, OBJErrorKind::MissingValues { statement: self.tokens[0].2.to_string(), expected: count, found: self.tokens.len() - 1 }));
+        }
+
+        Ok(())
+    }
+
+    pub fn parse_float(&self, file: &str, token: usize) -> Result<f32, OBJError> {
+        let value = self.tokens[token].2;
+        value.parse().map_err(|_| self.error(file, token, OBJErrorKind::InvalidNumber(value.to_string())))
+    }
+
+    pub fn rest(&self) -> String {
+        self.tokens[1..].iter().map(|token| token.2).collect::<Vec<&str>>().join(" ")
+    }
+}
+
 pub struct OBJModel {
     pub positions: Vec<Vector4f32>,
     pub tex_coords: Vec<Vector4f32>,
     pub indices: Vec<OBJIndex>,
     pub tangents: Vec<Vector4f32>,
     pub normals: Vec<Vector4f32>,
+    //NOTE(dustin): the line of the mtllib statement and the file name
+    pub material_libs: Vec<(usize, String)>,
+    pub groups: Vec<OBJGroup>,
+    pub warnings: Vec<OBJWarning>,
     pub has_tex_coords: bool,
     pub has_normals: bool
 }
@@ -40,106 +199,193 @@ impl OBJModel {
             indices: Vec::new(),
             tangents: Vec::new(),
             normals: Vec::new(),
+            material_libs: Vec::new(),
+            groups: Vec::new(),
+            warnings: Vec::new(),
             has_tex_coords: false,
             has_normals: false
         }
     }
 
-    pub fn init_from_path(&mut self, file_path: String) -> Result<OBJModel, String> {
-        let mut positions = Vec::new();
-        let mut tex_coords = Vec::new();
-        let mut indices = Vec::new();
-        let mut tangents = Vec::new();
-        let mut normals = Vec::new();
-        // let mut has_tex_coords = false;
-        // let mut has_normals = false;
+    pub fn from_path<P: AsRef<Path>>(file_path: P) -> Result<OBJModel, OBJError> {
+        let file_name = file_path.as_ref().to_string_lossy();
+        let file = File::open(file_path.as_ref()).map_err(|err| OBJError::new(&file_name, 0, 0, OBJErrorKind::Io(err)))?;
+        OBJModel::from_reader(file, &file_name)
+    }
 
-        let mut file = try!(File::open(&file_path).map_err(|err| format!("failed to open obj file: {}", err)));
-        let mut buffer = String::new();
-        try!(file.read_to_string(&mut buffer).map_err(|err| format!("failed to read obj file: {}", err)));
+    //NOTE(dustin): file_name is only used for errors and warnings
+    pub fn from_reader<R: Read>(reader: R, file_name: &str) -> Result<OBJModel, OBJError> {
+        let mut model = OBJModel::new();
+        let mut current_group = OBJGroup::new(0);
+
+        let mut reader = BufReader::new(reader);
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        let mut line_number = 0;
+
+        loop {
+            //NOTE(dustin): a trailing backslash continues the statement on the next line, the lines are kept apart so
+            // errors point at the line a token is on
+            lines.clear();
+            loop {
+                let mut line = String::new();
+                let read = reader.read_line(&mut line).map_err(|err| OBJError::new(file_name, line_number + 1, 0, OBJErrorKind::Io(err)))?;
+                if read == 0 {
+                    break;
+                }
+                line_number += 1;
 
-        for line in buffer.lines() {
+                let line = line.trim_end_matches(['\n', '\r']);
+                let is_continued = line.ends_with('\\');
+                lines.push((line_number, if is_continued { &line[..line.len() - 1] } else { line }.to_string()));
+                if !is_continued {
+                    break;
+                }
+            }
 
-            let tokens: Vec<&str> = line.split(" ").collect();
-            //TODO(dustin): remove empty strings?
+            if lines.is_empty() {
+                break;
+            }
 
-            if tokens.len() == 0 || tokens[0] == "#" {
+            let statement = Statement::new(&lines);
+            if statement.tokens.is_empty() || statement.tokens[0].2.starts_with('#') {
                 continue;
+            }
 
-            } else if tokens[0] == "v" {
-
-                let x: f32 = try!(tokens[1].parse().map_err(|err| format!("failed to parse token: {}", err)));
-                let y: f32 = try!(tokens[2].parse().map_err(|err| format!("failed to parse token: {}", err)));
-                let z: f32 = try!(tokens[3].parse().map_err(|err| format!("failed to parse token: {}", err)));
-
-                positions.push( Vector4f32::new(x, y, z, 1_f32));
-
-            } else if tokens[0] == "vt" {
-
-                let x: f32 = try!(tokens[1].parse().map_err(|err| format!("failed to parse token: {}", err)));
-                let y: f32 = try!(tokens[2].parse().map_err(|err| format!("failed to parse token: {}", err)));
-
-                tex_coords.push( Vector4f32::new(x, 1_f32 - y, 0_f32, 0_f32));
-
-            } else if tokens[0] == "vn" {
+            match statement.tokens[0].2 {
+                "v" => {
+                    statement.expect(file_name, 3)?;
+                    let x = statement.parse_float(file_name, 1)?;
+                    let y = statement.parse_float(file_name, 2)?;
+                    let z = statement.parse_float(file_name, 3)?;
+
+                    model.positions.push(Vector4f32::new(x, y, z, 1_f32));
+                },
+                "vt" => {
+                    statement.expect(file_name, 1)?;
+                    let x = statement.parse_float(file_name, 1)?;
+                    let y = if statement.tokens.len() > 2 { statement.parse_float(file_name, 2)? } else { 0_f32 };
+
+                    model.tex_coords.push(Vector4f32::new(x, 1_f32 - y, 0_f32, 0_f32));
+                },
+                "vn" => {
+                    statement.expect(file_name, 3)?;
+                    let x = statement.parse_float(file_name, 1)?;
+                    let y = statement.parse_float(file_name, 2)?;
+                    let z = statement.parse_float(file_name, 3)?;
+
+                    model.normals.push(Vector4f32::new(x, y, z, 0_f32));
+                },
+                "f" => {
+                    statement.expect(file_name, 3)?;
+
+                    let mut face = Vec::with_capacity(statement.tokens.len() - 1);
+                    for token in 1..statement.tokens.len() {
+                        face.push(model.parse_obj_index(&statement, token, file_name)?);
+                    }
+
+                    //TODO(dustin): use idiomatic iterators
+                    for idx in 0..(face.len() - 2) {
+                        model.indices.push(face[0]);
+                        model.indices.push(face[1 + idx]);
+                        model.indices.push(face[2 + idx]);
+                    }
+                },
+                "mtllib" => {
+                    statement.expect(file_name, 1)?;
+                    for token in statement.tokens[1..].iter() {
+                        model.material_libs.push((token.0, token.2.to_string()));
+                    }
+                },
+                "usemtl" | "o" | "g" | "s" => {
+                    let mut next_group = current_group.clone();
+                    next_group.start = model.indices.len();
+                    next_group.end = model.indices.len();
+
+                    let name = statement.rest();
+                    match statement.tokens[0].2 {
+                        "usemtl" => next_group.material = Some(name),
+                        "o" => next_group.object = name,
+                        "g" => next_group.group = name,
+                        _ => {
+                            statement.expect(file_name, 1)?;
+                            next_group.smoothing_group = if name == "off" { 0 } else {
+                                name.parse().map_err(|_| statement.error(file_name, 1, OBJErrorKind::InvalidNumber(name.clone())))?
+                            };
+                        },
+                    }
+
+                    current_group.end = model.indices.len();
+                    if current_group.end > current_group.start {
+                        model.groups.push(current_group);
+                    }
+                    current_group = next_group;
+                },
+                unsupported => {
+                    model.warnings.push(OBJWarning {
+                        file: file_name.to_string(),
+                        line: statement.line,
+                        message: format!("unsupported statement '{}' ignored", unsupported)
+                    });
+                },
+            }
+        }
 
-                let x: f32 = try!(tokens[1].parse().map_err(|err| format!("failed to parse token: {}", err)));
-                let y: f32 = try!(tokens[2].parse().map_err(|err| format!("failed to parse token: {}", err)));
-                let z: f32 = try!(tokens[3].parse().map_err(|err| format!("failed to parse token: {}", err)));
+        current_group.end = model.indices.len();
+        if current_group.end > current_group.start {
+            model.groups.push(current_group);
+        }
 
-                normals.push( Vector4f32::new(x, y, z, 0_f32));
+        Ok(model)
+    }
 
-            } else if tokens[0] == "f" {
-                //TODO(dustin): use idiomatic iterators
-                for idx in 0..(tokens.len() - 3) {
+    //NOTE(dustin): obj indices are 1 based, negative ones are relative to the elements defined so far
+    fn resolve_index(statement: &Statement, token: usize, file_name: &str, value: &str, count: usize) -> Result<i32, OBJError> {
+        let index: i64 = value.parse().map_err(|_| statement.error(file_name, token, OBJErrorKind::InvalidIndex(statement.tokens[token].2.to_string())))?;
 
-                    indices.push(try!(self.parse_obj_index(tokens[1 as usize])));
-                    indices.push(try!(self.parse_obj_index(tokens[(2 + idx) as usize])));
-                    indices.push(try!(self.parse_obj_index(tokens[(3 + idx) as usize])));
-                }
-            }
+        let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+        if index == 0 || resolved < 0 || resolved >= count as i64 {
+            return Err(statement.error(file_name, token, OBJErrorKind::IndexOutOfRange { index, count }));
         }
 
-        let model = OBJModel {
-            positions: positions,
-            tex_coords: tex_coords,
-            indices: indices,
-            tangents: tangents,
-            normals: normals,
-            has_tex_coords: self.has_tex_coords,
-            has_normals: self.has_normals
-        };
-
-        Ok(model)
+        Ok(resolved as i32)
     }
 
-    fn parse_obj_index(&mut self, token: &str) -> Result<OBJIndex, String> {
-        let values: Vec<&str> = token.split("/").collect();
+    fn parse_obj_index(&mut self, statement: &Statement, token: usize, file_name: &str) -> Result<OBJIndex, OBJError> {
+        let values: Vec<&str> = statement.tokens[token].2.split('/').collect();
 
         let mut result = OBJIndex::new();
-        let vidx: i32 = try!(values[0 as usize].to_string().parse().map_err(|err| format!("failed to parse obj index vertex: {}", err)));
-        result.vertex_index = vidx - 1_i32;
-
-        if values.len() > 1 {
+        result.vertex_index = OBJModel::resolve_index(statement, token, file_name, values[0], self.positions.len())?;
 
-            if !values[1].is_empty() {
-                self.has_tex_coords = true;
-                let tcidx: i32 = try!(values[1 as usize].to_string().parse().map_err(|err| format!("failed to parse obj index tex coord: {}", err)));
-                result.tex_coord_index = tcidx - 1_i32;
-            }
+        if values.len() > 1 && !values[1].is_empty() {
+            self.has_tex_coords = true;
+            result.tex_coord_index = OBJModel::resolve_index(statement, token, file_name, values[1], self.tex_coords.len())?;
+        }
 
-            if values.len() > 2  {
-                self.has_normals = true;
-                let nidx: i32 = try!(values[2 as usize].to_string().parse().map_err(|err| format!("failed to parse obj index normal: {}", err)));
-                result.normal_index = nidx - 1_i32;
-            }
+        if values.len() > 2 && !values[2].is_empty() {
+            self.has_normals = true;
+            result.normal_index = OBJModel::resolve_index(statement, token, file_name, values[2], self.normals.len())?;
+        }
 
+        if values.len() > 3 {
+            return Err(statement.error(file_name, token, OBJErrorKind::InvalidIndex(statement.tokens[token].2.to_string())));
         }
 
         Ok(result)
     }
 
+    fn get_or(values: &[Vector4f32], index: i32, default: Vector4f32) -> Vector4f32 {
+        if index < 0 {
+            return default;
+        }
+
+        values.get(index as usize).cloned().unwrap_or(default)
+    }
+
     pub fn to_indexed_model(&self) -> IndexedModel {
+        self.to_indexed_model_range(0, self.indices.len())
+    }
+
+    pub fn to_indexed_model_range(&self, start: usize, end: usize) -> IndexedModel {
         let mut result = IndexedModel::new();
         let mut normal_model = IndexedModel::new();
 
@@ -149,25 +395,15 @@ impl OBJModel {
         let mut index_map: HashMap<i32, i32> = HashMap::new();
 
         //TODO(dustin): use idiomatic iterators
-        for idx in 0..self.indices.len() {
-
-            let current_index = self.indices[idx as usize];  //NOTE(dustin): maybe as ref not copy see struct
+        for idx in start..end {
 
-            let current_position = self.positions[current_index.vertex_index as usize]; //NOTE(dustin): maybe as ref not copy see struct
-            let current_tex_coord: Vector4f32;
-            let current_normal: Vector4f32;
+            let current_index = self.indices[idx];  //NOTE(dustin): maybe as ref not copy see struct
 
-            if self.has_tex_coords {
-                current_tex_coord = self.tex_coords[current_index.tex_coord_index as usize]; //NOTE(dustin): maybe as ref not copy see struct
-            } else {
-                current_tex_coord = Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
-            }
-
-            if self.has_normals {
-                current_normal = self.normals[current_index.normal_index as usize]; //NOTE(dustin): maybe as ref not copy see struct
-            } else {
-                current_normal = Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
-            }
+            //NOTE(dustin): missing or out of range references fall back to zero instead of panicking
+            let zero = Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32);
+            let current_position = OBJModel::get_or(&self.positions, current_index.vertex_index, zero);
+            let current_tex_coord = OBJModel::get_or(&self.tex_coords, current_index.tex_coord_index, zero);
+            let current_normal = OBJModel::get_or(&self.normals, current_index.normal_index, zero);
 
             //TODO(dustin): fix this crappy unidiomatic code :(
             let mut model_vertex_index = {
@@ -235,3 +471,9 @@ impl OBJModel {
         result
     }
 }
+
+impl Default for OBJModel {
+    fn default() -> OBJModel {
+        OBJModel::new()
+    }
+}
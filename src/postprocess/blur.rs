@@ -0,0 +1,76 @@
+use orbclient;
+
+use postprocess::{self, PostPass};
+use target::framebuffer::Framebuffer;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BlurKernel {
+    Box,
+    //NOTE(dustin): the standard deviation in pixels, a sigma that is not above 0 keeps the image as it is
+    Gaussian(f32)
+}
+
+//NOTE(dustin): a separable blur over radius pixels in every direction, first along the rows and then along the columns.
+// pixels outside of the framebuffer are the closest edge pixel
+pub struct Blur {
+    pub kernel: BlurKernel,
+    pub radius: i32
+}
+
+impl Blur {
+    pub fn new(_kernel: BlurKernel, _radius: i32) -> Blur {
+        Blur {
+            kernel: _kernel,
+            radius: _radius
+        }
+    }
+
+    //NOTE(dustin): 2 * radius + 1 weights that add up to 1
+    pub fn calc_weights(&self) -> Vec<f32> {
+        let radius = self.radius.max(0);
+        let weights: Vec<f32> = (-radius..radius + 1).map(|offset| match self.kernel {
+            BlurKernel::Box => 1_f32,
+            BlurKernel::Gaussian(sigma) if sigma > 0_f32 && sigma * sigma > 0_f32 => (-(offset * offset) as f32 / (2_f32 * sigma * sigma)).exp(),
+            BlurKernel::Gaussian(_) => if offset == 0 { 1_f32 } else { 0_f32 },
+        }).collect();
+
+        let sum: f32 = weights.iter().sum();
+        weights.iter().map(|weight| weight / sum).collect()
+    }
+}
+
+impl PostPass for Blur {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        let weights = self.calc_weights();
+        let radius = self.radius.max(0);
+        let width = input.width;
+        let height = input.height;
+
+        let mut rows = vec![[0_f32; 4]; (width * height) as usize];
+        for idx_y in 0..height {
+            for idx_x in 0..width {
+                let sum = &mut rows[(idx_x + idx_y * width) as usize];
+                for (offset, weight) in (-radius..radius + 1).zip(weights.iter()) {
+                    let argb = postprocess::fetch(input, idx_x + offset, idx_y);
+                    for channel in 0..4 {
+                        sum[channel] += argb[channel] * weight;
+                    }
+                }
+            }
+        }
+
+        for idx_y in 0..height {
+            for idx_x in 0..width {
+                let mut sum = [0_f32; 4];
+                for (offset, weight) in (-radius..radius + 1).zip(weights.iter()) {
+                    let src_y = (idx_y + offset).max(0).min(height - 1);
+                    let argb = rows[(idx_x + src_y * width) as usize];
+                    for channel in 0..4 {
+                        sum[channel] += argb[channel] * weight;
+                    }
+                }
+                output[(idx_x + idx_y * width) as usize] = postprocess::from_argb(sum);
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use orbclient;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use postprocess::{self, PostPass};
+use target::framebuffer::Framebuffer;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TransferCurve {
+    //NOTE(dustin): out = in ^ (1 / gamma)
+    Gamma(f32),
+    LinearToSrgb,
+    SrgbToLinear
+}
+
+impl TransferCurve {
+    pub fn apply(&self, value: f32) -> f32 {
+        match *self {
+            TransferCurve::Gamma(gamma) => value.powf(1_f32 / gamma),
+            TransferCurve::LinearToSrgb if value <= 0.0031308_f32 => value * 12.92_f32,
+            TransferCurve::LinearToSrgb => 1.055_f32 * value.powf(1_f32 / 2.4_f32) - 0.055_f32,
+            TransferCurve::SrgbToLinear if value <= 0.04045_f32 => value / 12.92_f32,
+            TransferCurve::SrgbToLinear => ((value + 0.055_f32) / 1.055_f32).powf(2.4_f32),
+        }
+    }
+}
+
+//NOTE(dustin): applies the curve to the color channels, alpha is kept
+pub struct GammaCorrection {
+    pub curve: TransferCurve
+}
+
+impl GammaCorrection {
+    pub fn new(_curve: TransferCurve) -> GammaCorrection {
+        GammaCorrection { curve: _curve }
+    }
+}
+
+impl PostPass for GammaCorrection {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        //NOTE(dustin): there are only 256 values per channel, so the curve is evaluated once per value and not per pixel
+        let mut table = [0_f32; 256];
+        for (idx, value) in table.iter_mut().enumerate() {
+            *value = self.curve.apply(idx as f32 / 255_f32);
+        }
+
+        let lookup = |value: f32| table[(value * 255_f32 + 0.5_f32) as usize];
+        postprocess::map_pixels(input, output, |_, _, argb| [argb[0], lookup(argb[1]), lookup(argb[2]), lookup(argb[3])]);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ToneMapOperator {
+    //NOTE(dustin): x / (1 + x)
+    Reinhard,
+    //NOTE(dustin): the curve fit of the ACES filmic curve by Krzysztof Narkowicz, clamped since the fit overshoots 1 a bit
+    Aces
+}
+
+impl ToneMapOperator {
+    pub fn apply(&self, value: f32) -> f32 {
+        match *self {
+            ToneMapOperator::Reinhard => value / (1_f32 + value),
+            ToneMapOperator::Aces => ((value * (2.51_f32 * value + 0.03_f32)) / (value * (2.43_f32 * value + 0.59_f32) + 0.14_f32)).min(1_f32),
+        }
+    }
+}
+
+//NOTE(dustin): the colors are scaled by exposure and mapped back into 0..1 by the operator. the framebuffer has 8 bits per
+// channel, so exposure is what brings values above 1 into the curve
+pub struct ToneMapping {
+    pub operator: ToneMapOperator,
+    pub exposure: f32
+}
+
+impl ToneMapping {
+    pub fn new(_operator: ToneMapOperator) -> ToneMapping {
+        ToneMapping {
+            operator: _operator,
+            exposure: 1_f32
+        }
+    }
+
+    pub fn init_exposure(mut self, exposure: f32) -> ToneMapping {
+        self.exposure = exposure;
+        self
+    }
+}
+
+impl PostPass for ToneMapping {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        let map = |value: f32| self.operator.apply(value * self.exposure);
+        postprocess::map_pixels(input, output, |_, _, argb| [argb[0], map(argb[1]), map(argb[2]), map(argb[3])]);
+    }
+}
+
+//NOTE(dustin): darkens towards the corners. the distance from the center is 0 in the center and 1 in the corners, pixels
+// up to radius are unchanged and at radius + softness darkened by strength
+pub struct Vignette {
+    pub strength: f32,
+    pub radius: f32,
+    pub softness: f32
+}
+
+impl Vignette {
+    pub fn new() -> Vignette {
+        Vignette {
+            strength: 0.5_f32,
+            radius: 0.5_f32,
+            softness: 0.5_f32
+        }
+    }
+
+    pub fn calc_factor(&self, dist: f32) -> f32 {
+        let lerp_factor = ((dist - self.radius) / self.softness.max(1e-6_f32)).clamp(0_f32, 1_f32);
+        let smooth_factor = lerp_factor * lerp_factor * (3_f32 - 2_f32 * lerp_factor);
+        1_f32 - self.strength * smooth_factor
+    }
+}
+
+impl Default for Vignette {
+    fn default() -> Vignette {
+        Vignette::new()
+    }
+}
+
+impl PostPass for Vignette {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        let center_x = (input.width - 1) as f32 / 2_f32;
+        let center_y = (input.height - 1) as f32 / 2_f32;
+        let max_dist = (center_x * center_x + center_y * center_y).sqrt().max(1_f32);
+
+        postprocess::map_pixels(input, output, |x, y, argb| {
+            let dist_x = x as f32 - center_x;
+            let dist_y = y as f32 - center_y;
+            let factor = self.calc_factor((dist_x * dist_x + dist_y * dist_y).sqrt() / max_dist);
+            [argb[0], argb[1] * factor, argb[2] * factor, argb[3] * factor]
+        });
+    }
+}
+
+//NOTE(dustin): color grading with a 3D lookup table of size^3 rgb entries, red changes fastest like in .cube files. colors
+// between the entries are interpolated trilinear
+pub struct ColorLut {
+    pub size: usize,
+    pub data: Vec<[f32; 3]>
+}
+
+impl ColorLut {
+    //NOTE(dustin): the table that maps every color onto itself
+    pub fn identity(size: usize) -> ColorLut {
+        ColorLut::from_fn(size, |rgb| rgb)
+    }
+
+    pub fn from_fn<F: Fn([f32; 3]) -> [f32; 3]>(size: usize, map: F) -> ColorLut {
+        assert!(size >= 2, "a lut needs at least 2 entries per channel");
+        let scale = (size - 1) as f32;
+
+        let mut data = Vec::with_capacity(size * size * size);
+        for idx_b in 0..size {
+            for idx_g in 0..size {
+                for idx_r in 0..size {
+                    data.push(map([idx_r as f32 / scale, idx_g as f32 / scale, idx_b as f32 / scale]));
+                }
+            }
+        }
+
+        ColorLut { size, data }
+    }
+
+    pub fn from_path<P: AsRef<Path>>(file_path: P) -> Result<ColorLut, String> {
+        let file_path = file_path.as_ref();
+        let mut file = File::open(file_path).map_err(|err| format!("failed to open cube file {:?}: {}", file_path, err))?;
+        let mut buffer = String::new();
+        file.read_to_string(&mut buffer).map_err(|err| format!("failed to read cube file {:?}: {}", file_path, err))?;
+
+        ColorLut::parse_cube(&buffer)
+    }
+
+    //NOTE(dustin): the .cube format of Adobe, LUT_3D_SIZE and one line of rgb per entry. TITLE and the domain keywords
+    // are skipped, the domain is always 0..1
+    pub fn parse_cube(source: &str) -> Result<ColorLut, String> {
+        let mut size = None;
+        let mut data = Vec::new();
+
+        for line in source.lines() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+
+            if tokens.is_empty() || tokens[0].starts_with("#") {
+                continue;
+            }
+
+            match tokens[0] {
+                "LUT_3D_SIZE" => size = Some(tokens.get(1).ok_or("missing value for LUT_3D_SIZE".to_string())
+                    .and_then(|token| token.parse::<usize>().map_err(|err| format!("failed to parse LUT_3D_SIZE: {}", err)))?),
+                "LUT_1D_SIZE" => return Err("1D luts are not supported".to_string()),
+                "TITLE" | "DOMAIN_MIN" | "DOMAIN_MAX" => (),
+                _ => {
+                    if tokens.len() != 3 {
+                        return Err(format!("expected 3 values per entry: {}", line));
+                    }
+
+                    let mut rgb = [0_f32; 3];
+                    for (value, token) in rgb.iter_mut().zip(tokens.iter()) {
+                        *value = token.parse().map_err(|err| format!("failed to parse entry {}: {}", line, err))?;
+                    }
+                    data.push(rgb);
+                },
+            }
+        }
+
+        let size = size.ok_or("missing LUT_3D_SIZE".to_string())?;
+        let entry_count = size.checked_mul(size).and_then(|count| count.checked_mul(size)).ok_or(format!("LUT_3D_SIZE {} is too large", size))?;
+        if size < 2 || data.len() != entry_count {
+            return Err(format!("expected {} entries for size {} but found {}", entry_count, size, data.len()));
+        }
+
+        Ok(ColorLut { size, data })
+    }
+
+    pub fn lookup(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let scale = (self.size - 1) as f32;
+        let mut idx = [0_usize; 3];
+        let mut frac = [0_f32; 3];
+        for channel in 0..3 {
+            let pos = rgb[channel].clamp(0_f32, 1_f32) * scale;
+            idx[channel] = (pos.floor() as usize).min(self.size - 2);
+            frac[channel] = pos - idx[channel] as f32;
+        }
+
+        let entry = |r: usize, g: usize, b: usize| self.data[(idx[0] + r) + (idx[1] + g) * self.size + (idx[2] + b) * self.size * self.size];
+        let lerp = |a: [f32; 3], b: [f32; 3], lerp_amt: f32| [
+            (b[0] - a[0]) * lerp_amt + a[0],
+            (b[1] - a[1]) * lerp_amt + a[1],
+            (b[2] - a[2]) * lerp_amt + a[2]
+        ];
+
+        let front = lerp(lerp(entry(0, 0, 0), entry(1, 0, 0), frac[0]), lerp(entry(0, 1, 0), entry(1, 1, 0), frac[0]), frac[1]);
+        let back = lerp(lerp(entry(0, 0, 1), entry(1, 0, 1), frac[0]), lerp(entry(0, 1, 1), entry(1, 1, 1), frac[0]), frac[1]);
+        lerp(front, back, frac[2])
+    }
+}
+
+impl PostPass for ColorLut {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        postprocess::map_pixels(input, output, |_, _, argb| {
+            let rgb = self.lookup([argb[1], argb[2], argb[3]]);
+            [argb[0], rgb[0], rgb[1], rgb[2]]
+        });
+    }
+}
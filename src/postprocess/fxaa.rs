@@ -0,0 +1,77 @@
+use orbclient;
+
+use postprocess::{self, PostPass};
+use target::framebuffer::Framebuffer;
+
+//NOTE(dustin): fast approximate anti aliasing after Timothy Lottes. finds edges by the luma contrast of a pixel and its
+// neighbours and blends along the edge direction, smooths edges of any source but also blurs thin details a bit
+pub struct Fxaa {
+    //NOTE(dustin): pixels whose luma range is below edge_threshold * max luma or edge_threshold_min are no edge
+    pub edge_threshold: f32,
+    pub edge_threshold_min: f32,
+    //NOTE(dustin): how far along the edge in pixels the blend reaches
+    pub span_max: f32
+}
+
+impl Fxaa {
+    pub fn new() -> Fxaa {
+        Fxaa {
+            edge_threshold: 0.125_f32,
+            edge_threshold_min: 0.0312_f32,
+            span_max: 8_f32
+        }
+    }
+}
+
+impl Default for Fxaa {
+    fn default() -> Fxaa {
+        Fxaa::new()
+    }
+}
+
+const REDUCE_MUL: f32 = 1_f32 / 8_f32;
+const REDUCE_MIN: f32 = 1_f32 / 128_f32;
+
+impl PostPass for Fxaa {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        for idx_y in 0..input.height {
+            for idx_x in 0..input.width {
+                let data_idx = (idx_x + idx_y * input.width) as usize;
+                let luma_at = |offset_x: i32, offset_y: i32| postprocess::calc_luma(&postprocess::fetch(input, idx_x + offset_x, idx_y + offset_y));
+
+                let color = postprocess::fetch(input, idx_x, idx_y);
+                let luma = postprocess::calc_luma(&color);
+                let luma_nw = luma_at(-1, -1);
+                let luma_ne = luma_at(1, -1);
+                let luma_sw = luma_at(-1, 1);
+                let luma_se = luma_at(1, 1);
+
+                let luma_min = luma.min(luma_nw).min(luma_ne).min(luma_sw).min(luma_se);
+                let luma_max = luma.max(luma_nw).max(luma_ne).max(luma_sw).max(luma_se);
+                if luma_max - luma_min < self.edge_threshold_min.max(luma_max * self.edge_threshold) {
+                    output[data_idx] = input.get_pixel(idx_x, idx_y);
+                    continue;
+                }
+
+                //NOTE(dustin): perpendicular to the luma gradient, so along the edge, and scaled so the shorter axis is a pixel
+                let dir_x = (luma_sw + luma_se) - (luma_nw + luma_ne);
+                let dir_y = (luma_nw + luma_sw) - (luma_ne + luma_se);
+                let dir_reduce = ((luma_nw + luma_ne + luma_sw + luma_se) * 0.25_f32 * REDUCE_MUL).max(REDUCE_MIN);
+                let one_over_dir_min = 1_f32 / (dir_x.abs().min(dir_y.abs()) + dir_reduce);
+                let dir_x = (dir_x * one_over_dir_min).max(-self.span_max).min(self.span_max);
+                let dir_y = (dir_y * one_over_dir_min).max(-self.span_max).min(self.span_max);
+
+                let sample = |factor: f32| postprocess::sample_bilinear(input, idx_x as f32 + dir_x * factor, idx_y as f32 + dir_y * factor);
+                let average = |a: [f32; 4], b: [f32; 4]| [(a[0] + b[0]) * 0.5_f32, (a[1] + b[1]) * 0.5_f32, (a[2] + b[2]) * 0.5_f32, (a[3] + b[3]) * 0.5_f32];
+
+                let inner = average(sample(1_f32 / 3_f32 - 0.5_f32), sample(2_f32 / 3_f32 - 0.5_f32));
+                let outer = average(inner, average(sample(-0.5_f32), sample(0.5_f32)));
+
+                //NOTE(dustin): the wider blend crossed another edge if it leaves the luma range of the neighbours
+                let luma_outer = postprocess::calc_luma(&outer);
+                let result = if luma_outer < luma_min || luma_outer > luma_max { inner } else { outer };
+                output[data_idx] = postprocess::from_argb([color[0], result[1], result[2], result[3]]);
+            }
+        }
+    }
+}
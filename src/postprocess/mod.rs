@@ -0,0 +1,121 @@
+use orbclient;
+use std;
+
+use target::framebuffer::Framebuffer;
+
+pub mod blur;
+pub mod color;
+pub mod fxaa;
+pub mod outline;
+
+//NOTE(dustin): a full screen pass that runs after a frame is rasterized. reads the color and depth of input and writes every
+// pixel of output, which has one color per pixel of input and is not cleared before
+pub trait PostPass {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]);
+}
+
+//NOTE(dustin): runs the passes in the order they were added, the output of a pass is the input color of the next one and
+// the depth stays the one of the rasterized frame
+pub struct PostChain {
+    passes: Vec<Box<dyn PostPass>>,
+    scratch: Vec<orbclient::Color>
+}
+
+impl PostChain {
+    pub fn new() -> PostChain {
+        PostChain {
+            passes: Vec::new(),
+            scratch: Vec::new()
+        }
+    }
+
+    pub fn add<P: PostPass + 'static>(&mut self, pass: P) {
+        self.passes.push(Box::new(pass));
+    }
+
+    pub fn clear(&mut self) {
+        self.passes.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    //NOTE(dustin): only the first sample of a multisampled target is read, resolve it first
+    pub fn apply(&mut self, target: &mut Framebuffer) {
+        let pixel_count = (target.width * target.height) as usize;
+        assert!(target.color.len() == pixel_count, "post passes need a resolved target");
+
+        for pass in self.passes.iter() {
+            self.scratch.resize(pixel_count, orbclient::Color { data: 0 });
+            pass.apply(target, &mut self.scratch);
+            std::mem::swap(&mut target.color, &mut self.scratch);
+        }
+    }
+}
+
+impl Default for PostChain {
+    fn default() -> PostChain {
+        PostChain::new()
+    }
+}
+
+//NOTE(dustin): colors of a pass are argb in 0..1
+pub fn to_argb(color: orbclient::Color) -> [f32; 4] {
+    let channel = |shift: u32| ((color.data >> shift) & 0xFF) as f32 / 255_f32;
+    [channel(24), channel(16), channel(8), channel(0)]
+}
+
+pub fn from_argb(argb: [f32; 4]) -> orbclient::Color {
+    let channel = |value: f32, shift: u32| ((value.clamp(0_f32, 1_f32) * 255_f32 + 0.5_f32) as u32) << shift;
+    orbclient::Color { data: channel(argb[0], 24) | channel(argb[1], 16) | channel(argb[2], 8) | channel(argb[3], 0) }
+}
+
+pub fn calc_luma(argb: &[f32; 4]) -> f32 {
+    argb[1] * 0.299_f32 + argb[2] * 0.587_f32 + argb[3] * 0.114_f32
+}
+
+//NOTE(dustin): pixels outside of the framebuffer are the closest edge pixel
+pub fn fetch(input: &Framebuffer, x: i32, y: i32) -> [f32; 4] {
+    to_argb(input.get_pixel(x.max(0).min(input.width - 1), y.max(0).min(input.height - 1)))
+}
+
+pub fn fetch_depth(input: &Framebuffer, x: i32, y: i32) -> f32 {
+    input.depth.get_depth(x.max(0).min(input.width - 1), y.max(0).min(input.height - 1))
+}
+
+//NOTE(dustin): x and y are in pixels with the pixel centers at whole numbers like in the rasterizer
+pub fn sample_bilinear(input: &Framebuffer, x: f32, y: f32) -> [f32; 4] {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let frac_x = x - x0;
+    let frac_y = y - y0;
+    let x0 = x0 as i32;
+    let y0 = y0 as i32;
+
+    let lerp = |a: [f32; 4], b: [f32; 4], lerp_amt: f32| {
+        let mut result = [0_f32; 4];
+        for idx in 0..4 {
+            result[idx] = (b[idx] - a[idx]) * lerp_amt + a[idx];
+        }
+        result
+    };
+
+    let top = lerp(fetch(input, x0, y0), fetch(input, x0 + 1, y0), frac_x);
+    let bottom = lerp(fetch(input, x0, y0 + 1), fetch(input, x0 + 1, y0 + 1), frac_x);
+    lerp(top, bottom, frac_y)
+}
+
+//NOTE(dustin): for passes that only look at one pixel, map gets x, y and the color of the pixel
+pub fn map_pixels<M: Fn(i32, i32, [f32; 4]) -> [f32; 4]>(input: &Framebuffer, output: &mut [orbclient::Color], map: M) {
+    for idx_y in 0..input.height {
+        for idx_x in 0..input.width {
+            let data_idx = (idx_x + idx_y * input.width) as usize;
+            output[data_idx] = from_argb(map(idx_x, idx_y, to_argb(input.get_pixel(idx_x, idx_y))));
+        }
+    }
+}
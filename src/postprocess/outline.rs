@@ -0,0 +1,62 @@
+use orbclient;
+
+use postprocess::{self, PostPass};
+use target::framebuffer::Framebuffer;
+
+//NOTE(dustin): draws color over pixels where the sobel filter finds an edge in the depth or the luma. depth is the z of
+// the depth buffer, so silhouettes against the background stand out far more than creases. None skips that source
+pub struct Outline {
+    pub color: orbclient::Color,
+    pub depth_threshold: Option<f32>,
+    pub luma_threshold: Option<f32>
+}
+
+impl Outline {
+    pub fn new(_color: orbclient::Color) -> Outline {
+        Outline {
+            color: _color,
+            depth_threshold: Some(0.01_f32),
+            luma_threshold: None
+        }
+    }
+
+    pub fn init_depth_threshold(mut self, threshold: Option<f32>) -> Outline {
+        self.depth_threshold = threshold;
+        self
+    }
+
+    pub fn init_luma_threshold(mut self, threshold: Option<f32>) -> Outline {
+        self.luma_threshold = threshold;
+        self
+    }
+}
+
+//NOTE(dustin): length of the sobel gradient of value_at in the 3x3 pixels around x, y
+fn calc_sobel<F: Fn(i32, i32) -> f32>(x: i32, y: i32, value_at: F) -> f32 {
+    let mut values = [0_f32; 9];
+    for (idx, value) in values.iter_mut().enumerate() {
+        *value = value_at(x + idx as i32 % 3 - 1, y + idx as i32 / 3 - 1);
+    }
+
+    let gradient_x = (values[2] + 2_f32 * values[5] + values[8]) - (values[0] + 2_f32 * values[3] + values[6]);
+    let gradient_y = (values[6] + 2_f32 * values[7] + values[8]) - (values[0] + 2_f32 * values[1] + values[2]);
+    (gradient_x * gradient_x + gradient_y * gradient_y).sqrt()
+}
+
+impl PostPass for Outline {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        //NOTE(dustin): the cleared depth is far larger than the far plane, so it is clamped to the far plane
+        let depth_at = |x: i32, y: i32| postprocess::fetch_depth(input, x, y).min(1_f32);
+        let luma_at = |x: i32, y: i32| postprocess::calc_luma(&postprocess::fetch(input, x, y));
+
+        for idx_y in 0..input.height {
+            for idx_x in 0..input.width {
+                let is_depth_edge = self.depth_threshold.is_some_and(|threshold| calc_sobel(idx_x, idx_y, depth_at) > threshold);
+                let is_luma_edge = self.luma_threshold.is_some_and(|threshold| calc_sobel(idx_x, idx_y, luma_at) > threshold);
+
+                let data_idx = (idx_x + idx_y * input.width) as usize;
+                output[data_idx] = if is_depth_edge || is_luma_edge { self.color } else { input.get_pixel(idx_x, idx_y) };
+            }
+        }
+    }
+}
@@ -1,64 +1,45 @@
-use interpolate::Interpolator;
-use primitive::vertex::Vertex;
+use primitive::vertex::{Vertex, SUBPIXEL_SCALE};
 
+//NOTE(dustin): an edge of a ScreenTriangle from the vertex with the smaller y to the one with the larger y in fixed point,
+// the attributes are not stepped along the edge but evaluated per pixel by Rasterizer::shade_pixel
 pub struct Edge {
-    pub pos_x: f32,
-    pub step_x: f32,
     pub start_y: i32,
     pub end_y: i32,
 
-    pub tex_coords_x: f32,
-    pub tex_coords_step_x: f32,
-    pub tex_coords_y: f32,
-    pub tex_coords_step_y: f32,
-    pub one_over_z: f32,
-    pub one_over_step_z: f32
+    pub fixed_x: i64,
+    pub fixed_y: i64,
+    pub fixed_dist_x: i64,
+    pub fixed_dist_y: i64
 }
 
 impl Edge {
-    pub fn new(interpolator: &Interpolator, min_vert: &Vertex, max_vert: &Vertex, min_y_vert_index: i32) -> Edge {
-        let dist_y = max_vert.pos.y - min_vert.pos.y;
-        let dist_x = max_vert.pos.x - min_vert.pos.x;
-        let prestep_y = min_vert.pos.y.ceil() - min_vert.pos.y;
-        let _step_x = dist_x as f32 / dist_y as f32;
-        let _pos_x = min_vert.pos.x + prestep_y * _step_x;
-        let prestep_x = _pos_x - min_vert.pos.x;
-
-        let _tex_coord_x = interpolator.tex_coords_x[min_y_vert_index as usize] +
-            interpolator.tex_coords_step_xx * prestep_x +
-            interpolator.tex_coords_step_xy * prestep_y;
-        let _tex_coord_step_x = interpolator.tex_coords_step_xy + interpolator.tex_coords_step_xx * _step_x;
-
-        let _tex_coord_y = interpolator.tex_coords_y[min_y_vert_index as usize] +
-            interpolator.tex_coords_step_yx * prestep_x +
-            interpolator.tex_coords_step_yy * prestep_y;
-        let _tex_coord_step_y = interpolator.tex_coords_step_yy + interpolator.tex_coords_step_yx * _step_x;
-
-        let _one_over_z = interpolator.one_over_z[min_y_vert_index as usize] +
-            interpolator.one_over_step_zx * prestep_x +
-            interpolator.one_over_step_zy * prestep_y;
-        let _one_over_step_z = interpolator.one_over_step_zy + interpolator.one_over_step_zx * _step_x;
-
+    pub fn new(min_vert: &Vertex, max_vert: &Vertex) -> Edge {
+        let (min_x, min_y) = min_vert.get_fixed_pos();
+        let (max_x, max_y) = max_vert.get_fixed_pos();
 
         Edge {
-            pos_x: _pos_x,
-            step_x: _step_x,
-            start_y: min_vert.pos.y.ceil() as i32,
-            end_y: max_vert.pos.y.ceil() as i32,
+            start_y: (min_y + SUBPIXEL_SCALE - 1).div_euclid(SUBPIXEL_SCALE) as i32,
+            end_y: (max_y + SUBPIXEL_SCALE - 1).div_euclid(SUBPIXEL_SCALE) as i32,
 
-            tex_coords_x: _tex_coord_x,
-            tex_coords_step_x: _tex_coord_step_x,
-            tex_coords_y: _tex_coord_y,
-            tex_coords_step_y: _tex_coord_step_y,
-            one_over_z: _one_over_z,
-            one_over_step_z: _one_over_step_z
+            fixed_x: min_x,
+            fixed_y: min_y,
+            fixed_dist_x: max_x - min_x,
+            fixed_dist_y: max_y - min_y
         }
     }
 
-    pub fn step(&mut self) {
-        self.pos_x += self.step_x;
-        self.tex_coords_x += self.tex_coords_step_x;
-        self.tex_coords_y += self.tex_coords_step_y;
-        self.one_over_z += self.one_over_step_z;
+    //NOTE(dustin): the first pixel whose center on row idx_y lies on or right of the edge, exact so two triangles that share
+    // the edge agree. used as the inclusive start of a left edge and the exclusive end of a right edge, which together
+    // with the rows from Edge::start_y to Edge::end_y is the top-left fill rule
+    pub fn calc_pixel_x(&self, idx_y: i32) -> i32 {
+        let numerator = self.fixed_x * self.fixed_dist_y + (idx_y as i64 * SUBPIXEL_SCALE - self.fixed_y) * self.fixed_dist_x;
+        let denominator = self.fixed_dist_y * SUBPIXEL_SCALE;
+
+        let quotient = numerator.div_euclid(denominator);
+        if numerator.rem_euclid(denominator) > 0 {
+            (quotient + 1) as i32
+        } else {
+            quotient as i32
+        }
     }
 }
@@ -0,0 +1,235 @@
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use primitive::matrix::Matrix4f32;
+use primitive::scalar::{Float, Scalar};
+use primitive::vec::{Vec3, Vec4};
+
+//NOTE(dustin): row major like Matrix4f32, m[row][col], vectors are columns and get multiplied from the right
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mat3<T> {
+    pub m: [[T; 3]; 3]
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mat4<T> {
+    pub m: [[T; 4]; 4]
+}
+
+macro_rules! impl_matrix {
+    ($name:ident, $vec:ident, $size:expr) => {
+        impl<T: Scalar> $name<T> {
+            pub fn new(m: [[T; $size]; $size]) -> $name<T> {
+                $name { m }
+            }
+
+            pub fn zero() -> $name<T> {
+                $name { m: [[T::zero(); $size]; $size] }
+            }
+
+            pub fn identity() -> $name<T> {
+                let mut ret = $name::zero();
+                for idx in 0..$size {
+                    ret.m[idx][idx] = T::one();
+                }
+
+                ret
+            }
+
+            pub fn transpose(&self) -> $name<T> {
+                let mut ret = $name::zero();
+                for r_idx in 0..$size {
+                    for c_idx in 0..$size {
+                        ret.m[r_idx][c_idx] = self.m[c_idx][r_idx];
+                    }
+                }
+
+                ret
+            }
+
+            pub fn row(&self, r_idx: usize) -> $vec<T> {
+                let mut ret = $vec::zero();
+                for c_idx in 0..$size {
+                    ret[c_idx] = self.m[r_idx][c_idx];
+                }
+
+                ret
+            }
+
+            pub fn col(&self, c_idx: usize) -> $vec<T> {
+                let mut ret = $vec::zero();
+                for r_idx in 0..$size {
+                    ret[r_idx] = self.m[r_idx][c_idx];
+                }
+
+                ret
+            }
+
+            fn map<F: Fn(T) -> T>(&self, f: F) -> $name<T> {
+                let mut ret = *self;
+                for r_idx in 0..$size {
+                    for c_idx in 0..$size {
+                        ret.m[r_idx][c_idx] = f(self.m[r_idx][c_idx]);
+                    }
+                }
+
+                ret
+            }
+
+            fn zip<F: Fn(T, T) -> T>(&self, other: &$name<T>, f: F) -> $name<T> {
+                let mut ret = *self;
+                for r_idx in 0..$size {
+                    for c_idx in 0..$size {
+                        ret.m[r_idx][c_idx] = f(self.m[r_idx][c_idx], other.m[r_idx][c_idx]);
+                    }
+                }
+
+                ret
+            }
+        }
+
+        impl<T: Float> $name<T> {
+            pub fn approx_eq(&self, other: &$name<T>, epsilon: T) -> bool {
+                for r_idx in 0..$size {
+                    for c_idx in 0..$size {
+                        if !self.m[r_idx][c_idx].approx_eq(other.m[r_idx][c_idx], epsilon) {
+                            return false;
+                        }
+                    }
+                }
+
+                true
+            }
+        }
+
+        impl<T: Scalar> Add for $name<T> {
+            type Output = $name<T>;
+
+            fn add(self, other: $name<T>) -> $name<T> {
+                self.zip(&other, |a, b| a + b)
+            }
+        }
+
+        impl<T: Scalar> Sub for $name<T> {
+            type Output = $name<T>;
+
+            fn sub(self, other: $name<T>) -> $name<T> {
+                self.zip(&other, |a, b| a - b)
+            }
+        }
+
+        impl<T: Scalar> Mul for $name<T> {
+            type Output = $name<T>;
+
+            fn mul(self, other: $name<T>) -> $name<T> {
+                let mut ret = $name::zero();
+                for r_idx in 0..$size {
+                    for c_idx in 0..$size {
+                        let mut sum = T::zero();
+                        for idx in 0..$size {
+                            sum = sum + self.m[r_idx][idx] * other.m[idx][c_idx];
+                        }
+                        ret.m[r_idx][c_idx] = sum;
+                    }
+                }
+
+                ret
+            }
+        }
+
+        impl<T: Scalar> Mul<$vec<T>> for $name<T> {
+            type Output = $vec<T>;
+
+            fn mul(self, vector: $vec<T>) -> $vec<T> {
+                let mut ret = $vec::zero();
+                for r_idx in 0..$size {
+                    ret[r_idx] = self.row(r_idx).dot(&vector);
+                }
+
+                ret
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $name<T> {
+            type Output = $name<T>;
+
+            fn mul(self, factor: T) -> $name<T> {
+                self.map(|a| a * factor)
+            }
+        }
+
+        impl<T: Scalar> Neg for $name<T> {
+            type Output = $name<T>;
+
+            fn neg(self) -> $name<T> {
+                self.map(|a| -a)
+            }
+        }
+
+        impl<T> Index<usize> for $name<T> {
+            type Output = [T; $size];
+
+            fn index(&self, r_idx: usize) -> &[T; $size] {
+                &self.m[r_idx]
+            }
+        }
+
+        impl<T> IndexMut<usize> for $name<T> {
+            fn index_mut(&mut self, r_idx: usize) -> &mut [T; $size] {
+                &mut self.m[r_idx]
+            }
+        }
+    }
+}
+
+impl_matrix!(Mat3, Vec3, 3);
+impl_matrix!(Mat4, Vec4, 4);
+
+impl<T: Scalar> Mat3<T> {
+    pub fn determinant(&self) -> T {
+        let m = &self.m;
+
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    //NOTE(dustin): rotation and scale, translation is zero
+    pub fn extend(&self) -> Mat4<T> {
+        let mut ret = Mat4::identity();
+        for r_idx in 0..3 {
+            for c_idx in 0..3 {
+                ret.m[r_idx][c_idx] = self.m[r_idx][c_idx];
+            }
+        }
+
+        ret
+    }
+}
+
+impl<T: Scalar> Mat4<T> {
+    //NOTE(dustin): the upper 3x3, drops translation and projection
+    pub fn truncate(&self) -> Mat3<T> {
+        let mut ret = Mat3::zero();
+        for r_idx in 0..3 {
+            for c_idx in 0..3 {
+                ret.m[r_idx][c_idx] = self.m[r_idx][c_idx];
+            }
+        }
+
+        ret
+    }
+}
+
+impl From<Matrix4f32> for Mat4<f32> {
+    fn from(matrix: Matrix4f32) -> Mat4<f32> {
+        Mat4::new(matrix.m)
+    }
+}
+
+impl From<Mat4<f32>> for Matrix4f32 {
+    fn from(matrix: Mat4<f32>) -> Matrix4f32 {
+        let mut ret = Matrix4f32::new();
+        ret.m = matrix.m;
+        ret
+    }
+}
@@ -1,5 +1,7 @@
+use primitive::quaternion::Quaternion;
 use primitive::vector::Vector4f32;
 
+#[derive(Debug, Copy, Clone)]
 pub struct Matrix4f32 {
     pub m: [[f32; 4]; 4]
 }
@@ -21,6 +23,20 @@ impl Matrix4f32 {
         self
     }
 
+    //NOTE(dustin): maps the box to -1..1 on every axis with w = 1, z_near and z_far are distances along +z like init_perspective
+    pub fn init_orthographic(mut self, left: f32, right: f32, bottom: f32, top: f32, z_near: f32, z_far: f32) -> Matrix4f32 {
+        let width = right - left;
+        let height = top - bottom;
+        let depth = z_far - z_near;
+
+        self.m[0][0] = 2f32 / width;    self.m[0][1] = 0f32;            self.m[0][2] = 0f32;            self.m[0][3] = -(right + left) / width;
+        self.m[1][0] = 0f32;            self.m[1][1] = 2f32 / height;   self.m[1][2] = 0f32;            self.m[1][3] = -(top + bottom) / height;
+        self.m[2][0] = 0f32;            self.m[2][1] = 0f32;            self.m[2][2] = 2f32 / depth;    self.m[2][3] = -(z_far + z_near) / depth;
+        self.m[3][0] = 0f32;            self.m[3][1] = 0f32;            self.m[3][2] = 0f32;            self.m[3][3] = 1f32;
+
+        self
+    }
+
     pub fn init_translation(mut self, x: f32, y: f32, z: f32) -> Matrix4f32 {
         self.m[0][0] = 1f32;    self.m[0][1] = 0f32;    self.m[0][2] = 0f32;    self.m[0][3] = x;
         self.m[1][0] = 0f32;    self.m[1][1] = 1f32;    self.m[1][2] = 0f32;    self.m[1][3] = y;
@@ -30,6 +46,15 @@ impl Matrix4f32 {
         self
     }
 
+    pub fn init_scale(mut self, x: f32, y: f32, z: f32) -> Matrix4f32 {
+        self.m[0][0] = x;       self.m[0][1] = 0f32;    self.m[0][2] = 0f32;    self.m[0][3] = 0f32;
+        self.m[1][0] = 0f32;    self.m[1][1] = y;       self.m[1][2] = 0f32;    self.m[1][3] = 0f32;
+        self.m[2][0] = 0f32;    self.m[2][1] = 0f32;    self.m[2][2] = z;       self.m[2][3] = 0f32;
+        self.m[3][0] = 0f32;    self.m[3][1] = 0f32;    self.m[3][2] = 0f32;    self.m[3][3] = 1f32;
+
+        self
+    }
+
     pub fn init_rotation(mut self, x: f32, y: f32, z: f32) -> Matrix4f32 {
         let mut rx = Matrix4f32::new();
         let mut ry = Matrix4f32::new();
@@ -55,6 +80,36 @@ impl Matrix4f32 {
         self
     }
 
+    //NOTE(dustin): rotates counter clockwise around axis when looking down the axis, axis has to be normalized
+    pub fn init_axis_rotation(mut self, axis: &Vector4f32, angle: f32) -> Matrix4f32 {
+        let sin = angle.sin();
+        let cos = angle.cos();
+        let one_minus_cos = 1f32 - cos;
+        let (x, y, z) = (axis.x, axis.y, axis.z);
+
+        self.m[0][0] = cos + x * x * one_minus_cos;      self.m[0][1] = x * y * one_minus_cos - z * sin;  self.m[0][2] = x * z * one_minus_cos + y * sin;  self.m[0][3] = 0f32;
+        self.m[1][0] = y * x * one_minus_cos + z * sin;  self.m[1][1] = cos + y * y * one_minus_cos;      self.m[1][2] = y * z * one_minus_cos - x * sin;  self.m[1][3] = 0f32;
+        self.m[2][0] = z * x * one_minus_cos - y * sin;  self.m[2][1] = z * y * one_minus_cos + x * sin;  self.m[2][2] = cos + z * z * one_minus_cos;      self.m[2][3] = 0f32;
+        self.m[3][0] = 0f32;                             self.m[3][1] = 0f32;                             self.m[3][2] = 0f32;                             self.m[3][3] = 1f32;
+
+        self
+    }
+
+    //NOTE(dustin): view matrix, the camera looks down +z with +y up like init_perspective expects
+    pub fn init_look_at(mut self, eye: &Vector4f32, target: &Vector4f32, up: &Vector4f32) -> Matrix4f32 {
+        let forward = Vector4f32::new(target.x - eye.x, target.y - eye.y, target.z - eye.z, 0f32).normalized();
+        let right = up.cross(&forward).normalized();
+        let up = forward.cross(&right);
+        let eye = Vector4f32::new(eye.x, eye.y, eye.z, 0f32);
+
+        self.m[0][0] = right.x;     self.m[0][1] = right.y;     self.m[0][2] = right.z;     self.m[0][3] = -right.dot(&eye);
+        self.m[1][0] = up.x;        self.m[1][1] = up.y;        self.m[1][2] = up.z;        self.m[1][3] = -up.dot(&eye);
+        self.m[2][0] = forward.x;   self.m[2][1] = forward.y;   self.m[2][2] = forward.z;   self.m[2][3] = -forward.dot(&eye);
+        self.m[3][0] = 0f32;        self.m[3][1] = 0f32;        self.m[3][2] = 0f32;        self.m[3][3] = 1f32;
+
+        self
+    }
+
     pub fn init_sreenspace_transform(mut self, half_width: f32, half_height: f32) -> Matrix4f32 {
         self.m[0][0] = half_width;  self.m[0][1] = 0f32;            self.m[0][2] = 0f32;    self.m[0][3] = half_width;
         self.m[1][0] = 0f32;        self.m[1][1] = -half_height;    self.m[1][2] = 0f32;    self.m[1][3] = half_height;
@@ -64,6 +119,135 @@ impl Matrix4f32 {
         self
     }
 
+    //NOTE(dustin): inverse transpose of the upper 3x3, keeps normals perpendicular under non uniform scale
+    pub fn normal_matrix(&self) -> Matrix4f32 {
+        let m = &self.m;
+        let mut ret = Matrix4f32::new();
+
+        ret.m[0][0] = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+        ret.m[0][1] = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+        ret.m[0][2] = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+        ret.m[1][0] = m[0][2] * m[2][1] - m[0][1] * m[2][2];
+        ret.m[1][1] = m[0][0] * m[2][2] - m[0][2] * m[2][0];
+        ret.m[1][2] = m[0][1] * m[2][0] - m[0][0] * m[2][1];
+        ret.m[2][0] = m[0][1] * m[1][2] - m[0][2] * m[1][1];
+        ret.m[2][1] = m[0][2] * m[1][0] - m[0][0] * m[1][2];
+        ret.m[2][2] = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+        ret.m[3][3] = 1f32;
+
+        let det = m[0][0] * ret.m[0][0] + m[0][1] * ret.m[0][1] + m[0][2] * ret.m[0][2];
+        if det != 0f32 {
+            for r_idx in 0..3 {
+                for c_idx in 0..3 {
+                    ret.m[r_idx][c_idx] /= det;
+                }
+            }
+        }
+
+        ret
+    }
+
+    pub fn transpose(&self) -> Matrix4f32 {
+        let mut ret = Matrix4f32::new();
+
+        for r_idx in 0..4 {
+            for c_idx in 0..4 {
+                ret.m[r_idx][c_idx] = self.m[c_idx][r_idx];
+            }
+        }
+
+        ret
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let (low, high) = self.calc_sub_determinants();
+
+        low[0] * high[5] - low[1] * high[4] + low[2] * high[3] + low[3] * high[2] - low[4] * high[1] + low[5] * high[0]
+    }
+
+    //NOTE(dustin): None for singular matrices, e.g. a scale of 0 or a projection onto a plane
+    pub fn inverse(&self) -> Option<Matrix4f32> {
+        let m = &self.m;
+        let (low, high) = self.calc_sub_determinants();
+
+        let det = low[0] * high[5] - low[1] * high[4] + low[2] * high[3] + low[3] * high[2] - low[4] * high[1] + low[5] * high[0];
+        if det == 0f32 || !det.is_finite() {
+            return None;
+        }
+
+        let inv_det = 1f32 / det;
+        let mut ret = Matrix4f32::new();
+
+        ret.m[0][0] = ( m[1][1] * high[5] - m[1][2] * high[4] + m[1][3] * high[3]) * inv_det;
+        ret.m[0][1] = (-m[0][1] * high[5] + m[0][2] * high[4] - m[0][3] * high[3]) * inv_det;
+        ret.m[0][2] = ( m[3][1] * low[5] - m[3][2] * low[4] + m[3][3] * low[3]) * inv_det;
+        ret.m[0][3] = (-m[2][1] * low[5] + m[2][2] * low[4] - m[2][3] * low[3]) * inv_det;
+
+        ret.m[1][0] = (-m[1][0] * high[5] + m[1][2] * high[2] - m[1][3] * high[1]) * inv_det;
+        ret.m[1][1] = ( m[0][0] * high[5] - m[0][2] * high[2] + m[0][3] * high[1]) * inv_det;
+        ret.m[1][2] = (-m[3][0] * low[5] + m[3][2] * low[2] - m[3][3] * low[1]) * inv_det;
+        ret.m[1][3] = ( m[2][0] * low[5] - m[2][2] * low[2] + m[2][3] * low[1]) * inv_det;
+
+        ret.m[2][0] = ( m[1][0] * high[4] - m[1][1] * high[2] + m[1][3] * high[0]) * inv_det;
+        ret.m[2][1] = (-m[0][0] * high[4] + m[0][1] * high[2] - m[0][3] * high[0]) * inv_det;
+        ret.m[2][2] = ( m[3][0] * low[4] - m[3][1] * low[2] + m[3][3] * low[0]) * inv_det;
+        ret.m[2][3] = (-m[2][0] * low[4] + m[2][1] * low[2] - m[2][3] * low[0]) * inv_det;
+
+        ret.m[3][0] = (-m[1][0] * high[3] + m[1][1] * high[1] - m[1][2] * high[0]) * inv_det;
+        ret.m[3][1] = ( m[0][0] * high[3] - m[0][1] * high[1] + m[0][2] * high[0]) * inv_det;
+        ret.m[3][2] = (-m[3][0] * low[3] + m[3][1] * low[1] - m[3][2] * low[0]) * inv_det;
+        ret.m[3][3] = ( m[2][0] * low[3] - m[2][1] * low[1] + m[2][2] * low[0]) * inv_det;
+
+        Some(ret)
+    }
+
+    //NOTE(dustin): 2x2 determinants of the upper two rows (low) and the lower two rows (high) for the column pairs
+    // 01, 02, 03, 12, 13, 23, shared by determinant and inverse
+    fn calc_sub_determinants(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.m;
+        let pairs = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+        let mut low = [0f32; 6];
+        let mut high = [0f32; 6];
+
+        for (idx, &(a, b)) in pairs.iter().enumerate() {
+            low[idx] = m[0][a] * m[1][b] - m[0][b] * m[1][a];
+            high[idx] = m[2][a] * m[3][b] - m[2][b] * m[3][a];
+        }
+
+        (low, high)
+    }
+
+    //NOTE(dustin): splits an affine translation * rotation * scale matrix, a mirroring is folded into a negative x scale,
+    // None if a scale is 0 because the rotation is undefined then
+    pub fn decompose(&self) -> Option<(Vector4f32, Quaternion, Vector4f32)> {
+        let m = &self.m;
+        let translation = Vector4f32::new(m[0][3], m[1][3], m[2][3], 0f32);
+
+        let mut columns = [Vector4f32::new(0f32, 0f32, 0f32, 0f32); 3];
+        for c_idx in 0..3 {
+            columns[c_idx] = Vector4f32::new(m[0][c_idx], m[1][c_idx], m[2][c_idx], 0f32);
+        }
+
+        let mut scale = Vector4f32::new(columns[0].length(), columns[1].length(), columns[2].length(), 0f32);
+        if scale.x == 0f32 || scale.y == 0f32 || scale.z == 0f32 {
+            return None;
+        }
+
+        if columns[0].cross(&columns[1]).dot(&columns[2]) < 0f32 {
+            scale.x = -scale.x;
+        }
+
+        let mut rotation = Matrix4f32::new();
+        for (rotation_row, row) in rotation.m.iter_mut().zip(m.iter()).take(3) {
+            rotation_row[0] = row[0] / scale.x;
+            rotation_row[1] = row[1] / scale.y;
+            rotation_row[2] = row[2] / scale.z;
+        }
+        rotation.m[3][3] = 1f32;
+
+        Some((translation, Quaternion::from_matrix(&rotation), scale))
+    }
+
     pub fn transform(&self, other: &Vector4f32) -> Vector4f32 {
         Vector4f32 {
             x: self.m[0][0] * other.x + self.m[0][1] * other.y + self.m[0][2] * other.z + self.m[0][3] * other.w,
@@ -88,3 +272,9 @@ impl Matrix4f32 {
         ret
     }
 }
+
+impl Default for Matrix4f32 {
+    fn default() -> Matrix4f32 {
+        Matrix4f32::new()
+    }
+}
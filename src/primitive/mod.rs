@@ -1,4 +1,8 @@
 pub mod edge;
+pub mod mat;
 pub mod matrix;
+pub mod quaternion;
+pub mod scalar;
+pub mod vec;
 pub mod vector;
 pub mod vertex;
@@ -0,0 +1,149 @@
+use primitive::matrix::Matrix4f32;
+use primitive::vector::Vector4f32;
+
+//NOTE(dustin): unit quaternions represent rotations, the constructors and conversions assume and return normalized ones
+#[derive(Debug, Copy, Clone)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32
+}
+
+impl Quaternion {
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quaternion {
+        Quaternion {
+            x,
+            y,
+            z,
+            w
+        }
+    }
+
+    pub fn identity() -> Quaternion {
+        Quaternion::new(0_f32, 0_f32, 0_f32, 1_f32)
+    }
+
+    //NOTE(dustin): same direction as Matrix4f32::init_axis_rotation, axis has to be normalized
+    pub fn from_axis_angle(axis: &Vector4f32, angle: f32) -> Quaternion {
+        let half_sin = (angle / 2_f32).sin();
+        let half_cos = (angle / 2_f32).cos();
+
+        Quaternion::new(axis.x * half_sin, axis.y * half_sin, axis.z * half_sin, half_cos)
+    }
+
+    //NOTE(dustin): same rotation as Matrix4f32::init_rotation, x first then y then z, the y rotation there turns the other way
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Quaternion {
+        let rx = Quaternion::from_axis_angle(&Vector4f32::new(1_f32, 0_f32, 0_f32, 0_f32), x);
+        let ry = Quaternion::from_axis_angle(&Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32), -y);
+        let rz = Quaternion::from_axis_angle(&Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32), z);
+
+        rz.mul(&ry.mul(&rx))
+    }
+
+    //NOTE(dustin): only the upper 3x3 is used and it has to be a pure rotation
+    pub fn from_matrix(matrix: &Matrix4f32) -> Quaternion {
+        let m = &matrix.m;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        let quaternion = if trace > 0_f32 {
+            let s = 0.5_f32 / (trace + 1_f32).sqrt();
+            Quaternion::new((m[2][1] - m[1][2]) * s, (m[0][2] - m[2][0]) * s, (m[1][0] - m[0][1]) * s, 0.25_f32 / s)
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = 2_f32 * (1_f32 + m[0][0] - m[1][1] - m[2][2]).sqrt();
+            Quaternion::new(0.25_f32 * s, (m[0][1] + m[1][0]) / s, (m[0][2] + m[2][0]) / s, (m[2][1] - m[1][2]) / s)
+        } else if m[1][1] > m[2][2] {
+            let s = 2_f32 * (1_f32 + m[1][1] - m[0][0] - m[2][2]).sqrt();
+            Quaternion::new((m[0][1] + m[1][0]) / s, 0.25_f32 * s, (m[1][2] + m[2][1]) / s, (m[0][2] - m[2][0]) / s)
+        } else {
+            let s = 2_f32 * (1_f32 + m[2][2] - m[0][0] - m[1][1]).sqrt();
+            Quaternion::new((m[0][2] + m[2][0]) / s, (m[1][2] + m[2][1]) / s, 0.25_f32 * s, (m[1][0] - m[0][1]) / s)
+        };
+
+        quaternion.normalized()
+    }
+
+    pub fn to_matrix(&self) -> Matrix4f32 {
+        let (x, y, z, w) = (self.x, self.y, self.z, self.w);
+        let mut ret = Matrix4f32::new();
+
+        ret.m[0][0] = 1_f32 - 2_f32 * (y * y + z * z);  ret.m[0][1] = 2_f32 * (x * y - w * z);          ret.m[0][2] = 2_f32 * (x * z + w * y);
+        ret.m[1][0] = 2_f32 * (x * y + w * z);          ret.m[1][1] = 1_f32 - 2_f32 * (x * x + z * z);  ret.m[1][2] = 2_f32 * (y * z - w * x);
+        ret.m[2][0] = 2_f32 * (x * z - w * y);          ret.m[2][1] = 2_f32 * (y * z + w * x);          ret.m[2][2] = 1_f32 - 2_f32 * (x * x + y * y);
+        ret.m[3][3] = 1_f32;
+
+        ret
+    }
+
+    //NOTE(dustin): self.mul(other) rotates by other first, like matrices
+    pub fn mul(&self, other: &Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z)
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalized(&self) -> Quaternion {
+        let length = self.length();
+        Quaternion::new(self.x / length, self.y / length, self.z / length, self.w / length)
+    }
+
+    //NOTE(dustin): keeps w of the vector, so points and directions both work
+    pub fn rotate(&self, vector: &Vector4f32) -> Vector4f32 {
+        let rotated = self.mul(&Quaternion::new(vector.x, vector.y, vector.z, 0_f32)).mul(&self.conjugate());
+        Vector4f32::new(rotated.x, rotated.y, rotated.z, vector.w)
+    }
+
+    //NOTE(dustin): normalized linear interpolation, cheap but the angular speed is not constant
+    pub fn nlerp(&self, dest: &Quaternion, lerp_factor: f32) -> Quaternion {
+        let dest = self.shortest(dest);
+
+        Quaternion::new(
+            (dest.x - self.x) * lerp_factor + self.x,
+            (dest.y - self.y) * lerp_factor + self.y,
+            (dest.z - self.z) * lerp_factor + self.z,
+            (dest.w - self.w) * lerp_factor + self.w).normalized()
+    }
+
+    //NOTE(dustin): constant angular speed along the shorter arc, falls back to nlerp for nearly equal rotations
+    pub fn slerp(&self, dest: &Quaternion, lerp_factor: f32) -> Quaternion {
+        let dest = self.shortest(dest);
+        let cos = self.dot(&dest).min(1_f32);
+        if cos > 0.9995_f32 {
+            return self.nlerp(&dest, lerp_factor);
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+        let src_factor = ((1_f32 - lerp_factor) * angle).sin() / sin;
+        let dest_factor = (lerp_factor * angle).sin() / sin;
+
+        Quaternion::new(
+            self.x * src_factor + dest.x * dest_factor,
+            self.y * src_factor + dest.y * dest_factor,
+            self.z * src_factor + dest.z * dest_factor,
+            self.w * src_factor + dest.w * dest_factor)
+    }
+
+    //NOTE(dustin): q and -q are the same rotation, pick the one on the same hemisphere as self
+    fn shortest(&self, other: &Quaternion) -> Quaternion {
+        if self.dot(other) < 0_f32 {
+            Quaternion::new(-other.x, -other.y, -other.z, -other.w)
+        } else {
+            *other
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+//NOTE(dustin): the component type of Vec2/3/4 and Mat3/4, implemented for the types we actually use
+pub trait Scalar: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    fn min(self, other: Self) -> Self {
+        if other < self { other } else { self }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other > self { other } else { self }
+    }
+
+    fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+}
+
+//NOTE(dustin): everything that needs a square root or a tolerance, length, normalize, approx_eq
+pub trait Float: Scalar {
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn epsilon() -> Self;
+
+    fn approx_eq(self, other: Self, epsilon: Self) -> bool {
+        (self - other).abs() <= epsilon
+    }
+}
+
+impl Scalar for f32 {
+    fn zero() -> f32 { 0_f32 }
+    fn one() -> f32 { 1_f32 }
+}
+
+impl Scalar for f64 {
+    fn zero() -> f64 { 0_f64 }
+    fn one() -> f64 { 1_f64 }
+}
+
+impl Scalar for i32 {
+    fn zero() -> i32 { 0 }
+    fn one() -> i32 { 1 }
+}
+
+impl Float for f32 {
+    fn sqrt(self) -> f32 { f32::sqrt(self) }
+    fn abs(self) -> f32 { f32::abs(self) }
+    fn epsilon() -> f32 { 1e-5_f32 }
+}
+
+impl Float for f64 {
+    fn sqrt(self) -> f64 { f64::sqrt(self) }
+    fn abs(self) -> f64 { f64::abs(self) }
+    fn epsilon() -> f64 { 1e-10_f64 }
+}
@@ -0,0 +1,207 @@
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use primitive::scalar::{Float, Scalar};
+use primitive::vector::Vector4f32;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Vec4<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T
+}
+
+//NOTE(dustin): the three vector types only differ in their fields, everything componentwise is written once here
+macro_rules! impl_vector {
+    ($name:ident, $($field:ident => $idx:tt),+) => {
+        impl<T: Scalar> $name<T> {
+            pub fn new($($field: T),+) -> $name<T> {
+                $name { $($field: $field),+ }
+            }
+
+            pub fn splat(value: T) -> $name<T> {
+                $name { $($field: value),+ }
+            }
+
+            pub fn zero() -> $name<T> {
+                $name::splat(T::zero())
+            }
+
+            pub fn dot(&self, other: &$name<T>) -> T {
+                T::zero() $(+ self.$field * other.$field)+
+            }
+
+            pub fn length_squared(&self) -> T {
+                self.dot(self)
+            }
+
+            pub fn mul_v(&self, other: &$name<T>) -> $name<T> {
+                $name { $($field: self.$field * other.$field),+ }
+            }
+
+            pub fn lerp(&self, dest: &$name<T>, lerp_factor: T) -> $name<T> {
+                (*dest - *self) * lerp_factor + *self
+            }
+
+            //NOTE(dustin): reflects around the plane with the given normal, normal has to be normalized
+            pub fn reflect(&self, normal: &$name<T>) -> $name<T> {
+                *self - *normal * ((T::one() + T::one()) * self.dot(normal))
+            }
+
+            pub fn min(&self, other: &$name<T>) -> $name<T> {
+                $name { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            pub fn max(&self, other: &$name<T>) -> $name<T> {
+                $name { $($field: self.$field.max(other.$field)),+ }
+            }
+
+            pub fn clamp(&self, min: &$name<T>, max: &$name<T>) -> $name<T> {
+                $name { $($field: self.$field.clamp(min.$field, max.$field)),+ }
+            }
+        }
+
+        impl<T: Float> $name<T> {
+            pub fn length(&self) -> T {
+                self.dot(self).sqrt()
+            }
+
+            pub fn normalized(&self) -> $name<T> {
+                let length = self.length();
+                $name { $($field: self.$field / length),+ }
+            }
+
+            pub fn approx_eq(&self, other: &$name<T>, epsilon: T) -> bool {
+                true $(&& self.$field.approx_eq(other.$field, epsilon))+
+            }
+        }
+
+        impl<T: Scalar> Add for $name<T> {
+            type Output = $name<T>;
+
+            fn add(self, other: $name<T>) -> $name<T> {
+                $name { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Scalar> Sub for $name<T> {
+            type Output = $name<T>;
+
+            fn sub(self, other: $name<T>) -> $name<T> {
+                $name { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $name<T> {
+            type Output = $name<T>;
+
+            fn mul(self, factor: T) -> $name<T> {
+                $name { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl<T: Scalar> Neg for $name<T> {
+            type Output = $name<T>;
+
+            fn neg(self) -> $name<T> {
+                $name { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T> Index<usize> for $name<T> {
+            type Output = T;
+
+            fn index(&self, idx: usize) -> &T {
+                match idx {
+                    $($idx => &self.$field,)+
+                    _ => panic!("index {} out of range for {}", idx, stringify!($name))
+                }
+            }
+        }
+
+        impl<T> IndexMut<usize> for $name<T> {
+            fn index_mut(&mut self, idx: usize) -> &mut T {
+                match idx {
+                    $($idx => &mut self.$field,)+
+                    _ => panic!("index {} out of range for {}", idx, stringify!($name))
+                }
+            }
+        }
+    }
+}
+
+impl_vector!(Vec2, x => 0, y => 1);
+impl_vector!(Vec3, x => 0, y => 1, z => 2);
+impl_vector!(Vec4, x => 0, y => 1, z => 2, w => 3);
+
+impl<T: Scalar> Vec2<T> {
+    //NOTE(dustin): z of the 3d cross product, positive if other is counter clockwise from self
+    pub fn perp_dot(&self, other: &Vec2<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn extend(&self, z: T) -> Vec3<T> {
+        Vec3::new(self.x, self.y, z)
+    }
+}
+
+impl<T: Scalar> Vec3<T> {
+    pub fn cross(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.y * other.z - self.z * other.y,
+                  self.z * other.x - self.x * other.z,
+                  self.x * other.y - self.y * other.x)
+    }
+
+    pub fn extend(&self, w: T) -> Vec4<T> {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+
+    pub fn truncate(&self) -> Vec2<T> {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+impl<T: Scalar> Vec4<T> {
+    pub fn truncate(&self) -> Vec3<T> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl<T: Float> Vec4<T> {
+    //NOTE(dustin): perspective divide, only meaningful for points with w != 0
+    pub fn project(&self) -> Vec3<T> {
+        Vec3::new(self.x / self.w, self.y / self.w, self.z / self.w)
+    }
+}
+
+impl From<Vector4f32> for Vec4<f32> {
+    fn from(vector: Vector4f32) -> Vec4<f32> {
+        Vec4::new(vector.x, vector.y, vector.z, vector.w)
+    }
+}
+
+impl From<Vec4<f32>> for Vector4f32 {
+    fn from(vector: Vec4<f32>) -> Vector4f32 {
+        Vector4f32::new(vector.x, vector.y, vector.z, vector.w)
+    }
+}
+
+//NOTE(dustin): drops w, use Vec3::extend to pick one for the way back
+impl From<Vector4f32> for Vec3<f32> {
+    fn from(vector: Vector4f32) -> Vec3<f32> {
+        Vec3::new(vector.x, vector.y, vector.z)
+    }
+}
@@ -10,10 +10,10 @@ impl Vector4f32 {
 
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Vector4f32 {
         Vector4f32 {
-            x: x,
-            y: y,
-            z: z,
-            w: w
+            x,
+            y,
+            z,
+            w
         }
     }
 
@@ -25,6 +25,18 @@ impl Vector4f32 {
         Vector4f32::new(self.x - other.x, self.y - other.y, self.z - other.z, self.w - other.w)
     }
 
+    pub fn mul_v(&self, other: &Vector4f32) -> Vector4f32 {
+        Vector4f32::new(self.x * other.x, self.y * other.y, self.z * other.z, self.w * other.w)
+    }
+
+    pub fn mul_f(&self, factor: f32) -> Vector4f32 {
+        Vector4f32::new(self.x * factor, self.y * factor, self.z * factor, self.w * factor)
+    }
+
+    pub fn lerp(&self, dest: &Vector4f32, lerp_factor: f32) -> Vector4f32 {
+        dest.sub_v(self).mul_f(lerp_factor).add_v(self)
+    }
+
     pub fn length(&self) -> f32 {
         ((self.x * self.x) + (self.y * self.y) + (self.z * self.z) + (self.w * self.w)).sqrt()
     }
@@ -34,6 +46,10 @@ impl Vector4f32 {
         Vector4f32::new(self.x / length, self.y / length, self.z / length, self.w / length)
     }
 
+    pub fn dot(&self, other: &Vector4f32) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
     pub fn cross(&self, other: &Vector4f32) -> Vector4f32 {
         let x = self.y * other.z - self.z * other.y;
         let y = self.z * other.x - self.x * other.z;
@@ -1,9 +1,17 @@
 use primitive::matrix::Matrix4f32;
 use primitive::vector::Vector4f32;
+use shader::Varyings;
 
+//NOTE(dustin): screen positions are snapped to 28.4 fixed point before rasterization, 1/16 of a pixel
+pub const SUBPIXEL_BITS: i64 = 4;
+pub const SUBPIXEL_SCALE: i64 = 1 << SUBPIXEL_BITS;
+
+#[derive(Debug, Clone)]
 pub struct Vertex {
     pub pos: Vector4f32,
-    pub tex_coords: Vector4f32 //TODO(dustin): don't waste space here we only need 2 values
+    pub tex_coords: Vector4f32, //TODO(dustin): don't waste space here we only need 2 values
+    pub normal: Vector4f32,
+    pub varyings: Varyings
 }
 
 impl Vertex {
@@ -11,25 +19,66 @@ impl Vertex {
     //     Vertex{pos: Vector4f32{x: _x, y: _y, z: _z, w: 1f32}}
     // }
 
+    pub fn new(_pos: Vector4f32, _coords: Vector4f32, _normal: Vector4f32) -> Vertex {
+        Vertex{pos: _pos, tex_coords: _coords, normal: _normal, varyings: Varyings::new(0)}
+    }
+
     pub fn new_with_pos_and_texcoords(_pos: Vector4f32, _coords: Vector4f32) -> Vertex {
-        Vertex{pos: _pos, tex_coords: _coords}
+        Vertex::new(_pos, _coords, Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32))
+    }
+
+    pub fn new_with_pos_and_varyings(_pos: Vector4f32, _coords: Vector4f32, _normal: Vector4f32, _varyings: Varyings) -> Vertex {
+        Vertex{pos: _pos, tex_coords: _coords, normal: _normal, varyings: _varyings}
+    }
+
+    pub fn get(&self, index: usize) -> f32 {
+        match index {
+            0 => self.pos.x,
+            1 => self.pos.y,
+            2 => self.pos.z,
+            3 => self.pos.w,
+            _ => panic!("vertex position index out of range: {}", index),
+        }
+    }
+
+    pub fn lerp(&self, other: &Vertex, lerp_amt: f32) -> Vertex {
+        Vertex::new_with_pos_and_varyings(self.pos.lerp(&other.pos, lerp_amt), self.tex_coords.lerp(&other.tex_coords, lerp_amt), self.normal.lerp(&other.normal, lerp_amt), self.varyings.lerp(&other.varyings, lerp_amt))
     }
 
-    pub fn calc_double_area(&self, v1: &Vertex, v2: &Vertex) -> i32 {
-        let x1 = (v1.pos.x as i32 - self.pos.x as i32) as i32;
-        let y1 = (v1.pos.y as i32 - self.pos.y as i32) as i32;
-        let x2 = (v2.pos.x as i32 - self.pos.x as i32) as i32;
-        let y2 = (v2.pos.y as i32 - self.pos.y as i32) as i32;
+    pub fn is_inside_view_frustum(&self) -> bool {
+        self.pos.x.abs() <= self.pos.w.abs() &&
+        self.pos.y.abs() <= self.pos.w.abs() &&
+        self.pos.z.abs() <= self.pos.w.abs()
+    }
+
+    //NOTE(dustin): twice the signed area in fixed point, exact for vertices that are snapped to the sub pixel grid
+    pub fn calc_double_area(&self, v1: &Vertex, v2: &Vertex) -> i64 {
+        let x1 = v1.get_fixed_x() - self.get_fixed_x();
+        let y1 = v1.get_fixed_y() - self.get_fixed_y();
+        let x2 = v2.get_fixed_x() - self.get_fixed_x();
+        let y2 = v2.get_fixed_y() - self.get_fixed_y();
+
+        x1 * y2 - x2 * y1
+    }
+
+    pub fn get_fixed_x(&self) -> i64 {
+        (self.pos.x * SUBPIXEL_SCALE as f32).round() as i64
+    }
+
+    pub fn get_fixed_y(&self) -> i64 {
+        (self.pos.y * SUBPIXEL_SCALE as f32).round() as i64
+    }
 
-        (x1 * y2 - x2 * y1)
+    pub fn get_fixed_pos(&self) -> (i64, i64) {
+        (self.get_fixed_x(), self.get_fixed_y())
     }
 
     //TODO(dustin): fix this!
     pub fn transform(&self, transform: &Matrix4f32) -> Vertex {
-        Vertex::new_with_pos_and_texcoords(transform.transform(&self.pos), Vector4f32{x: self.tex_coords.x, y: self.tex_coords.y, z: self.tex_coords.z, w: self.tex_coords.w})
+        Vertex::new_with_pos_and_varyings(transform.transform(&self.pos), self.tex_coords, self.normal, self.varyings)
     }
 
     pub fn perspective_divide(&self) -> Vertex {
-        Vertex::new_with_pos_and_texcoords(Vector4f32{ x: self.pos.x / self.pos.w, y: self.pos.y / self.pos.w, z: self.pos.z / self.pos.w, w: self.pos.w}, Vector4f32{x: self.tex_coords.x, y: self.tex_coords.y, z: self.tex_coords.z, w: self.tex_coords.w})
+        Vertex::new_with_pos_and_varyings(Vector4f32{ x: self.pos.x / self.pos.w, y: self.pos.y / self.pos.w, z: self.pos.z / self.pos.w, w: self.pos.w}, self.tex_coords, self.normal, self.varyings)
     }
 }
@@ -0,0 +1,254 @@
+use orbclient;
+use std;
+
+use blend::BlendState;
+use depth::DepthFunc;
+use halfspace;
+use interpolate::Interpolator;
+use primitive::edge::Edge;
+use primitive::vertex::{Vertex, SUBPIXEL_SCALE};
+use shader::{Fragment, FragmentShader, Varyings};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CullMode {
+    None,
+    Front,
+    Back
+}
+
+//NOTE(dustin): the winding of front faces as seen on screen
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrontFace {
+    Clockwise,
+    CounterClockwise
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RasterState {
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace
+}
+
+impl RasterState {
+    pub fn new() -> RasterState {
+        RasterState {
+            cull_mode: CullMode::Back,
+            front_face: FrontFace::Clockwise
+        }
+    }
+
+    pub fn init_cull_mode(mut self, cull_mode: CullMode) -> RasterState {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    pub fn init_front_face(mut self, front_face: FrontFace) -> RasterState {
+        self.front_face = front_face;
+        self
+    }
+}
+
+impl Default for RasterState {
+    fn default() -> RasterState {
+        RasterState::new()
+    }
+}
+
+//NOTE(dustin): screen space triangle after clipping, culling and perspective divide, sorted by y
+pub struct ScreenTriangle {
+    pub min_vert: Vertex,
+    pub mid_vert: Vertex,
+    pub max_vert: Vertex,
+    pub side: bool,
+    pub front_facing: bool
+}
+
+impl ScreenTriangle {
+    //NOTE(dustin): the winding and the covered pixels are decided on the positions snapped to the sub pixel grid, so they do
+    // not depend on float rounding. the attributes are interpolated from the unsnapped positions. triangles that are culled
+    // by the state or have no area after snapping give None
+    pub fn new(v1: Vertex, v2: Vertex, v3: Vertex, state: &RasterState) -> Option<ScreenTriangle> {
+        let mut min_vert = v1;
+        let mut mid_vert = v2;
+        let mut max_vert = v3;
+
+        //NOTE(dustin): screen space y points down, so a negative area is clockwise on screen
+        let area = min_vert.calc_double_area(&max_vert, &mid_vert);
+        if area == 0 {
+            return None;
+        }
+
+        let front_facing = (area < 0) == (state.front_face == FrontFace::Clockwise);
+        let is_culled = match state.cull_mode {
+            CullMode::None => false,
+            CullMode::Front => front_facing,
+            CullMode::Back => !front_facing,
+        };
+        if is_culled {
+            return None;
+        }
+
+        if max_vert.pos.y < mid_vert.pos.y {
+            std::mem::swap(&mut mid_vert, &mut max_vert);
+        }
+
+        if mid_vert.pos.y < min_vert.pos.y {
+            std::mem::swap(&mut mid_vert, &mut min_vert);
+        }
+
+        if max_vert.pos.y < mid_vert.pos.y {
+            std::mem::swap(&mut max_vert, &mut mid_vert);
+        }
+
+        let side = min_vert.calc_double_area(&max_vert, &mid_vert) >= 0;
+
+        Some(ScreenTriangle {
+            min_vert,
+            mid_vert,
+            max_vert,
+            side,
+            front_facing
+        })
+    }
+
+    //NOTE(dustin): first and one past the last scan line touched, same rounding as Edge
+    pub fn get_rows(&self) -> (i32, i32) {
+        self.get_sample_rows(0)
+    }
+
+    //NOTE(dustin): the rows with a sample inside the triangle, when samples are up to reach 1/16 pixel from the pixel center
+    pub fn get_sample_rows(&self, reach: i32) -> (i32, i32) {
+        let start_y = self.min_vert.get_fixed_y() - reach as i64;
+        let end_y = self.max_vert.get_fixed_y() + reach as i64;
+        ((start_y + SUBPIXEL_SCALE - 1).div_euclid(SUBPIXEL_SCALE) as i32, (end_y + SUBPIXEL_SCALE - 1).div_euclid(SUBPIXEL_SCALE) as i32)
+    }
+
+    //NOTE(dustin): first and one past the last column that can have a sample inside the triangle, not tight on purpose
+    pub fn get_sample_columns(&self, reach: i32) -> (i32, i32) {
+        let fixed_x = [self.min_vert.get_fixed_x(), self.mid_vert.get_fixed_x(), self.max_vert.get_fixed_x()];
+        let start_x = fixed_x.iter().min().unwrap() - reach as i64;
+        let end_x = fixed_x.iter().max().unwrap() + reach as i64;
+        ((start_x + SUBPIXEL_SCALE - 1).div_euclid(SUBPIXEL_SCALE) as i32, (end_x.div_euclid(SUBPIXEL_SCALE) + 1) as i32)
+    }
+
+    //NOTE(dustin): average clip space w of the corners, larger is further away
+    pub fn get_view_depth(&self) -> f32 {
+        (self.min_vert.pos.w + self.mid_vert.pos.w + self.max_vert.pos.w) / 3_f32
+    }
+}
+
+//NOTE(dustin): both give the same pixels, Scanline walks the edges row by row and HalfSpace tests blocks of pixels
+// against the edge functions, see halfspace. multisampled targets always use HalfSpace
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RasterAlgorithm {
+    Scanline,
+    HalfSpace
+}
+
+//NOTE(dustin): rasterizes into the rectangle min_x..max_x, min_y..max_y of a framebuffer, color and depth only hold this
+// rectangle row by row with all samples of a pixel next to each other like in Framebuffer
+pub struct Rasterizer<'a> {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+    pub samples: &'a [(i32, i32)],
+    pub color: &'a mut [orbclient::Color],
+    pub depth: &'a mut [f32],
+
+    pub depth_func: DepthFunc,
+    pub depth_write: bool,
+    pub blend: BlendState,
+    pub algorithm: RasterAlgorithm
+}
+
+impl<'a> Rasterizer<'a> {
+    pub fn scan_triangle<F: FragmentShader>(&mut self, triangle: &ScreenTriangle, shader: &F) {
+        let interpolator = Interpolator::new(&triangle.min_vert, &triangle.mid_vert, &triangle.max_vert);
+        let mut fragment = Fragment {
+            x: 0,
+            y: 0,
+            depth: 0_f32,
+            w: 0_f32,
+            front_facing: triangle.front_facing,
+            varyings: Varyings::new(interpolator.varying_count),
+            interpolator: &interpolator
+        };
+
+        match self.algorithm {
+            RasterAlgorithm::Scanline if self.samples.len() == 1 => {
+                let top_to_bottom = Edge::new(&triangle.min_vert, &triangle.max_vert);
+                let top_to_middle = Edge::new(&triangle.min_vert, &triangle.mid_vert);
+                let middle_to_bottom = Edge::new(&triangle.mid_vert, &triangle.max_vert);
+
+                self.scan_edges(&top_to_bottom, &top_to_middle, triangle.side, &mut fragment, shader);
+                self.scan_edges(&top_to_bottom, &middle_to_bottom, triangle.side, &mut fragment, shader);
+            },
+            _ => halfspace::scan_triangle(self, triangle, &mut fragment, shader),
+        }
+    }
+
+    //NOTE(dustin): the attributes are evaluated from the planes of the interpolator instead of stepped along the edges,
+    // so the result of a pixel does not depend on the order the pixels are visited in. bit idx of coverage is set if
+    // sample idx is inside the triangle, depth is tested per covered sample but the shader runs once at the pixel center
+    pub fn shade_pixel<F: FragmentShader>(&mut self, fragment: &mut Fragment, idx_x: i32, idx_y: i32, coverage: u32, shader: &F) {
+        let samples = self.samples;
+        let sample_count = samples.len();
+        let data_idx = ((idx_y - self.min_y) * (self.max_x - self.min_x) + idx_x - self.min_x) as usize * sample_count;
+        let interpolator = fragment.interpolator;
+        let dist_x = idx_x as f32 - interpolator.origin_x;
+        let dist_y = idx_y as f32 - interpolator.origin_y;
+
+        let depth = interpolator.depth[0] + interpolator.depth_step_x * dist_x + interpolator.depth_step_y * dist_y;
+        let sample_depth = |sample_idx: usize| {
+            let (offset_x, offset_y) = samples[sample_idx];
+            depth + (interpolator.depth_step_x * offset_x as f32 + interpolator.depth_step_y * offset_y as f32) / SUBPIXEL_SCALE as f32
+        };
+
+        let mut passed = 0;
+        for sample_idx in 0..sample_count {
+            if coverage & (1 << sample_idx) != 0 && self.depth_func.compare(sample_depth(sample_idx), self.depth[data_idx + sample_idx]) {
+                passed |= 1 << sample_idx;
+            }
+        }
+        if passed == 0 {
+            return;
+        }
+
+        let one_over_z = interpolator.one_over_z[0] + interpolator.one_over_step_zx * dist_x + interpolator.one_over_step_zy * dist_y;
+        let w = 1_f32 / one_over_z;
+        fragment.x = idx_x;
+        fragment.y = idx_y;
+        fragment.depth = depth;
+        fragment.w = w;
+        for idx in 0..interpolator.varying_count {
+            let varying = interpolator.varyings[idx][0] + interpolator.varying_step_x[idx] * dist_x + interpolator.varying_step_y[idx] * dist_y;
+            fragment.varyings.values[idx] = varying * w;
+        }
+
+        match shader.shade(fragment) {
+            Some(color) if !self.blend.is_discarded(color) => {
+                for sample_idx in (0..sample_count).filter(|sample_idx| passed & (1 << sample_idx) != 0) {
+                    if self.depth_write {
+                        self.depth[data_idx + sample_idx] = sample_depth(sample_idx);
+                    }
+                    self.color[data_idx + sample_idx] = self.blend.mode.blend(color, self.color[data_idx + sample_idx]);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn scan_edges<F: FragmentShader>(&mut self, long: &Edge, short: &Edge, side: bool, fragment: &mut Fragment, shader: &F) {
+        let (left, right) = if side { (short, long) } else { (long, short) };
+
+        for idx_y in short.start_y.max(self.min_y)..short.end_y.min(self.max_y) {
+            let min_x = left.calc_pixel_x(idx_y).max(self.min_x);
+            let max_x = right.calc_pixel_x(idx_y).min(self.max_x);
+
+            for idx_x in min_x..max_x {
+                self.shade_pixel(fragment, idx_x, idx_y, 1, shader);
+            }
+        }
+    }
+}
@@ -1,149 +1,630 @@
-use orbclient::{self, EventIter, Renderer};
-use std;
-
-use interpolate::Interpolator;
+use orbclient;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use blend::{BlendMode, BlendState};
+use camera::Camera;
+use clip;
+use cull::{Aabb, CullStats, Frustum};
+use depth::{DepthBuffer, DepthFunc};
+use line::{self, LineColor, LineRasterizer, PolygonMode, ScreenPoint};
+use light::{Lighting, LightingFragmentShader, LightingMaterialShader, LightingVertexShader, Reflectance, ShadingMode};
+use model::material::Material;
 use model::mesh::Mesh;
-use primitive::edge::Edge;
+use model::multimesh::MultiMesh;
+use postprocess::{PostChain, PostPass};
 use primitive::matrix::Matrix4f32;
+use primitive::vector::Vector4f32;
 use primitive::vertex::Vertex;
+use raster::{RasterAlgorithm, RasterState, Rasterizer, ScreenTriangle};
+use scene::Scene;
+use shader::{FragmentShader, MaterialShader, TextureShader, TransformShader, Varyings, VertexShader};
+use target::framebuffer::Framebuffer;
+use target::multisample::Antialiasing;
 use texture::bitmap::BitmapTexture;
+use texture::sampler::Sampler;
+use tile;
+
+//NOTE(dustin): a textured mesh for draw_transparent
+pub struct TransparentDraw<'a> {
+    pub mesh: &'a Mesh,
+    pub transform: Matrix4f32,
+    pub texture: &'a BitmapTexture,
+    pub blend_mode: BlendMode
+}
 
 pub struct RenderContext {
-    window: orbclient::Window,
+    target: Framebuffer,
+    resolved: Option<Framebuffer>,
+    antialiasing: Antialiasing,
+    worker_count: usize,
+    sampler: Sampler,
+    blend: BlendState,
+    culling: bool,
+    cull_stats: CullStats,
+    polygon_mode: PolygonMode,
+    wire_color: orbclient::Color,
+    raster_state: RasterState,
+    raster_algorithm: RasterAlgorithm,
+    post_chain: PostChain,
+    is_frame_finished: bool,
 }
 
 impl RenderContext {
-    pub fn new(width: u32, height: u32, title: &str) -> RenderContext {
-        let orb_window = orbclient::Window::new_flags(100, 100, width, height, title, true).unwrap();
-        RenderContext{window: orb_window}
+    pub fn new(width: u32, height: u32) -> RenderContext {
+        RenderContext::from_target(Framebuffer::new(width as i32, height as i32))
+    }
+
+    //NOTE(dustin): Msaa(count) draws into a target with count samples per pixel, Ssaa(factor) into one that is factor times
+    // wider and higher. draws go into get_target, resolve averages it into get_output which has the requested size
+    pub fn with_antialiasing(width: u32, height: u32, antialiasing: Antialiasing) -> RenderContext {
+        let (width, height) = (width as i32, height as i32);
+        let target = match antialiasing {
+            Antialiasing::None => Framebuffer::new(width, height),
+            Antialiasing::Msaa(sample_count) => Framebuffer::new_multisampled(width, height, sample_count),
+            Antialiasing::Ssaa(factor) => Framebuffer::new(width * factor.get_factor(), height * factor.get_factor()),
+        };
+
+        let mut render_context = RenderContext::from_target(target);
+        if antialiasing != Antialiasing::None {
+            render_context.resolved = Some(Framebuffer::new(width, height));
+            render_context.antialiasing = antialiasing;
+        }
+        render_context
     }
 
+    pub fn from_target(target: Framebuffer) -> RenderContext {
+        RenderContext{target, resolved: None, antialiasing: Antialiasing::None, worker_count: 1, sampler: Sampler::new(), blend: BlendState::new(), culling: true, cull_stats: CullStats::new(),
+            polygon_mode: PolygonMode::Fill, wire_color: orbclient::Color { data: 0xFFFFFFFF }, raster_state: RasterState::new(),
+            raster_algorithm: RasterAlgorithm::Scanline, post_chain: PostChain::new(), is_frame_finished: false}
+    }
+
+    //NOTE(dustin): the size of get_output, with Ssaa the target is larger
     pub fn get_height(&self) -> u32 {
-        self.window.height()
+        self.get_output().height as u32
     }
 
     pub fn get_width(&self) -> u32 {
-        self.window.width()
+        self.get_output().width as u32
+    }
+
+    pub fn get_antialiasing(&self) -> Antialiasing {
+        self.antialiasing
+    }
+
+    pub fn get_target(&self) -> &Framebuffer {
+        &self.target
+    }
+
+    pub fn get_target_mut(&mut self) -> &mut Framebuffer {
+        &mut self.target
+    }
+
+    pub fn into_target(self) -> Framebuffer {
+        self.target
+    }
+
+    //NOTE(dustin): averages the samples of the target into the output, does nothing without antialiasing
+    pub fn resolve(&mut self) {
+        if let Some(ref mut resolved) = self.resolved {
+            self.target.resolve(resolved);
+        }
+    }
+
+    //NOTE(dustin): the target without antialiasing, else the result of the last resolve
+    pub fn get_output(&self) -> &Framebuffer {
+        match self.resolved {
+            Some(ref resolved) => resolved,
+            None => &self.target,
+        }
+    }
+
+    //NOTE(dustin): the passes run on the output in the order they are added, see postprocess
+    pub fn add_post_pass<P: PostPass + 'static>(&mut self, pass: P) {
+        self.post_chain.add(pass);
+    }
+
+    pub fn clear_post_passes(&mut self) {
+        self.post_chain.clear();
+    }
+
+    pub fn get_post_pass_count(&self) -> usize {
+        self.post_chain.len()
+    }
+
+    //NOTE(dustin): runs the post passes on the output, without antialiasing that is the target itself
+    pub fn post_process(&mut self) {
+        let output = match self.resolved {
+            Some(ref mut resolved) => resolved,
+            None => &mut self.target,
+        };
+        self.post_chain.apply(output);
+    }
+
+    //NOTE(dustin): call once all draws of a frame are done and before presenting get_output. only the first call after
+    // clear resolves and post processes, so the passes never run twice on the same frame
+    pub fn finish_frame(&mut self) {
+        if self.is_frame_finished {
+            return;
+        }
+
+        self.resolve();
+        self.post_process();
+        self.is_frame_finished = true;
+    }
+
+    //NOTE(dustin): finishes the frame if that did not happen yet
+    pub fn into_output(mut self) -> Framebuffer {
+        self.finish_frame();
+        match self.resolved {
+            Some(resolved) => resolved,
+            None => self.target,
+        }
+    }
+
+    //NOTE(dustin): 1 rasterizes on the calling thread, more bins the triangles of every draw into tiles that are
+    // rasterized on that many threads
+    pub fn set_worker_count(&mut self, worker_count: usize) {
+        self.worker_count = worker_count.max(1);
     }
 
-    pub fn events(&mut self) -> EventIter {
-        self.window.events()
+    pub fn get_worker_count(&self) -> usize {
+        self.worker_count
     }
 
+    //NOTE(dustin): also starts a new frame for the cull stats
     pub fn clear(&mut self) {
-        self.window.set(orbclient::Color { data: 0xFF220CE8});
+        self.target.clear(orbclient::Color { data: 0xFF220CE8});
+        self.cull_stats = CullStats::new();
+        self.is_frame_finished = false;
     }
 
-    pub fn sync(&mut self) {
-        self.window.sync();
+    pub fn set_depth_func(&mut self, func: DepthFunc) {
+        self.target.depth.func = func;
+    }
+
+    pub fn set_depth_write(&mut self, write: bool) {
+        self.target.depth.write = write;
+    }
+
+    pub fn get_depth_buffer(&self) -> &DepthBuffer {
+        &self.target.depth
+    }
+
+    //NOTE(dustin): used by the built in shaders of draw_mesh, draw_mesh_lit, draw_multi_mesh and draw_scene
+    pub fn set_sampler(&mut self, sampler: Sampler) {
+        self.sampler = sampler;
+    }
+
+    pub fn get_sampler(&self) -> Sampler {
+        self.sampler
+    }
+
+    pub fn set_blend_state(&mut self, blend: BlendState) {
+        self.blend = blend;
+    }
+
+    pub fn get_blend_state(&self) -> BlendState {
+        self.blend
+    }
+
+    //NOTE(dustin): cull mode and front face for the following draws, materials with their own raster state replace it
+    // in draw_multi_mesh and draw_scene
+    pub fn set_raster_state(&mut self, raster_state: RasterState) {
+        self.raster_state = raster_state;
+    }
+
+    pub fn get_raster_state(&self) -> RasterState {
+        self.raster_state
+    }
+
+    //NOTE(dustin): both algorithms give the same pixels, only the speed differs
+    pub fn set_raster_algorithm(&mut self, algorithm: RasterAlgorithm) {
+        self.raster_algorithm = algorithm;
+    }
+
+    pub fn get_raster_algorithm(&self) -> RasterAlgorithm {
+        self.raster_algorithm
+    }
+
+    //NOTE(dustin): draw_mesh, draw_mesh_lit, draw_multi_mesh, draw_transparent and draw_scene skip meshes whose bounds are
+    // outside the view frustum, draw_mesh_shaded and draw_triangle can not know the transform and always draw
+    pub fn set_frustum_culling(&mut self, culling: bool) {
+        self.culling = culling;
+    }
+
+    pub fn get_cull_stats(&self) -> CullStats {
+        self.cull_stats
+    }
+
+    //NOTE(dustin): applies to every mesh and triangle draw, Line draws each edge once and Point each vertex once in the wire color,
+    // back faces are not culled and the depth state of the target is used
+    pub fn set_polygon_mode(&mut self, mode: PolygonMode) {
+        self.polygon_mode = mode;
+    }
+
+    pub fn get_polygon_mode(&self) -> PolygonMode {
+        self.polygon_mode
+    }
+
+    pub fn set_wire_color(&mut self, color: orbclient::Color) {
+        self.wire_color = color;
+    }
+
+    //NOTE(dustin): from and to are transformed into clip space by transform and clipped, the blend state applies,
+    // without depth_test the depth buffer is neither read nor written
+    pub fn draw_line(&mut self, from: &Vector4f32, to: &Vector4f32, transform: &Matrix4f32, color: LineColor, depth_test: bool) {
+        let (from_color, to_color) = color.get_colors();
+        self.draw_clip_line(&transform.transform(from), &transform.transform(to), from_color, to_color, depth_test);
+    }
+
+    pub fn draw_point(&mut self, pos: &Vector4f32, transform: &Matrix4f32, color: orbclient::Color, size: i32, depth_test: bool) {
+        self.draw_clip_point(&transform.transform(pos), color, size, depth_test);
+    }
+
+    //NOTE(dustin): the 12 edges of a box, e.g. mesh.aabb with the transform of the mesh
+    pub fn draw_aabb(&mut self, aabb: &Aabb, transform: &Matrix4f32, color: orbclient::Color, depth_test: bool) {
+        let corner = |idx: usize| Vector4f32::new(
+            if idx & 1 == 0 { aabb.min.x } else { aabb.max.x },
+            if idx & 2 == 0 { aabb.min.y } else { aabb.max.y },
+            if idx & 4 == 0 { aabb.min.z } else { aabb.max.z },
+            1_f32);
+
+        for idx in 0..8 {
+            for &bit in [1, 2, 4].iter() {
+                if idx & bit == 0 {
+                    self.draw_line(&corner(idx), &corner(idx | bit), transform, LineColor::Constant(color), depth_test);
+                }
+            }
+        }
     }
 
     pub fn draw_mesh(&mut self, mesh: &Mesh, transform: &Matrix4f32, texture: &BitmapTexture) {
-        for idx in (0..mesh.indices.len()).step_by(3) {
-            let v1 = &mesh.vertices[mesh.indices[idx as usize] as usize].transform(&transform);
-            let v2 = &mesh.vertices[mesh.indices[(idx + 1) as usize] as usize].transform(&transform);
-            let v3 = &mesh.vertices[mesh.indices[(idx + 2) as usize] as usize].transform(&transform);
+        if self.cull_mesh(mesh, transform) {
+            return;
+        }
+
+        self.draw_mesh_shaded(mesh, &TransformShader::new(transform), &TextureShader::with_sampler(texture, self.sampler));
+    }
+
+    pub fn draw_multi_mesh(&mut self, multi_mesh: &MultiMesh, transform: &Matrix4f32) {
+        let default_material = Material::new("default");
+        let vertex_shader = TransformShader::new(transform);
+        let get_material = |sub_mesh_idx: usize| multi_mesh.get_material(&multi_mesh.sub_meshes[sub_mesh_idx]).unwrap_or(&default_material);
+
+        //NOTE(dustin): opaque sub meshes first, then the blended ones sorted back to front
+        let mut transparent = Vec::new();
+        for (sub_mesh_idx, sub_mesh) in multi_mesh.sub_meshes.iter().enumerate() {
+            if self.cull_mesh(&sub_mesh.mesh, transform) {
+                continue;
+            }
+
+            let material = get_material(sub_mesh_idx);
+            let raster_state = self.raster_state;
+            self.raster_state = material.raster_state.unwrap_or(raster_state);
+
+            if self.is_blended(material) {
+                let start = transparent.len();
+                self.setup_mesh(&sub_mesh.mesh, &vertex_shader, &mut transparent);
+                RenderContext::tag_triangles(&mut transparent[start..], sub_mesh_idx);
+            } else {
+                self.draw_mesh_shaded(&sub_mesh.mesh, &vertex_shader, &MaterialShader::new(material, self.sampler));
+            }
 
-            self.draw_triangle(v1, v2, v3, &texture);
+            self.raster_state = raster_state;
         }
+
+        let sampler = self.sampler;
+        self.rasterize_sorted(transparent, |sub_mesh_idx| (MaterialShader::new(get_material(sub_mesh_idx), sampler), BlendMode::AlphaBlend));
     }
 
-    pub fn draw_triangle(&mut self, v1: &Vertex, v2: &Vertex, v3: &Vertex, texture: &BitmapTexture) {
+    //NOTE(dustin): the triangles of all draws are sorted back to front together and drawn without depth writes,
+    // so call it after the opaque geometry
+    pub fn draw_transparent(&mut self, draws: &[TransparentDraw]) {
+        let mut triangles = Vec::new();
+        for (draw_idx, draw) in draws.iter().enumerate() {
+            if self.cull_mesh(draw.mesh, &draw.transform) {
+                continue;
+            }
 
-        //TODO(dustin): optimisation do not calculate/init every time
-        let screen_space_transform = Matrix4f32::new().init_sreenspace_transform(self.get_width() as f32 / 2f32, self.get_height() as f32 / 2f32);
+            let start = triangles.len();
+            self.setup_mesh(draw.mesh, &TransformShader::new(&draw.transform), &mut triangles);
+            RenderContext::tag_triangles(&mut triangles[start..], draw_idx);
+        }
 
-        let mut min_vert = v1.transform(&screen_space_transform).perspective_divide();
-        let mut mid_vert = v2.transform(&screen_space_transform).perspective_divide();
-        let mut max_vert = v3.transform(&screen_space_transform).perspective_divide();
+        let sampler = self.sampler;
+        self.rasterize_sorted(triangles, |draw_idx| (TextureShader::with_sampler(draws[draw_idx].texture, sampler), draws[draw_idx].blend_mode));
+    }
 
-        if min_vert.calc_double_area(&max_vert, &mid_vert) >= 0 {
+    pub fn draw_mesh_lit(&mut self, mesh: &Mesh, model: &Matrix4f32, view_projection: &Matrix4f32, texture: &BitmapTexture, lighting: &Lighting, mode: ShadingMode) {
+        if self.cull_mesh(mesh, &view_projection.mul(model)) {
             return;
         }
 
-        if max_vert.pos.y < mid_vert.pos.y {
-            std::mem::swap(&mut mid_vert, &mut max_vert);
+        let vertex_shader = LightingVertexShader::new(model, view_projection, lighting, mode);
+        let fragment_shader = LightingFragmentShader::new(texture, self.sampler, lighting, mode);
+
+        self.draw_mesh_shaded(mesh, &vertex_shader, &fragment_shader);
+    }
+
+    //NOTE(dustin): every node with a mesh is drawn with its material and the lights of all nodes, transparent
+    // materials are sorted back to front like in draw_multi_mesh. without lights or shading the materials are unlit
+    pub fn draw_scene(&mut self, scene: &Scene, camera: &Camera) {
+        let world = scene.calc_world_matrices();
+        let view_projection = camera.get_view_projection();
+        let default_material = Material::new("default");
+        let sampler = self.sampler;
+
+        let transforms: Vec<Matrix4f32> = world.iter().map(|model| view_projection.mul(model)).collect();
+
+        let mut lighting = Lighting::new(camera.position);
+        lighting.lights = scene.collect_lights(&world);
+        let lighting = &lighting;
+
+        let get_material = |node_idx: usize| scene.get_material(&scene.nodes[node_idx]).unwrap_or(&default_material);
+
+        match scene.shading {
+            Some(mode) if !lighting.lights.is_empty() => self.draw_scene_nodes(scene, &transforms, &default_material,
+                |node_idx| LightingVertexShader::new(&world[node_idx], &view_projection, lighting, mode)
+                    .init_reflectance(Reflectance::from_material(get_material(node_idx))),
+                |material| LightingMaterialShader::new(material, sampler, lighting, mode)),
+            _ => self.draw_scene_nodes(scene, &transforms, &default_material,
+                |node_idx| TransformShader::new(&transforms[node_idx]),
+                |material| MaterialShader::new(material, sampler)),
         }
+    }
 
-        if mid_vert.pos.y < min_vert.pos.y {
-            std::mem::swap(&mut mid_vert, &mut min_vert);
+    pub fn draw_mesh_shaded<V: VertexShader, F: FragmentShader>(&mut self, mesh: &Mesh, vertex_shader: &V, fragment_shader: &F) {
+        let mut triangles = Vec::with_capacity(mesh.indices.len() / 3);
+        self.setup_mesh(mesh, vertex_shader, &mut triangles);
+
+        let triangles: Vec<ScreenTriangle> = triangles.into_iter().map(|(triangle, _)| triangle).collect();
+        self.rasterize(&triangles, fragment_shader);
+    }
+
+    pub fn draw_triangle(&mut self, v1: &Vertex, v2: &Vertex, v3: &Vertex, texture: &BitmapTexture) {
+        //NOTE(dustin): the vertices are already in clip space
+        let identity = Matrix4f32::new().init_translation(0_f32, 0_f32, 0_f32);
+        self.draw_triangle_shaded(v1, v2, v3, &TransformShader::new(&identity), &TextureShader::with_sampler(texture, self.sampler));
+    }
+
+    pub fn draw_triangle_shaded<V: VertexShader, F: FragmentShader>(&mut self, v1: &Vertex, v2: &Vertex, v3: &Vertex, vertex_shader: &V, fragment_shader: &F) {
+        let v1 = RenderContext::shade_vertex(v1, vertex_shader);
+        let v2 = RenderContext::shade_vertex(v2, vertex_shader);
+        let v3 = RenderContext::shade_vertex(v3, vertex_shader);
+        if self.polygon_mode != PolygonMode::Fill {
+            self.draw_wire(&[v1, v2, v3], &[0, 1, 2]);
+            return;
         }
 
-        if max_vert.pos.y < mid_vert.pos.y {
-            std::mem::swap(&mut max_vert, &mut mid_vert);
+        let mut triangles = Vec::new();
+        self.setup_triangle(&v1, &v2, &v3, &mut triangles);
+
+        let triangles: Vec<ScreenTriangle> = triangles.into_iter().map(|(triangle, _)| triangle).collect();
+        self.rasterize(&triangles, fragment_shader);
+    }
+
+    //NOTE(dustin): transforms are the model view projection matrices of the nodes, only used for culling
+    fn draw_scene_nodes<'a, V, F, VS, FS>(&mut self, scene: &'a Scene, transforms: &[Matrix4f32], default_material: &'a Material, vertex_shader_for: VS, fragment_shader_for: FS)
+        where V: VertexShader, F: FragmentShader, VS: Fn(usize) -> V, FS: Fn(&'a Material) -> F {
+        let get_material = |node_idx: usize| scene.get_material(&scene.nodes[node_idx]).unwrap_or(default_material);
+
+        let mut transparent = Vec::new();
+        for (node_idx, node) in scene.nodes.iter().enumerate() {
+            let mesh = match scene.get_mesh(node) {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            if self.cull_mesh(mesh, &transforms[node_idx]) {
+                continue;
+            }
+
+            let material = get_material(node_idx);
+            let raster_state = self.raster_state;
+            self.raster_state = material.raster_state.unwrap_or(raster_state);
+
+            if self.is_blended(material) {
+                let start = transparent.len();
+                self.setup_mesh(mesh, &vertex_shader_for(node_idx), &mut transparent);
+                RenderContext::tag_triangles(&mut transparent[start..], node_idx);
+            } else {
+                self.draw_mesh_shaded(mesh, &vertex_shader_for(node_idx), &fragment_shader_for(material));
+            }
+
+            self.raster_state = raster_state;
         }
 
-        self.scan_triangle(&min_vert, &mid_vert, &max_vert, min_vert.calc_double_area(&max_vert, &mid_vert) >= 0, texture);
+        self.rasterize_sorted(transparent, |node_idx| (fragment_shader_for(get_material(node_idx)), BlendMode::AlphaBlend));
     }
 
-    fn scan_triangle(&mut self,  min_vert: &Vertex, mid_vert: &Vertex, max_vert: &Vertex, side: bool, texture: &BitmapTexture) {
+    //NOTE(dustin): a dissolve below 1 is always blended, an alpha map only without an alpha threshold, with one it is a cutout
+    fn is_blended(&self, material: &Material) -> bool {
+        material.dissolve < 1_f32 || (material.alpha_texture.is_some() && self.blend.alpha_threshold.is_none())
+    }
 
-        let interpolator = Interpolator::new(min_vert, mid_vert, max_vert);
-        let mut top_to_bottom = Edge::new(&interpolator, min_vert, max_vert, 0);
-        let mut top_to_middle = Edge::new(&interpolator, min_vert, mid_vert, 0);
-        let mut middle_to_bottom = Edge::new(&interpolator, mid_vert, max_vert, 1);
+    //NOTE(dustin): true if the mesh is outside the frustum of its model view projection matrix, counts into the cull stats
+    fn cull_mesh(&mut self, mesh: &Mesh, transform: &Matrix4f32) -> bool {
+        if self.culling {
+            let frustum = Frustum::from_matrix(transform);
+            if !frustum.intersects_sphere(&mesh.bounding_sphere) || !frustum.intersects_aabb(&mesh.aabb) {
+                self.cull_stats.objects_culled += 1;
+                self.cull_stats.triangles_culled += mesh.get_triangle_count();
+                return true;
+            }
+        }
 
-        self.scan_edges(&mut top_to_bottom, &mut top_to_middle, side, texture);
-        self.scan_edges(&mut top_to_bottom, &mut middle_to_bottom, side, texture);
+        self.cull_stats.objects_drawn += 1;
+        false
     }
 
-    fn scan_edges(&mut self, first: &mut Edge, second: &mut Edge, side: bool, texture: &BitmapTexture) {
+    fn shade_vertex<V: VertexShader>(vertex: &Vertex, vertex_shader: &V) -> Vertex {
+        let mut varyings = Varyings::new(0);
+        let pos = vertex_shader.shade(vertex, &mut varyings);
 
-        let start_y = second.start_y;
-        let end_y = second.end_y;
+        Vertex::new_with_pos_and_varyings(pos, vertex.tex_coords, vertex.normal, varyings)
+    }
 
-        let mut left = first;
-        let mut right = second;
+    //NOTE(dustin): the usize next to each triangle is an owner tag for rasterize_sorted, 0 until tag_triangles
+    // in line and point mode the wire is drawn right away and no triangles are added
+    fn setup_mesh<V: VertexShader>(&mut self, mesh: &Mesh, vertex_shader: &V, triangles: &mut Vec<(ScreenTriangle, usize)>) {
+        let vertices: Vec<Vertex> = mesh.vertices.iter().map(|vertex| RenderContext::shade_vertex(vertex, vertex_shader)).collect();
+        if self.polygon_mode != PolygonMode::Fill {
+            self.draw_wire(&vertices, &mesh.indices);
+            return;
+        }
+
+        for idx in (0..mesh.indices.len()).step_by(3) {
+            let v1 = &vertices[mesh.indices[idx] as usize];
+            let v2 = &vertices[mesh.indices[idx + 1] as usize];
+            let v3 = &vertices[mesh.indices[idx + 2] as usize];
+
+            self.setup_triangle(v1, v2, v3, triangles);
+        }
+    }
+
+    fn draw_wire(&mut self, vertices: &[Vertex], indices: &[i32]) {
+        let wire_color = self.wire_color;
 
-        if side {
-            std::mem::swap(&mut left, &mut right);
+        if self.polygon_mode == PolygonMode::Point {
+            let mut drawn = HashSet::new();
+            for &vertex_idx in indices.iter() {
+                if drawn.insert(vertex_idx) {
+                    self.draw_clip_point(&vertices[vertex_idx as usize].pos, wire_color, 1, true);
+                }
+            }
+            return;
+        }
+
+        let mut drawn = HashSet::new();
+        for triangle in indices.chunks(3) {
+            for &(start, end) in [(triangle[0], triangle[1]), (triangle[1], triangle[2]), (triangle[2], triangle[0])].iter() {
+                if drawn.insert((start.min(end), start.max(end))) {
+                    self.draw_clip_line(&vertices[start as usize].pos, &vertices[end as usize].pos, wire_color, wire_color, true);
+                }
+            }
+        }
+    }
+
+    fn draw_clip_line(&mut self, from: &Vector4f32, to: &Vector4f32, from_color: orbclient::Color, to_color: orbclient::Color, depth_test: bool) {
+        let (start, end) = match line::clip_line(from, to) {
+            Some(range) => range,
+            None => return,
+        };
+
+        //NOTE(dustin): clip space is before the divide, so the colors of the clipped end points are a plain lerp
+        let from_clipped = self.to_screen_point(&from.lerp(to, start));
+        let to_clipped = self.to_screen_point(&from.lerp(to, end));
+        let from_clipped_color = line::lerp_color(from_color, to_color, start);
+        let to_clipped_color = line::lerp_color(from_color, to_color, end);
+
+        let blend = self.blend;
+        let mut rasterizer = LineRasterizer { target: &mut self.target, depth_test, blend };
+        rasterizer.draw_line(&from_clipped, &to_clipped, from_clipped_color, to_clipped_color);
+    }
+
+    fn draw_clip_point(&mut self, pos: &Vector4f32, color: orbclient::Color, size: i32, depth_test: bool) {
+        if !line::is_point_inside(pos) {
+            return;
         }
 
-        for idx_y in start_y..end_y {
-            self.draw_scan_line(&left, &right, idx_y, texture);
-            left.step();
-            right.step();
+        let point = self.to_screen_point(pos);
+        let blend = self.blend;
+        let mut rasterizer = LineRasterizer { target: &mut self.target, depth_test, blend };
+        rasterizer.draw_point(&point, color, size);
+    }
+
+    fn to_screen_point(&self, pos: &Vector4f32) -> ScreenPoint {
+        let screen_space_transform = Matrix4f32::new().init_sreenspace_transform(self.target.width as f32 / 2f32, self.target.height as f32 / 2f32);
+        ScreenPoint::from_screen_space(&screen_space_transform.transform(pos))
+    }
+
+    fn tag_triangles(triangles: &mut [(ScreenTriangle, usize)], owner: usize) {
+        for triangle in triangles.iter_mut() {
+            triangle.1 = owner;
         }
     }
 
-    fn draw_scan_line(&mut self, left: &Edge, right: &Edge, idx_y: i32, texture: &BitmapTexture) {
+    fn setup_triangle(&self, v1: &Vertex, v2: &Vertex, v3: &Vertex, triangles: &mut Vec<(ScreenTriangle, usize)>) {
+        if v1.is_inside_view_frustum() && v2.is_inside_view_frustum() && v3.is_inside_view_frustum() {
+            self.push_screen_triangle(v1, v2, v3, triangles);
+            return;
+        }
 
-        let min_x = left.pos_x.ceil() as i32;
-        let max_x = right.pos_x.ceil()as i32;
-        let prestep_x = min_x as f32 - left.pos_x;
+        let mut vertices = vec![v1.clone(), v2.clone(), v3.clone()];
+        if !clip::clip_polygon(&mut vertices) {
+            return;
+        }
 
-        let dist_x = right.pos_x - left.pos_x;
-        let tex_coords_step_xx = (right.tex_coords_x - left.tex_coords_x) / dist_x;
-        let tex_coords_step_yx = (right.tex_coords_y - left.tex_coords_y) / dist_x;
-        let one_over_step_zx = (right.one_over_z - left.one_over_z) / dist_x;
+        for idx in 1..vertices.len() - 1 {
+            self.push_screen_triangle(&vertices[0], &vertices[idx], &vertices[idx + 1], triangles);
+        }
+    }
 
-        let mut tex_coords_x = left.tex_coords_x + tex_coords_step_xx * prestep_x;
-        let mut tex_coords_y = left.tex_coords_y + tex_coords_step_yx * prestep_x;
-        let mut one_over_z = left.one_over_z + one_over_step_zx * prestep_x;
+    fn push_screen_triangle(&self, v1: &Vertex, v2: &Vertex, v3: &Vertex, triangles: &mut Vec<(ScreenTriangle, usize)>) {
 
-        let ww = self.window.width();
-        let wh = self.window.height();
-        let data = self.window.data_mut();
+        //TODO(dustin): optimisation do not calculate/init every time
+        let screen_space_transform = Matrix4f32::new().init_sreenspace_transform(self.target.width as f32 / 2f32, self.target.height as f32 / 2f32);
 
-        for idx_x in min_x..max_x {
+        let min_vert = v1.transform(&screen_space_transform).perspective_divide();
+        let mid_vert = v2.transform(&screen_space_transform).perspective_divide();
+        let max_vert = v3.transform(&screen_space_transform).perspective_divide();
 
-            let data_idx = idx_y * ww as i32 + idx_x;
-            if data_idx >= data.len() as i32 || data_idx < 0 {
-                break;
+        if let Some(triangle) = ScreenTriangle::new(min_vert, mid_vert, max_vert, &self.raster_state) {
+            triangles.push((triangle, 0));
+        }
+    }
+
+    //NOTE(dustin): sorts back to front and rasterizes runs of the same owner with the shader and blend mode of
+    // shader_for(owner), depth writes are off and the alpha threshold of the current blend state is kept
+    fn rasterize_sorted<F: FragmentShader, S: Fn(usize) -> (F, BlendMode)>(&mut self, mut triangles: Vec<(ScreenTriangle, usize)>, shader_for: S) {
+        triangles.sort_by(|a, b| b.0.get_view_depth().partial_cmp(&a.0.get_view_depth()).unwrap_or(Ordering::Equal));
+        let (triangles, owners): (Vec<ScreenTriangle>, Vec<usize>) = triangles.into_iter().unzip();
+
+        let blend = self.blend;
+        let depth_write = self.target.depth.write;
+        self.target.depth.write = false;
+
+        let mut start = 0;
+        while start < triangles.len() {
+            let owner = owners[start];
+            let mut end = start + 1;
+            while end < triangles.len() && owners[end] == owner {
+                end += 1;
             }
 
-            let z = 1_f32 / one_over_z;
-            let src_x = ((tex_coords_x * z) * (texture.width - 1) as f32 + 0.5_f32) as i32;
-            let src_y = ((tex_coords_y * z) * (texture.height - 1) as f32 + 0.5_f32) as i32;
+            let (shader, mode) = shader_for(owner);
+            self.blend.mode = mode;
+            self.rasterize(&triangles[start..end], &shader);
+            start = end;
+        }
 
-//            self.window.pixel(idx_x, idx_y, texture.get_orb_pixel(src_x, src_y));
-            let new = texture.get_orb_pixel(src_x, src_y).data;
-            let old = &mut data[idx_y as usize * ww as usize + idx_x as usize].data;
-            *old = new;
+        self.blend = blend;
+        self.target.depth.write = depth_write;
+    }
+
+    fn rasterize<F: FragmentShader>(&mut self, triangles: &[ScreenTriangle], shader: &F) {
+        if self.worker_count > 1 {
+            tile::rasterize_tiled(self.worker_count, &mut self.target, triangles, shader, self.blend, self.raster_algorithm);
+            return;
+        }
 
-            one_over_z += one_over_step_zx;
-            tex_coords_x += tex_coords_step_xx;
-            tex_coords_y += tex_coords_step_yx;
+        let mut rasterizer = Rasterizer {
+            min_x: 0,
+            min_y: 0,
+            max_x: self.target.width,
+            max_y: self.target.height,
+            samples: &self.target.samples,
+            color: &mut self.target.color,
+            depth: &mut self.target.depth.data,
+
+            depth_func: self.target.depth.func,
+            depth_write: self.target.depth.write,
+            blend: self.blend,
+            algorithm: self.raster_algorithm
+        };
+
+        for triangle in triangles {
+            rasterizer.scan_triangle(triangle, shader);
         }
     }
 }
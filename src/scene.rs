@@ -0,0 +1,208 @@
+use light::{Light, ShadingMode};
+use model::material::Material;
+use model::mesh::Mesh;
+use primitive::matrix::Matrix4f32;
+use primitive::quaternion::Quaternion;
+use primitive::vector::Vector4f32;
+
+//NOTE(dustin): scale first, then rotation, then translation
+#[derive(Debug, Copy, Clone)]
+pub struct Transform {
+    pub translation: Vector4f32,
+    pub rotation: Quaternion,
+    pub scale: Vector4f32
+}
+
+impl Transform {
+    pub fn new() -> Transform {
+        Transform {
+            translation: Vector4f32::new(0_f32, 0_f32, 0_f32, 0_f32),
+            rotation: Quaternion::identity(),
+            scale: Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32)
+        }
+    }
+
+    //NOTE(dustin): None if the matrix has a zero scale, see Matrix4f32::decompose
+    pub fn from_matrix(matrix: &Matrix4f32) -> Option<Transform> {
+        matrix.decompose().map(|(translation, rotation, scale)| Transform {
+            translation,
+            rotation,
+            scale
+        })
+    }
+
+    pub fn init_translation(mut self, x: f32, y: f32, z: f32) -> Transform {
+        self.translation = Vector4f32::new(x, y, z, 0_f32);
+        self
+    }
+
+    pub fn init_rotation(mut self, rotation: Quaternion) -> Transform {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn init_scale(mut self, x: f32, y: f32, z: f32) -> Transform {
+        self.scale = Vector4f32::new(x, y, z, 0_f32);
+        self
+    }
+
+    pub fn to_matrix(&self) -> Matrix4f32 {
+        Matrix4f32::new().init_translation(self.translation.x, self.translation.y, self.translation.z)
+            .mul(&self.rotation.to_matrix())
+            .mul(&Matrix4f32::new().init_scale(self.scale.x, self.scale.y, self.scale.z))
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::new()
+    }
+}
+
+//NOTE(dustin): mesh and material are indices into the scene, so several nodes can share one mesh,
+// the light is given in node space and follows the node
+pub struct Node {
+    pub name: String,
+    pub transform: Transform,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub mesh: Option<usize>,
+    pub material: Option<usize>,
+    pub light: Option<Light>
+}
+
+impl Node {
+    pub fn new(_name: &str) -> Node {
+        Node {
+            name: _name.to_string(),
+            transform: Transform::new(),
+            parent: None,
+            children: Vec::new(),
+            mesh: None,
+            material: None,
+            light: None
+        }
+    }
+
+    pub fn init_transform(mut self, transform: Transform) -> Node {
+        self.transform = transform;
+        self
+    }
+
+    pub fn init_mesh(mut self, mesh: usize) -> Node {
+        self.mesh = Some(mesh);
+        self
+    }
+
+    pub fn init_material(mut self, material: usize) -> Node {
+        self.material = Some(material);
+        self
+    }
+
+    pub fn init_light(mut self, light: Light) -> Node {
+        self.light = Some(light);
+        self
+    }
+}
+
+//NOTE(dustin): nodes can only be attached to nodes that already exist, so a parent always comes before its children
+// and the world matrices are one pass over the nodes. shading None draws the materials unlit
+pub struct Scene {
+    pub nodes: Vec<Node>,
+    pub meshes: Vec<Mesh>,
+    pub materials: Vec<Material>,
+    pub shading: Option<ShadingMode>
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene {
+            nodes: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            shading: Some(ShadingMode::Gouraud)
+        }
+    }
+
+    pub fn add_mesh(&mut self, mesh: Mesh) -> usize {
+        self.meshes.push(mesh);
+        self.meshes.len() - 1
+    }
+
+    pub fn add_material(&mut self, material: Material) -> usize {
+        self.materials.push(material);
+        self.materials.len() - 1
+    }
+
+    pub fn add_node(&mut self, mut node: Node, parent: Option<usize>) -> usize {
+        let node_idx = self.nodes.len();
+        if let Some(parent_idx) = parent {
+            assert!(parent_idx < node_idx, "parent {} of node {} does not exist", parent_idx, node.name);
+            self.nodes[parent_idx].children.push(node_idx);
+        }
+
+        node.parent = parent;
+        node.children.clear();
+        self.nodes.push(node);
+
+        node_idx
+    }
+
+    pub fn get_node(&self, node_idx: usize) -> &Node {
+        &self.nodes[node_idx]
+    }
+
+    pub fn get_node_mut(&mut self, node_idx: usize) -> &mut Node {
+        &mut self.nodes[node_idx]
+    }
+
+    pub fn find_node(&self, name: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.name == name)
+    }
+
+    pub fn get_mesh(&self, node: &Node) -> Option<&Mesh> {
+        node.mesh.map(|idx| &self.meshes[idx])
+    }
+
+    pub fn get_material(&self, node: &Node) -> Option<&Material> {
+        node.material.map(|idx| &self.materials[idx])
+    }
+
+    //NOTE(dustin): indexed like nodes
+    pub fn calc_world_matrices(&self) -> Vec<Matrix4f32> {
+        let mut world: Vec<Matrix4f32> = Vec::with_capacity(self.nodes.len());
+        for node in self.nodes.iter() {
+            let local = node.transform.to_matrix();
+            let matrix = match node.parent {
+                Some(parent_idx) => world[parent_idx].mul(&local),
+                None => local,
+            };
+            world.push(matrix);
+        }
+
+        world
+    }
+
+    pub fn calc_world_matrix(&self, node_idx: usize) -> Matrix4f32 {
+        let node = &self.nodes[node_idx];
+        let local = node.transform.to_matrix();
+
+        match node.parent {
+            Some(parent_idx) => self.calc_world_matrix(parent_idx).mul(&local),
+            None => local,
+        }
+    }
+
+    //NOTE(dustin): the lights of all nodes in world space, world has to come from calc_world_matrices
+    pub fn collect_lights(&self, world: &[Matrix4f32]) -> Vec<Light> {
+        self.nodes.iter().enumerate()
+            .filter_map(|(node_idx, node)| node.light.map(|light| light.transform(&world[node_idx])))
+            .collect()
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Scene {
+        Scene::new()
+    }
+}
@@ -0,0 +1,173 @@
+use orbclient;
+
+use interpolate::Interpolator;
+use light::apply_light;
+use model::material::Material;
+use primitive::matrix::Matrix4f32;
+use primitive::vector::Vector4f32;
+use primitive::vertex::Vertex;
+use texture::bitmap::BitmapTexture;
+use texture::sampler::{Sampler, TexCoords};
+
+pub const MAX_VARYINGS: usize = 16;
+
+//NOTE(dustin): fixed size so vertices and fragments never allocate, only the first count values are used
+#[derive(Debug, Copy, Clone)]
+pub struct Varyings {
+    pub values: [f32; MAX_VARYINGS],
+    pub count: usize
+}
+
+impl Varyings {
+    pub fn new(_count: usize) -> Varyings {
+        assert!(_count <= MAX_VARYINGS, "at most {} varyings are supported", MAX_VARYINGS);
+        Varyings {
+            values: [0_f32; MAX_VARYINGS],
+            count: _count
+        }
+    }
+
+    pub fn from_slice(values: &[f32]) -> Varyings {
+        let mut varyings = Varyings::new(values.len());
+        varyings.values[..values.len()].copy_from_slice(values);
+        varyings
+    }
+
+    pub fn get(&self, index: usize) -> f32 {
+        self.values[index]
+    }
+
+    pub fn set(&mut self, index: usize, value: f32) {
+        self.values[index] = value;
+    }
+
+    pub fn lerp(&self, other: &Varyings, lerp_amt: f32) -> Varyings {
+        let mut result = *self;
+        for idx in 0..self.count {
+            result.values[idx] = (other.values[idx] - self.values[idx]) * lerp_amt + self.values[idx];
+        }
+        result
+    }
+}
+
+pub struct Fragment<'a> {
+    pub x: i32,
+    pub y: i32,
+    //NOTE(dustin): z after the perspective divide as stored in the depth buffer
+    pub depth: f32,
+    //NOTE(dustin): clip space w, 1 for orthographic projections
+    pub w: f32,
+    //NOTE(dustin): whether the triangle faces the viewer, see RasterState::front_face
+    pub front_facing: bool,
+    pub varyings: Varyings,
+    pub interpolator: &'a Interpolator
+}
+
+impl<'a> Fragment<'a> {
+    //NOTE(dustin): perspective correct screen space derivative of a varying, d(v/w)/dx * w - v * d(1/w)/dx * w
+    pub fn ddx(&self, index: usize) -> f32 {
+        self.w * (self.interpolator.varying_step_x[index] - self.varyings.get(index) * self.interpolator.one_over_step_zx)
+    }
+
+    pub fn ddy(&self, index: usize) -> f32 {
+        self.w * (self.interpolator.varying_step_y[index] - self.varyings.get(index) * self.interpolator.one_over_step_zy)
+    }
+}
+
+pub trait VertexShader: Sync {
+    //NOTE(dustin): returns the clip space position and fills the varyings that get interpolated for the fragment shader
+    fn shade(&self, vertex: &Vertex, varyings: &mut Varyings) -> Vector4f32;
+}
+
+pub trait FragmentShader: Sync {
+    //NOTE(dustin): None discards the fragment
+    fn shade(&self, fragment: &Fragment) -> Option<orbclient::Color>;
+}
+
+//NOTE(dustin): transforms positions and passes the texture coordinates through as varyings 0 and 1
+pub struct TransformShader<'a> {
+    pub transform: &'a Matrix4f32
+}
+
+impl<'a> TransformShader<'a> {
+    pub fn new(transform: &'a Matrix4f32) -> TransformShader<'a> {
+        TransformShader { transform }
+    }
+}
+
+impl<'a> VertexShader for TransformShader<'a> {
+    fn shade(&self, vertex: &Vertex, varyings: &mut Varyings) -> Vector4f32 {
+        *varyings = Varyings::from_slice(&[vertex.tex_coords.x, vertex.tex_coords.y]);
+        self.transform.transform(&vertex.pos)
+    }
+}
+
+//NOTE(dustin): texture lookup with the texture coordinates in varyings 0 and 1
+pub struct TextureShader<'a> {
+    pub texture: &'a BitmapTexture,
+    pub sampler: Sampler
+}
+
+impl<'a> TextureShader<'a> {
+    pub fn new(texture: &'a BitmapTexture) -> TextureShader<'a> {
+        TextureShader::with_sampler(texture, Sampler::new())
+    }
+
+    pub fn with_sampler(texture: &'a BitmapTexture, sampler: Sampler) -> TextureShader<'a> {
+        TextureShader { texture, sampler }
+    }
+}
+
+impl<'a> FragmentShader for TextureShader<'a> {
+    fn shade(&self, fragment: &Fragment) -> Option<orbclient::Color> {
+        Some(sample_texture(self.texture, &self.sampler, fragment, 0, 1))
+    }
+}
+
+//NOTE(dustin): samples with the texture coordinates in varyings u_index and v_index and their derivatives
+pub fn sample_texture(texture: &BitmapTexture, sampler: &Sampler, fragment: &Fragment, u_index: usize, v_index: usize) -> orbclient::Color {
+    let coords = TexCoords::new(fragment.varyings.get(u_index), fragment.varyings.get(v_index))
+        .init_derivatives(fragment.ddx(u_index), fragment.ddx(v_index), fragment.ddy(u_index), fragment.ddy(v_index));
+    sampler.sample_grad(texture, &coords)
+}
+
+//NOTE(dustin): diffuse map modulated by the diffuse color as in the mtl spec, plain diffuse color without a map,
+// the dissolve scales the alpha for blending
+pub struct MaterialShader<'a> {
+    pub material: &'a Material,
+    pub sampler: Sampler
+}
+
+impl<'a> MaterialShader<'a> {
+    pub fn new(material: &'a Material, sampler: Sampler) -> MaterialShader<'a> {
+        MaterialShader { material, sampler }
+    }
+}
+
+impl<'a> FragmentShader for MaterialShader<'a> {
+    fn shade(&self, fragment: &Fragment) -> Option<orbclient::Color> {
+        Some(sample_material(self.material, &self.sampler, fragment))
+    }
+}
+
+//NOTE(dustin): the unlit material color with the texture coordinates in varyings 0 and 1. the alpha map scales the
+// alpha by its red channel and its alpha, so gray maps and maps with the value in the alpha channel both work
+pub fn sample_material(material: &Material, sampler: &Sampler, fragment: &Fragment) -> orbclient::Color {
+    let color = match material.diffuse_texture {
+        Some(ref texture) => sample_texture(texture, sampler, fragment, 0, 1),
+        None => orbclient::Color { data: 0xFFFFFFFF },
+    };
+
+    let coverage = match material.alpha_texture {
+        Some(ref texture) => {
+            let texel = sample_texture(texture, sampler, fragment, 0, 1).data;
+            ((texel >> 16) & 0xFF) as f32 / 255_f32 * (texel >> 24) as f32 / 255_f32
+        },
+        None => 1_f32,
+    };
+
+    let color = apply_light(color, &material.diffuse);
+    let alpha = ((color.data >> 24) as f32 * material.dissolve.clamp(0_f32, 1_f32) * coverage + 0.5_f32) as u32;
+
+    orbclient::Color { data: (color.data & 0x00FFFFFF) | (alpha << 24) }
+}
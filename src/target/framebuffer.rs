@@ -0,0 +1,102 @@
+use orbclient;
+
+use depth::DepthBuffer;
+use target::multisample::SampleCount;
+
+//NOTE(dustin): plain offscreen render target, format ARGB like orbclient. a multisampled target stores color and depth for
+// every entry of samples next to each other per pixel, the offsets are in 1/16 pixel from the pixel center
+pub struct Framebuffer {
+    pub width: i32,
+    pub height: i32,
+    pub samples: Vec<(i32, i32)>,
+    pub color: Vec<orbclient::Color>,
+    pub depth: DepthBuffer
+}
+
+impl Framebuffer {
+    pub fn new(_width: i32, _height: i32) -> Framebuffer {
+        Framebuffer::with_samples(_width, _height, vec![(0, 0)])
+    }
+
+    pub fn new_multisampled(_width: i32, _height: i32, sample_count: SampleCount) -> Framebuffer {
+        Framebuffer::with_samples(_width, _height, sample_count.get_positions())
+    }
+
+    fn with_samples(_width: i32, _height: i32, _samples: Vec<(i32, i32)>) -> Framebuffer {
+        let sample_count = _samples.len();
+        Framebuffer {
+            width: _width,
+            height: _height,
+            samples: _samples,
+            color: vec![orbclient::Color { data: 0 }; (_width * _height) as usize * sample_count],
+            depth: DepthBuffer::new_multisampled(_width, _height, sample_count)
+        }
+    }
+
+    pub fn clear(&mut self, clear_color: orbclient::Color) {
+        for color in self.color.iter_mut() {
+            *color = clear_color;
+        }
+        self.depth.clear();
+    }
+
+    //NOTE(dustin): the first sample of a multisampled target, use resolve for the averaged color
+    pub fn get_pixel(&self, x: i32, y: i32) -> orbclient::Color {
+        self.color[(x + y * self.width) as usize * self.samples.len()]
+    }
+
+    //NOTE(dustin): sets every sample of the pixel
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: orbclient::Color) {
+        let sample_count = self.samples.len();
+        let data_idx = (x + y * self.width) as usize * sample_count;
+        for sample_color in self.color[data_idx..data_idx + sample_count].iter_mut() {
+            *sample_color = color;
+        }
+    }
+
+    //NOTE(dustin): copies other into self with its top left corner at (x, y), clipped to self
+    pub fn blit(&mut self, other: &Framebuffer, x: i32, y: i32) {
+        let start_x = 0.max(-x);
+        let start_y = 0.max(-y);
+        let end_x = other.width.min(self.width - x);
+        let end_y = other.height.min(self.height - y);
+
+        for src_y in start_y..end_y {
+            for src_x in start_x..end_x {
+                let color = other.get_pixel(src_x, src_y);
+                self.set_pixel(src_x + x, src_y + y, color);
+            }
+        }
+    }
+
+    //NOTE(dustin): averages all samples of self into target, which is either the same size or smaller by a whole factor in
+    // which case every pixel of target is the average of a square of pixels of self. depth is not averaged, target gets
+    // the first sample of the top left pixel of the square
+    pub fn resolve(&self, target: &mut Framebuffer) {
+        let scale = self.width / target.width;
+        assert!(scale >= 1 && self.width == target.width * scale && self.height == target.height * scale, "can not resolve {}x{} into {}x{}", self.width, self.height, target.width, target.height);
+
+        let sample_count = self.samples.len();
+        let total_count = (scale * scale) as u32 * sample_count as u32;
+        for idx_y in 0..target.height {
+            for idx_x in 0..target.width {
+                let mut sums = [0_u32; 4];
+                for src_y in idx_y * scale..(idx_y + 1) * scale {
+                    let data_idx = (idx_x * scale + src_y * self.width) as usize * sample_count;
+                    for color in self.color[data_idx..data_idx + scale as usize * sample_count].iter() {
+                        for (channel, sum) in sums.iter_mut().enumerate() {
+                            *sum += (color.data >> (channel * 8)) & 0xFF;
+                        }
+                    }
+                }
+
+                let mut data = 0;
+                for (channel, sum) in sums.iter().enumerate() {
+                    data |= ((sum + total_count / 2) / total_count) << (channel * 8);
+                }
+                target.set_pixel(idx_x, idx_y, orbclient::Color { data });
+                target.depth.data[(idx_x + idx_y * target.width) as usize * target.samples.len()] = self.depth.get_depth(idx_x * scale, idx_y * scale);
+            }
+        }
+    }
+}
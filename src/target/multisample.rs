@@ -0,0 +1,59 @@
+//NOTE(dustin): chosen when the RenderContext is created. Msaa keeps coverage and depth per sample but shades once per pixel,
+// Ssaa renders everything at factor times the size. both are averaged into a target of the requested size by RenderContext::resolve
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Antialiasing {
+    None,
+    Msaa(SampleCount),
+    Ssaa(ScaleFactor)
+}
+
+//NOTE(dustin): the sample counts there are sample patterns for
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SampleCount {
+    X2,
+    X4,
+    X8
+}
+
+impl SampleCount {
+    pub fn get_count(&self) -> usize {
+        match *self {
+            SampleCount::X2 => 2,
+            SampleCount::X4 => 4,
+            SampleCount::X8 => 8,
+        }
+    }
+
+    //NOTE(dustin): the standard sample patterns of D3D in 1/16 pixel from the pixel center, the same unit as the fixed point
+    // positions of the rasterizer
+    pub fn get_positions(&self) -> Vec<(i32, i32)> {
+        match *self {
+            SampleCount::X2 => vec![(4, 4), (-4, -4)],
+            SampleCount::X4 => vec![(-2, -6), (6, -2), (-6, 2), (2, 6)],
+            SampleCount::X8 => vec![(1, -3), (-1, 3), (5, 1), (-3, -5), (-5, 5), (-7, -1), (3, 7), (7, -7)],
+        }
+    }
+}
+
+//NOTE(dustin): how many times wider and higher the target of Ssaa is
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScaleFactor {
+    X2,
+    X3,
+    X4
+}
+
+impl ScaleFactor {
+    pub fn get_factor(&self) -> i32 {
+        match *self {
+            ScaleFactor::X2 => 2,
+            ScaleFactor::X3 => 3,
+            ScaleFactor::X4 => 4,
+        }
+    }
+}
+
+//NOTE(dustin): how far in 1/16 pixel a sample can be from the pixel center, the rasterizer widens its bounds by this much
+pub fn get_sample_reach(samples: &[(i32, i32)]) -> i32 {
+    samples.iter().map(|&(offset_x, offset_y)| offset_x.abs().max(offset_y.abs())).max().unwrap_or(0)
+}
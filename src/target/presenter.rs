@@ -0,0 +1,52 @@
+use orbclient::{self, EventIter, Renderer};
+
+use target::framebuffer::Framebuffer;
+
+pub trait Presenter {
+    fn present(&mut self, target: &Framebuffer);
+}
+
+pub struct WindowPresenter {
+    window: orbclient::Window
+}
+
+impl WindowPresenter {
+    pub fn new(width: u32, height: u32, title: &str) -> WindowPresenter {
+        let orb_window = orbclient::Window::new_flags(100, 100, width, height, title, true).unwrap();
+        WindowPresenter{window: orb_window}
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.window.height()
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.window.width()
+    }
+
+    pub fn events(&mut self) -> EventIter {
+        self.window.events()
+    }
+}
+
+impl Presenter for WindowPresenter {
+    fn present(&mut self, target: &Framebuffer) {
+        let ww = self.window.width() as i32;
+        let width = ww.min(target.width) as usize;
+        let height = (self.window.height() as i32).min(target.height);
+
+        {
+            let data = self.window.data_mut();
+            for idx_y in 0..height {
+                let dst = (idx_y * ww) as usize;
+                let src = (idx_y * target.width) as usize;
+                //NOTE(dustin): the framebuffer keeps the blended alpha, the window is always opaque
+                for (dst_color, src_color) in data[dst..dst + width].iter_mut().zip(target.color[src..src + width].iter()) {
+                    dst_color.data = src_color.data | 0xFF000000;
+                }
+            }
+        }
+
+        self.window.sync();
+    }
+}
@@ -1,11 +1,14 @@
 use orbimage::Image;
 use orbclient::{self, Renderer};
+use std::path::Path;
 
 //NOTE(dustin): format ARGB
 pub struct BitmapTexture {
     pub width: i32,
     pub height: i32,
-    pub data: Vec<u8>
+    pub data: Vec<u8>,
+    //NOTE(dustin): levels 1..n, each half the size of the previous one down to 1x1, empty until generate_mipmaps
+    pub mip_levels: Vec<BitmapTexture>
 }
 
 //TODO:(dustin) use orbclient color format, avoid expensive conversation
@@ -14,10 +17,64 @@ impl BitmapTexture {
         BitmapTexture {
             width: _width,
             height: _height,
-            data: vec![0_u8; (_width * _height * 4) as usize]
+            data: vec![0_u8; (_width * _height * 4) as usize],
+            mip_levels: Vec::new()
         }
     }
 
+    pub fn get_mip_count(&self) -> usize {
+        self.mip_levels.len() + 1
+    }
+
+    pub fn get_mip_level(&self, level: usize) -> &BitmapTexture {
+        if level == 0 {
+            self
+        } else {
+            &self.mip_levels[(level - 1).min(self.mip_levels.len() - 1)]
+        }
+    }
+
+    //NOTE(dustin): 2x2 box filter, odd sizes reuse the last row/column
+    pub fn generate_mipmaps(&mut self) {
+        let mut levels: Vec<BitmapTexture> = Vec::new();
+
+        loop {
+            let next = {
+                let previous = levels.last().unwrap_or(&*self);
+                if previous.width == 1 && previous.height == 1 {
+                    break;
+                }
+
+                let mut next = BitmapTexture::new((previous.width / 2).max(1), (previous.height / 2).max(1));
+                for y in 0..next.height {
+                    for x in 0..next.width {
+                        let x0 = (x * 2).min(previous.width - 1);
+                        let y0 = (y * 2).min(previous.height - 1);
+                        let x1 = (x * 2 + 1).min(previous.width - 1);
+                        let y1 = (y * 2 + 1).min(previous.height - 1);
+
+                        let mut sum = [0_u32; 4];
+                        for &(sx, sy) in [(x0, y0), (x1, y0), (x0, y1), (x1, y1)].iter() {
+                            let (a, r, g, b) = previous.get_pixel(sx, sy);
+                            sum[0] += a as u32;
+                            sum[1] += r as u32;
+                            sum[2] += g as u32;
+                            sum[3] += b as u32;
+                        }
+
+                        next.set_pixel(x, y, ((sum[0] + 2) / 4) as u8, ((sum[1] + 2) / 4) as u8, ((sum[2] + 2) / 4) as u8, ((sum[3] + 2) / 4) as u8);
+                    }
+                }
+
+                next
+            };
+
+            levels.push(next);
+        }
+
+        self.mip_levels = levels;
+    }
+
     pub fn set_pixel(&mut self, x: i32, y: i32, a: u8, r: u8, g: u8, b: u8) {
         let idx = ((x + y * self.width) * 4) as usize;
         self.data[idx    ] = a;
@@ -44,6 +101,15 @@ impl BitmapTexture {
         orbclient::Color { data: color }
     }
 
+    //NOTE(dustin): also builds the mip chain
+    pub fn from_path<P: AsRef<Path>>(file_path: P) -> Result<BitmapTexture, String> {
+        let image = Image::from_path(file_path.as_ref()).map_err(|err| format!("failed to load texture {:?}: {}", file_path.as_ref(), err))?;
+        let mut texture = BitmapTexture::from_orbimage(&image);
+        texture.generate_mipmaps();
+
+        Ok(texture)
+    }
+
     pub fn from_orbimage(image: &Image) -> BitmapTexture {
         let mut texture = BitmapTexture::new(image.width() as i32, image.height() as i32);
 
@@ -53,10 +119,11 @@ impl BitmapTexture {
                 let col_idx = (x + y * image.width() as i32) as usize;
                 let orb_color = image.data()[col_idx];
 
+                let a = (orb_color.data >> 24) as u8;
                 let r = (orb_color.data >> 16) as u8;
                 let g = (orb_color.data >> 8) as u8;
                 let b = orb_color.data as u8;
-                texture.set_pixel(x, y, 255, r, g, b);
+                texture.set_pixel(x, y, a, r, g, b);
             }
         }
 
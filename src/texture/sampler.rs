@@ -0,0 +1,228 @@
+use orbclient;
+
+use texture::bitmap::BitmapTexture;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Filter {
+    //NOTE(dustin): closest texel of the base level
+    Nearest,
+    //NOTE(dustin): weighted 2x2 texels of the base level
+    Bilinear,
+    //NOTE(dustin): bilinear in the two mip levels around the lod, blended, needs BitmapTexture::generate_mipmaps
+    Trilinear
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    //NOTE(dustin): texels outside of 0..1 are the border color of the sampler
+    ClampToBorder
+}
+
+impl Wrap {
+    //NOTE(dustin): maps a texture coordinate into 0..1, clamp to border keeps it so the lookup can fall outside
+    pub fn apply(&self, coord: f32) -> f32 {
+        match *self {
+            Wrap::Repeat => coord - coord.floor(),
+            Wrap::MirroredRepeat => {
+                let period = coord - (coord * 0.5_f32).floor() * 2_f32;
+                if period > 1_f32 { 2_f32 - period } else { period }
+            },
+            Wrap::ClampToEdge => coord.clamp(0_f32, 1_f32),
+            Wrap::ClampToBorder => coord,
+        }
+    }
+
+    //NOTE(dustin): maps a texel index into 0..size, None if it is outside with clamp to border. apply keeps the index at
+    // most one texel past either end for the repeating modes, so a bilinear lookup at the seam reaches the other side
+    pub fn apply_texel(&self, idx: i32, size: i32) -> Option<i32> {
+        match *self {
+            Wrap::Repeat => Some(idx.rem_euclid(size)),
+            Wrap::MirroredRepeat => {
+                let period = idx.rem_euclid(2 * size);
+                Some(if period >= size { 2 * size - 1 - period } else { period })
+            },
+            Wrap::ClampToEdge => Some(idx.clamp(0, size - 1)),
+            Wrap::ClampToBorder if idx < 0 || idx >= size => None,
+            Wrap::ClampToBorder => Some(idx),
+        }
+    }
+}
+
+//NOTE(dustin): a texture coordinate with its screen space derivatives, the derivatives are only used for the lod
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TexCoords {
+    pub u: f32,
+    pub v: f32,
+    pub du_dx: f32,
+    pub dv_dx: f32,
+    pub du_dy: f32,
+    pub dv_dy: f32
+}
+
+impl TexCoords {
+    pub fn new(_u: f32, _v: f32) -> TexCoords {
+        TexCoords {
+            u: _u,
+            v: _v,
+            du_dx: 0_f32,
+            dv_dx: 0_f32,
+            du_dy: 0_f32,
+            dv_dy: 0_f32
+        }
+    }
+
+    pub fn init_derivatives(mut self, du_dx: f32, dv_dx: f32, du_dy: f32, dv_dy: f32) -> TexCoords {
+        self.du_dx = du_dx;
+        self.dv_dx = dv_dx;
+        self.du_dy = du_dy;
+        self.dv_dy = dv_dy;
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Sampler {
+    pub filter: Filter,
+    pub wrap_u: Wrap,
+    pub wrap_v: Wrap,
+    pub border_color: orbclient::Color
+}
+
+impl Sampler {
+    pub fn new() -> Sampler {
+        Sampler::with_filter(Filter::Nearest)
+    }
+
+    pub fn with_filter(_filter: Filter) -> Sampler {
+        Sampler {
+            filter: _filter,
+            wrap_u: Wrap::ClampToEdge,
+            wrap_v: Wrap::ClampToEdge,
+            border_color: orbclient::Color { data: 0xFF000000 }
+        }
+    }
+
+    pub fn init_wrap(mut self, wrap_u: Wrap, wrap_v: Wrap) -> Sampler {
+        self.wrap_u = wrap_u;
+        self.wrap_v = wrap_v;
+        self
+    }
+
+    pub fn init_border_color(mut self, border_color: orbclient::Color) -> Sampler {
+        self.border_color = border_color;
+        self
+    }
+
+    pub fn sample_grad(&self, texture: &BitmapTexture, coords: &TexCoords) -> orbclient::Color {
+        let lod = match self.filter {
+            Filter::Trilinear => Sampler::calc_lod(texture, coords),
+            _ => 0_f32,
+        };
+
+        self.sample_lod(texture, coords.u, coords.v, lod)
+    }
+
+    pub fn sample_lod(&self, texture: &BitmapTexture, u: f32, v: f32, lod: f32) -> orbclient::Color {
+        let u = self.wrap_u.apply(u);
+        let v = self.wrap_v.apply(v);
+
+        match self.filter {
+            Filter::Nearest => self.sample_nearest(texture, u, v),
+            Filter::Bilinear => Sampler::to_color(self.sample_bilinear(texture, u, v)),
+            Filter::Trilinear => {
+                let max_level = (texture.get_mip_count() - 1) as f32;
+                let lod = lod.max(0_f32).min(max_level);
+                let level = lod.floor();
+                let lerp_amt = lod - level;
+
+                let lower = self.sample_bilinear(texture.get_mip_level(level as usize), u, v);
+                if lerp_amt == 0_f32 {
+                    return Sampler::to_color(lower);
+                }
+
+                let upper = self.sample_bilinear(texture.get_mip_level(level as usize + 1), u, v);
+                Sampler::to_color(Sampler::lerp(&lower, &upper, lerp_amt))
+            },
+        }
+    }
+
+    pub fn calc_lod(texture: &BitmapTexture, coords: &TexCoords) -> f32 {
+        let width = texture.width as f32;
+        let height = texture.height as f32;
+
+        let rho_x = ((coords.du_dx * width) * (coords.du_dx * width) + (coords.dv_dx * height) * (coords.dv_dx * height)).sqrt();
+        let rho_y = ((coords.du_dy * width) * (coords.du_dy * width) + (coords.dv_dy * height) * (coords.dv_dy * height)).sqrt();
+
+        rho_x.max(rho_y).max(1e-8_f32).log2()
+    }
+
+    //NOTE(dustin): texel idx covers idx / width to (idx + 1) / width, so every texel has the same width and u = 1 is the
+    // first texel again with repeat
+    fn sample_nearest(&self, texture: &BitmapTexture, u: f32, v: f32) -> orbclient::Color {
+        let x = (u * texture.width as f32).max(-1_f32).min(texture.width as f32).floor() as i32;
+        let y = (v * texture.height as f32).max(-1_f32).min(texture.height as f32).floor() as i32;
+
+        Sampler::to_color(self.fetch(texture, x, y))
+    }
+
+    //NOTE(dustin): the texel centers are at (idx + 0.5) / width, between the last and the first center the texels wrap
+    fn sample_bilinear(&self, texture: &BitmapTexture, u: f32, v: f32) -> [f32; 4] {
+        //NOTE(dustin): one texel past the edge is enough to reach the border, keeps x0 + 1 from overflowing
+        let x = (u * texture.width as f32 - 0.5_f32).max(-1_f32).min(texture.width as f32);
+        let y = (v * texture.height as f32 - 0.5_f32).max(-1_f32).min(texture.height as f32);
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let frac_x = x - x0;
+        let frac_y = y - y0;
+
+        let x0 = x0 as i32;
+        let y0 = y0 as i32;
+
+        let top = Sampler::lerp(&self.fetch(texture, x0, y0), &self.fetch(texture, x0 + 1, y0), frac_x);
+        let bottom = Sampler::lerp(&self.fetch(texture, x0, y0 + 1), &self.fetch(texture, x0 + 1, y0 + 1), frac_x);
+
+        Sampler::lerp(&top, &bottom, frac_y)
+    }
+
+    //NOTE(dustin): texels outside of the texture are wrapped by the wrap mode or are the border color, so nan or infinite
+    // coordinates never index out of bounds
+    fn fetch(&self, texture: &BitmapTexture, x: i32, y: i32) -> [f32; 4] {
+        match (self.wrap_u.apply_texel(x, texture.width), self.wrap_v.apply_texel(y, texture.height)) {
+            (Some(x), Some(y)) => {
+                let (a, r, g, b) = texture.get_pixel(x, y);
+                [a as f32, r as f32, g as f32, b as f32]
+            },
+            _ => {
+                let border = self.border_color.data;
+                [(border >> 24) as f32, ((border >> 16) & 0xFF) as f32, ((border >> 8) & 0xFF) as f32, (border & 0xFF) as f32]
+            },
+        }
+    }
+
+    fn lerp(a: &[f32; 4], b: &[f32; 4], lerp_amt: f32) -> [f32; 4] {
+        [
+            (b[0] - a[0]) * lerp_amt + a[0],
+            (b[1] - a[1]) * lerp_amt + a[1],
+            (b[2] - a[2]) * lerp_amt + a[2],
+            (b[3] - a[3]) * lerp_amt + a[3]
+        ]
+    }
+
+    fn to_color(argb: [f32; 4]) -> orbclient::Color {
+        let a = (argb[0] + 0.5_f32) as u32;
+        let r = (argb[1] + 0.5_f32) as u32;
+        let g = (argb[2] + 0.5_f32) as u32;
+        let b = (argb[3] + 0.5_f32) as u32;
+
+        orbclient::Color { data: (a << 24) + (r << 16) + (g << 8) + b }
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Sampler {
+        Sampler::new()
+    }
+}
@@ -0,0 +1,143 @@
+use orbclient;
+use std::sync::Mutex;
+use std::thread;
+
+use blend::BlendState;
+use raster::{RasterAlgorithm, Rasterizer, ScreenTriangle};
+use shader::FragmentShader;
+use target::framebuffer::Framebuffer;
+use target::multisample;
+
+pub const TILE_SIZE: i32 = 64;
+
+//NOTE(dustin): a square of the target with a copy of its color and depth, so every worker writes to its own memory.
+// bin holds the indices of the triangles touching the tile in submission order
+struct Tile {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32,
+    color: Vec<orbclient::Color>,
+    depth: Vec<f32>,
+    bin: Vec<usize>
+}
+
+impl Tile {
+    fn load(target: &Framebuffer, min_x: i32, min_y: i32, bin: Vec<usize>) -> Tile {
+        let max_x = (min_x + TILE_SIZE).min(target.width);
+        let max_y = (min_y + TILE_SIZE).min(target.height);
+        let sample_count = target.samples.len();
+
+        let mut color = Vec::with_capacity(((max_x - min_x) * (max_y - min_y)) as usize * sample_count);
+        let mut depth = Vec::with_capacity(color.capacity());
+        for idx_y in min_y..max_y {
+            let start = (min_x + idx_y * target.width) as usize * sample_count;
+            let end = (max_x + idx_y * target.width) as usize * sample_count;
+            color.extend_from_slice(&target.color[start..end]);
+            depth.extend_from_slice(&target.depth.data[start..end]);
+        }
+
+        Tile {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            color,
+            depth,
+            bin
+        }
+    }
+
+    fn store(&self, target: &mut Framebuffer) {
+        let sample_count = target.samples.len();
+        let row_len = (self.max_x - self.min_x) as usize * sample_count;
+        for (row, idx_y) in (self.min_y..self.max_y).enumerate() {
+            let start = (self.min_x + idx_y * target.width) as usize * sample_count;
+            target.color[start..start + row_len].copy_from_slice(&self.color[row * row_len..(row + 1) * row_len]);
+            target.depth.data[start..start + row_len].copy_from_slice(&self.depth[row * row_len..(row + 1) * row_len]);
+        }
+    }
+}
+
+//NOTE(dustin): bins the triangles into tiles of TILE_SIZE x TILE_SIZE pixels and rasterizes the tiles with triangles on
+// worker_count threads. a tile is only touched by one worker, so the result is the same as on a single thread
+pub fn rasterize_tiled<F: FragmentShader>(worker_count: usize, target: &mut Framebuffer, triangles: &[ScreenTriangle], shader: &F, blend: BlendState, algorithm: RasterAlgorithm) {
+    let reach = multisample::get_sample_reach(&target.samples);
+    let tiles_x = (target.width + TILE_SIZE - 1) / TILE_SIZE;
+    let tiles_y = (target.height + TILE_SIZE - 1) / TILE_SIZE;
+
+    //NOTE(dustin): bins keep submission order, so depth ties resolve the same way as the single threaded path
+    let mut bins = vec![Vec::new(); (tiles_x * tiles_y) as usize];
+    for (triangle_idx, triangle) in triangles.iter().enumerate() {
+        let (start_y, end_y) = triangle.get_sample_rows(reach);
+        let (start_x, end_x) = triangle.get_sample_columns(reach);
+        let (start_x, end_x) = (start_x.max(0), end_x.min(target.width));
+        let (start_y, end_y) = (start_y.max(0), end_y.min(target.height));
+        if start_x >= end_x || start_y >= end_y {
+            continue;
+        }
+
+        for tile_y in (start_y / TILE_SIZE)..((end_y - 1) / TILE_SIZE + 1) {
+            for tile_x in (start_x / TILE_SIZE)..((end_x - 1) / TILE_SIZE + 1) {
+                bins[(tile_x + tile_y * tiles_x) as usize].push(triangle_idx);
+            }
+        }
+    }
+
+    let tiles: Vec<Tile> = bins.into_iter()
+        .enumerate()
+        .filter(|(_, bin)| !bin.is_empty())
+        .map(|(tile_idx, bin)| Tile::load(target, (tile_idx as i32 % tiles_x) * TILE_SIZE, (tile_idx as i32 / tiles_x) * TILE_SIZE, bin))
+        .collect();
+
+    let queue = Mutex::new(tiles.into_iter());
+    let finished = Mutex::new(Vec::new());
+    let samples = &target.samples;
+    let depth_func = target.depth.func;
+    let depth_write = target.depth.write;
+
+    let work = || {
+        loop {
+            let next = queue.lock().unwrap().next();
+            let mut tile = match next {
+                Some(tile) => tile,
+                None => break,
+            };
+
+            {
+                let mut rasterizer = Rasterizer {
+                    min_x: tile.min_x,
+                    min_y: tile.min_y,
+                    max_x: tile.max_x,
+                    max_y: tile.max_y,
+                    samples,
+                    color: &mut tile.color,
+                    depth: &mut tile.depth,
+
+                    depth_func,
+                    depth_write,
+                    blend,
+                    algorithm
+                };
+
+                for &triangle_idx in tile.bin.iter() {
+                    rasterizer.scan_triangle(&triangles[triangle_idx], shader);
+                }
+            }
+
+            finished.lock().unwrap().push(tile);
+        }
+    };
+
+    //NOTE(dustin): the calling thread is one of the workers, a panic in any of them is passed on once all returned
+    thread::scope(|scope| {
+        for _ in 1..worker_count {
+            scope.spawn(work);
+        }
+        work();
+    });
+
+    for tile in finished.into_inner().unwrap() {
+        tile.store(target);
+    }
+}
@@ -0,0 +1,38 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use orbclient::Color;
+
+use pixelcannon::blend::{BlendMode, BlendState};
+
+const SRC: Color = Color { data: 0x80FF4000 };
+const DST: Color = Color { data: 0xFF204080 };
+
+#[test]
+fn blend_modes() {
+    assert_eq!(BlendMode::Replace.blend(SRC, DST).data, SRC.data);
+    assert_eq!(BlendMode::AlphaBlend.blend(SRC, DST).data, 0xFF904040);
+    assert_eq!(BlendMode::Additive.blend(SRC, DST).data, 0xFFA06080);
+    assert_eq!(BlendMode::Multiply.blend(SRC, DST).data, 0x80201000);
+    assert_eq!(BlendMode::Premultiplied.blend(SRC, DST).data, 0xFFFF6040);
+}
+
+#[test]
+fn opaque_and_transparent_sources() {
+    let opaque = Color { data: 0xFF123456 };
+    let transparent = Color { data: 0x00123456 };
+
+    assert_eq!(BlendMode::AlphaBlend.blend(opaque, DST).data, opaque.data);
+    assert_eq!(BlendMode::AlphaBlend.blend(transparent, DST).data, DST.data);
+    assert_eq!(BlendMode::Additive.blend(transparent, DST).data, DST.data);
+}
+
+#[test]
+fn alpha_threshold() {
+    let mut state = BlendState::new();
+    assert!(!state.is_discarded(Color { data: 0x00FFFFFF }));
+
+    state.alpha_threshold = Some(0x80);
+    assert!(state.is_discarded(Color { data: 0x7FFFFFFF }));
+    assert!(!state.is_discarded(SRC));
+}
@@ -0,0 +1,134 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use orbclient::{ButtonEvent, EventOption, KeyEvent, MouseEvent, ScrollEvent};
+
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::controller::{CameraController, FlyController, OrbitController, TurntableController};
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::vector::Vector4f32;
+
+const EPSILON: f32 = 1e-4;
+
+fn projection() -> Projection {
+    Projection::Perspective { fov: 70.0_f32.to_radians(), aspect_ratio: 4_f32 / 3_f32, z_near: 0.1_f32, z_far: 1000_f32 }
+}
+
+fn point(x: f32, y: f32, z: f32) -> Vector4f32 {
+    Vector4f32::new(x, y, z, 1_f32)
+}
+
+fn assert_vector(actual: &Vector4f32, expected: &Vector4f32) {
+    assert!(actual.sub_v(expected).length() < EPSILON, "expected {:?}, got {:?}", expected, actual);
+}
+
+fn assert_matrix(actual: &Matrix4f32, expected: &Matrix4f32) {
+    for r_idx in 0..4 {
+        for c_idx in 0..4 {
+            assert!((actual.m[r_idx][c_idx] - expected.m[r_idx][c_idx]).abs() < EPSILON,
+                "m[{}][{}] expected {}, got {}", r_idx, c_idx, expected.m[r_idx][c_idx], actual.m[r_idx][c_idx]);
+        }
+    }
+}
+
+#[test]
+fn default_camera_matches_translation() {
+    //NOTE(dustin): a camera at -4 looking down +z sees the same as moving the model to +4
+    let camera = Camera::new(point(0_f32, 0_f32, -4_f32), projection());
+
+    assert_matrix(&camera.get_view(), &Matrix4f32::new().init_translation(0_f32, 0_f32, 4_f32));
+    assert_matrix(&camera.get_view_projection(), &projection().to_matrix().mul(&Matrix4f32::new().init_translation(0_f32, 0_f32, 4_f32)));
+}
+
+#[test]
+fn orthographic_maps_box_to_unit_cube() {
+    let orthographic = Projection::Orthographic { left: -2_f32, right: 4_f32, bottom: -1_f32, top: 2_f32, z_near: 1_f32, z_far: 11_f32 };
+    let matrix = orthographic.to_matrix();
+
+    assert_vector(&matrix.transform(&point(-2_f32, -1_f32, 1_f32)), &point(-1_f32, -1_f32, -1_f32));
+    assert_vector(&matrix.transform(&point(4_f32, 2_f32, 11_f32)), &point(1_f32, 1_f32, 1_f32));
+    assert_vector(&matrix.transform(&point(1_f32, 0.5_f32, 6_f32)), &point(0_f32, 0_f32, 0_f32));
+
+    //NOTE(dustin): same depth range as the perspective projection, near is -1 and far is 1
+    let perspective = projection().to_matrix();
+    let near = perspective.transform(&point(0_f32, 0_f32, 0.1_f32));
+    let far = perspective.transform(&point(0_f32, 0_f32, 1000_f32));
+    assert!((near.z / near.w + 1_f32).abs() < EPSILON && (far.z / far.w - 1_f32).abs() < EPSILON);
+}
+
+#[test]
+fn look_at_puts_target_on_the_view_axis() {
+    let mut camera = Camera::new(point(3_f32, 2_f32, -1_f32), projection());
+    camera.look_at(&point(-1_f32, 0.5_f32, 4_f32), &Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32));
+
+    let distance = point(-1_f32, 0.5_f32, 4_f32).sub_v(&camera.position).length();
+    let view_pos = camera.get_view().transform(&point(-1_f32, 0.5_f32, 4_f32));
+    assert_vector(&view_pos, &point(0_f32, 0_f32, distance));
+
+    //NOTE(dustin): the camera itself ends up in the origin and world up stays up
+    assert_vector(&camera.get_view().transform(&camera.position), &point(0_f32, 0_f32, 0_f32));
+    assert!(camera.get_view().transform(&point(3_f32, 3_f32, -1_f32)).y > 0_f32);
+}
+
+#[test]
+fn rotate_turns_right_around_up() {
+    let mut camera = Camera::new(point(0_f32, 0_f32, 0_f32), projection());
+    camera.rotate(&Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32), std::f32::consts::PI / 2_f32);
+
+    assert_vector(&camera.forward, &Vector4f32::new(1_f32, 0_f32, 0_f32, 0_f32));
+    assert_vector(&camera.get_right(), &Vector4f32::new(0_f32, 0_f32, -1_f32, 0_f32));
+}
+
+#[test]
+fn fly_controller_moves_and_looks() {
+    let mut camera = Camera::new(point(0_f32, 0_f32, -4_f32), projection());
+    let mut fly = FlyController::new(&camera);
+
+    fly.handle_event(&EventOption::Key(KeyEvent { character: 'w', scancode: orbclient::K_W, pressed: true }));
+    fly.update(&mut camera, 500_f32);
+    assert_vector(&camera.position, &point(0_f32, 0_f32, -3_f32));
+
+    fly.handle_event(&EventOption::Key(KeyEvent { character: 'w', scancode: orbclient::K_W, pressed: false }));
+    fly.handle_event(&EventOption::Button(ButtonEvent { left: true, middle: false, right: false }));
+    fly.handle_event(&EventOption::Mouse(MouseEvent { x: 100, y: 100 }));
+    fly.handle_event(&EventOption::Mouse(MouseEvent { x: 100, y: -10000 }));
+    fly.update(&mut camera, 500_f32);
+
+    //NOTE(dustin): dragging far up clamps the pitch short of straight up
+    assert_vector(&camera.position, &point(0_f32, 0_f32, -3_f32));
+    assert!(camera.forward.y > 0.99_f32 && camera.forward.y < 1_f32);
+}
+
+#[test]
+fn orbit_controller_keeps_distance() {
+    let target = point(1_f32, 0_f32, 0_f32);
+    let mut camera = Camera::new(point(1_f32, 0_f32, -4_f32), projection());
+    let mut orbit = OrbitController::new(&camera, target);
+
+    orbit.handle_event(&EventOption::Button(ButtonEvent { left: true, middle: false, right: false }));
+    orbit.handle_event(&EventOption::Mouse(MouseEvent { x: 0, y: 0 }));
+    orbit.handle_event(&EventOption::Mouse(MouseEvent { x: 150, y: 80 }));
+    orbit.update(&mut camera, 16_f32);
+
+    assert!((camera.position.sub_v(&target).length() - 4_f32).abs() < EPSILON);
+    assert!(camera.position.y > 0_f32);
+    assert_vector(&camera.get_view().transform(&target), &point(0_f32, 0_f32, 4_f32));
+
+    orbit.handle_event(&EventOption::Scroll(ScrollEvent { x: 0, y: 2 }));
+    orbit.update(&mut camera, 16_f32);
+    assert!((camera.position.sub_v(&target).length() - 4_f32 * 0.81_f32).abs() < EPSILON);
+}
+
+#[test]
+fn turntable_controller_circles_target() {
+    let target = point(0_f32, 1_f32, 0_f32);
+    let mut camera = Camera::new(point(0_f32, 0_f32, 0_f32), projection());
+    let mut turntable = TurntableController::new(target, 3_f32, 1_f32, std::f32::consts::PI);
+
+    turntable.update(&mut camera, 0_f32);
+    assert_vector(&camera.position, &point(0_f32, 2_f32, -3_f32));
+
+    turntable.update(&mut camera, 500_f32);
+    assert_vector(&camera.position, &point(-3_f32, 2_f32, 0_f32));
+    assert!(camera.get_view().transform(&target).z > 0_f32);
+}
@@ -0,0 +1,122 @@
+extern crate pixelcannon;
+
+use std::path::PathBuf;
+
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::cull::{Aabb, BoundingSphere, CullStats, Frustum};
+use pixelcannon::model::material::Material;
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::render::RenderContext;
+use pixelcannon::scene::{Node, Scene, Transform};
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+fn manifest_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+fn load_mesh(path: &str) -> Mesh {
+    Mesh::from_path(manifest_path(path).to_str().unwrap().to_string()).unwrap()
+}
+
+fn point(x: f32, y: f32, z: f32) -> Vector4f32 {
+    Vector4f32::new(x, y, z, 1_f32)
+}
+
+fn camera() -> Camera {
+    Camera::new(point(0_f32, 0_f32, -4_f32), Projection::Perspective { fov: 70.0_f32.to_radians(), aspect_ratio: 4_f32 / 3_f32, z_near: 0.1_f32, z_far: 100_f32 })
+}
+
+#[test]
+fn mesh_bounds_contain_every_vertex() {
+    for path in ["assets/sphere.obj", "assets/monkey2.obj", "assets/cube.obj"].iter() {
+        let mesh = load_mesh(path);
+
+        for vertex in mesh.vertices.iter() {
+            let pos = &vertex.pos;
+            assert!(pos.x >= mesh.aabb.min.x && pos.y >= mesh.aabb.min.y && pos.z >= mesh.aabb.min.z, "{} {:?} below {:?}", path, pos, mesh.aabb.min);
+            assert!(pos.x <= mesh.aabb.max.x && pos.y <= mesh.aabb.max.y && pos.z <= mesh.aabb.max.z, "{} {:?} above {:?}", path, pos, mesh.aabb.max);
+
+            let center = &mesh.bounding_sphere.center;
+            let offset = Vector4f32::new(pos.x - center.x, pos.y - center.y, pos.z - center.z, 0_f32);
+            assert!(offset.length() <= mesh.bounding_sphere.radius + 1e-5_f32, "{} {:?} outside the bounding sphere", path, pos);
+        }
+    }
+
+    let empty = Aabb::from_points([].iter());
+    assert_eq!((empty.min.x, empty.max.x), (0_f32, 0_f32));
+}
+
+#[test]
+fn frustum_planes() {
+    let frustum = Frustum::from_matrix(&camera().get_view_projection());
+    let sphere = |x: f32, y: f32, z: f32, radius: f32| BoundingSphere { center: point(x, y, z), radius };
+    let aabb = |min: Vector4f32, max: Vector4f32| Aabb { min, max };
+
+    assert!(frustum.intersects_sphere(&sphere(0_f32, 0_f32, 0_f32, 1_f32)));
+    assert!(!frustum.intersects_sphere(&sphere(0_f32, 0_f32, -6_f32, 1_f32)), "behind the camera");
+    assert!(!frustum.intersects_sphere(&sphere(0_f32, 0_f32, 200_f32, 1_f32)), "beyond the far plane");
+    assert!(!frustum.intersects_sphere(&sphere(20_f32, 0_f32, 0_f32, 1_f32)), "right of the view");
+    assert!(frustum.intersects_sphere(&sphere(4_f32, 0_f32, 0_f32, 2_f32)), "crosses the right plane");
+
+    assert!(frustum.intersects_aabb(&aabb(point(-1_f32, -1_f32, -1_f32), point(1_f32, 1_f32, 1_f32))));
+    assert!(!frustum.intersects_aabb(&aabb(point(-1_f32, 10_f32, -1_f32), point(1_f32, 12_f32, 1_f32))), "above the view");
+    assert!(frustum.intersects_aabb(&aabb(point(-1_f32, 2_f32, 0_f32), point(1_f32, 20_f32, 1_f32))), "crosses the top plane");
+}
+
+#[test]
+fn offscreen_meshes_are_skipped() {
+    let mesh = load_mesh("assets/sphere.obj");
+    let view_projection = camera().get_view_projection();
+    let behind = view_projection.mul(&Matrix4f32::new().init_translation(0_f32, 0_f32, -8_f32));
+    let texture = BitmapTexture::new(1, 1);
+
+    let mut render_context = RenderContext::new(80, 60);
+    render_context.clear();
+    render_context.draw_mesh(&mesh, &view_projection, &texture);
+    render_context.draw_mesh(&mesh, &behind, &texture);
+
+    assert_eq!(render_context.get_cull_stats(), CullStats { objects_drawn: 1, objects_culled: 1, triangles_culled: mesh.get_triangle_count() });
+
+    render_context.clear();
+    assert_eq!(render_context.get_cull_stats(), CullStats::new());
+}
+
+#[test]
+fn culled_scene_matches_unculled() {
+    let mut scene = Scene::new();
+    let mesh = scene.add_mesh(load_mesh("assets/monkey2.obj"));
+    let material = scene.add_material(Material::new("default"));
+
+    //NOTE(dustin): a ring of monkeys around the camera, only the ones in front of it are visible, one pokes in from the left edge
+    let mut expected_culled = 0;
+    for monkey_idx in 0..12 {
+        let angle = monkey_idx as f32 * ::std::f32::consts::PI / 6_f32;
+        let (x, z) = (angle.sin() * 6_f32, angle.cos() * 6_f32 - 4_f32);
+        scene.add_node(Node::new("monkey").init_mesh(mesh).init_material(material)
+            .init_transform(Transform::new().init_translation(x, 0_f32, z)), None);
+        if z < -4_f32 + 2_f32 {
+            expected_culled += 1;
+        }
+    }
+    scene.add_node(Node::new("edge").init_mesh(mesh).init_material(material)
+        .init_transform(Transform::new().init_translation(-4.2_f32, 0_f32, 0_f32)), None);
+
+    let render = |culling: bool| {
+        let mut render_context = RenderContext::new(80, 60);
+        render_context.set_frustum_culling(culling);
+        render_context.clear();
+        render_context.draw_scene(&scene, &camera());
+        (render_context.get_cull_stats(), render_context.into_target())
+    };
+    let (culled_stats, culled) = render(true);
+    let (unculled_stats, unculled) = render(false);
+
+    assert!(culled_stats.objects_culled >= expected_culled && culled_stats.objects_drawn > 1, "{:?}", culled_stats);
+    assert_eq!(culled_stats.objects_drawn + culled_stats.objects_culled, 13);
+    assert_eq!(culled_stats.triangles_culled, culled_stats.objects_culled * scene.meshes[mesh].get_triangle_count());
+    assert_eq!(unculled_stats.objects_culled, 0);
+    assert!(culled.color.iter().zip(unculled.color.iter()).all(|(a, b)| a.data == b.data));
+    assert!(culled.depth.data == unculled.depth.data);
+}
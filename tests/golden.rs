@@ -0,0 +1,436 @@
+extern crate orbclient;
+extern crate orbimage;
+extern crate pixelcannon;
+
+use std::env;
+use std::f32::consts::PI;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use orbimage::Image;
+
+use pixelcannon::blend::{BlendMode, BlendState};
+
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::light::{Attenuation, Light, Lighting, ShadingMode};
+use pixelcannon::model::material::Material;
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::model::multimesh::MultiMesh;
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::quaternion::Quaternion;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::raster::RasterAlgorithm;
+use pixelcannon::render::{RenderContext, TransparentDraw};
+use pixelcannon::scene::{Node, Scene, Transform};
+use pixelcannon::target::framebuffer::Framebuffer;
+use pixelcannon::target::multisample::{Antialiasing, SampleCount, ScaleFactor};
+use pixelcannon::texture::bitmap::BitmapTexture;
+use pixelcannon::texture::sampler::{Filter, Sampler};
+
+const WIDTH: i32 = 160;
+const HEIGHT: i32 = 120;
+
+//NOTE(dustin): max difference per color channel and number of pixels allowed to exceed it
+const CHANNEL_TOLERANCE: i32 = 4;
+const MAX_MISMATCHED_PIXELS: usize = 8;
+
+struct Pose {
+    trans: (f32, f32, f32),
+    rot: (f32, f32, f32)
+}
+
+fn manifest_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+fn render(model: &str, image: &str, pose: &Pose, worker_count: usize) -> Framebuffer {
+    render_filtered(model, image, pose, worker_count, Filter::Nearest)
+}
+
+fn render_filtered(model: &str, image: &str, pose: &Pose, worker_count: usize, filter: Filter) -> Framebuffer {
+    render_with_algorithm(model, image, pose, worker_count, filter, RasterAlgorithm::Scanline)
+}
+
+fn render_with_algorithm(model: &str, image: &str, pose: &Pose, worker_count: usize, filter: Filter, algorithm: RasterAlgorithm) -> Framebuffer {
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.set_worker_count(worker_count);
+    render_context.set_sampler(Sampler::with_filter(filter));
+    render_context.set_raster_algorithm(algorithm);
+    draw_posed_mesh(&mut render_context, model, image, pose);
+
+    render_context.into_target()
+}
+
+fn render_antialiased(model: &str, image: &str, pose: &Pose, worker_count: usize, antialiasing: Antialiasing) -> Framebuffer {
+    let mut render_context = RenderContext::with_antialiasing(WIDTH as u32, HEIGHT as u32, antialiasing);
+    render_context.set_worker_count(worker_count);
+    draw_posed_mesh(&mut render_context, model, image, pose);
+
+    render_context.into_output()
+}
+
+fn draw_posed_mesh(render_context: &mut RenderContext, model: &str, image: &str, pose: &Pose) {
+    let mesh = Mesh::from_path(manifest_path(model).to_str().unwrap().to_string()).unwrap();
+    let image = Image::from_path(manifest_path(image)).unwrap();
+    let mut texture = BitmapTexture::from_orbimage(&image);
+    texture.generate_mipmaps();
+
+    let projection = Matrix4f32::new().init_perspective(70.0_f32.to_radians(), WIDTH as f32 / HEIGHT as f32, 0.1_f32, 1000_f32);
+    let translation = Matrix4f32::new().init_translation(pose.trans.0, pose.trans.1, pose.trans.2);
+    let rotation = Matrix4f32::new().init_rotation(pose.rot.0, pose.rot.1, pose.rot.2);
+    let transform = projection.mul(&translation.mul(&rotation));
+
+    render_context.clear();
+    render_context.draw_mesh(&mesh, &transform, &texture);
+}
+
+fn render_lit(model: &str, image: &str, pose: &Pose, mode: ShadingMode) -> Framebuffer {
+    let mesh = Mesh::from_path(manifest_path(model).to_str().unwrap().to_string()).unwrap();
+    let image = Image::from_path(manifest_path(image)).unwrap();
+    let texture = BitmapTexture::from_orbimage(&image);
+
+    let mut lighting = Lighting::new(Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32));
+    lighting.add_light(Light::Ambient { color: Vector4f32::new(0.1_f32, 0.1_f32, 0.1_f32, 0_f32) });
+    lighting.add_light(Light::Directional { direction: Vector4f32::new(1_f32, -1_f32, 1_f32, 0_f32), color: Vector4f32::new(0.7_f32, 0.7_f32, 0.6_f32, 0_f32) });
+    lighting.add_light(Light::Point { position: Vector4f32::new(-2_f32, 1_f32, 1_f32, 1_f32), color: Vector4f32::new(1_f32, 0.2_f32, 0_f32, 0_f32), attenuation: Attenuation::new(0_f32, 0_f32, 0.4_f32) });
+    lighting.add_light(Light::Spot {
+        position: Vector4f32::new(2_f32, 0_f32, 1_f32, 1_f32),
+        direction: Vector4f32::new(-1_f32, 0_f32, 1_f32, 0_f32),
+        color: Vector4f32::new(0_f32, 0.5_f32, 1_f32, 0_f32),
+        attenuation: Attenuation::new(1_f32, 0_f32, 0_f32),
+        inner_angle: 0.2_f32,
+        outer_angle: 0.4_f32
+    });
+
+    let projection = Matrix4f32::new().init_perspective(70.0_f32.to_radians(), WIDTH as f32 / HEIGHT as f32, 0.1_f32, 1000_f32);
+    let translation = Matrix4f32::new().init_translation(pose.trans.0, pose.trans.1, pose.trans.2);
+    let rotation = Matrix4f32::new().init_rotation(pose.rot.0, pose.rot.1, pose.rot.2);
+
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.clear();
+    render_context.draw_mesh_lit(&mesh, &translation.mul(&rotation), &projection, &texture, &lighting, mode);
+
+    render_context.into_target()
+}
+
+fn render_multi(model: &str, pose: &Pose) -> Framebuffer {
+    let multi_mesh = MultiMesh::from_path(manifest_path(model).to_str().unwrap().to_string()).unwrap();
+
+    let projection = Matrix4f32::new().init_perspective(70.0_f32.to_radians(), WIDTH as f32 / HEIGHT as f32, 0.1_f32, 1000_f32);
+    let translation = Matrix4f32::new().init_translation(pose.trans.0, pose.trans.1, pose.trans.2);
+    let rotation = Matrix4f32::new().init_rotation(pose.rot.0, pose.rot.1, pose.rot.2);
+    let transform = projection.mul(&translation.mul(&rotation));
+
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.clear();
+    render_context.draw_multi_mesh(&multi_mesh, &transform);
+
+    render_context.into_target()
+}
+
+//NOTE(dustin): opaque sphere behind a half transparent monkey and a red additive sphere, both in one transparent pass
+fn render_transparent() -> Framebuffer {
+    let sphere = Mesh::from_path(manifest_path("assets/sphere.obj").to_str().unwrap().to_string()).unwrap();
+    let monkey = Mesh::from_path(manifest_path("assets/monkey2.obj").to_str().unwrap().to_string()).unwrap();
+    let texture = BitmapTexture::from_orbimage(&Image::from_path(manifest_path("assets/img2.png")).unwrap());
+
+    let mut glass = BitmapTexture::new(1, 1);
+    glass.set_pixel(0, 0, 128, 40, 200, 255);
+    let mut glow = BitmapTexture::new(1, 1);
+    glow.set_pixel(0, 0, 160, 255, 40, 0);
+
+    let projection = Matrix4f32::new().init_perspective(70.0_f32.to_radians(), WIDTH as f32 / HEIGHT as f32, 0.1_f32, 1000_f32);
+    let pose = |x: f32, z: f32, rot_y: f32| projection.mul(&Matrix4f32::new().init_translation(x, 0_f32, z).mul(&Matrix4f32::new().init_rotation(0_f32, rot_y, 0_f32)));
+
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.clear();
+    render_context.draw_mesh(&sphere, &pose(0_f32, 5_f32, -0.5_f32), &texture);
+    render_context.draw_transparent(&[
+        TransparentDraw { mesh: &monkey, transform: pose(-0.4_f32, 3_f32, PI), texture: &glass, blend_mode: BlendMode::AlphaBlend },
+        TransparentDraw { mesh: &sphere, transform: pose(0.8_f32, 3.5_f32, 0_f32), texture: &glow, blend_mode: BlendMode::Additive }
+    ]);
+
+    render_context.into_target()
+}
+
+//NOTE(dustin): three monkeys on a turned pivot around a sphere, the point light sits on the pivot and the third monkey is half transparent
+fn render_scene() -> Framebuffer {
+    let load_material = |name: &str, image: &str, dissolve: f32| {
+        let mut material = Material::new(name);
+        material.diffuse_texture = Some(BitmapTexture::from_orbimage(&Image::from_path(manifest_path(image)).unwrap()));
+        material.dissolve = dissolve;
+        material.specular = Vector4f32::new(0.5_f32, 0.5_f32, 0.5_f32, 0_f32);
+        material.specular_exponent = 16_f32;
+        material
+    };
+    let up = Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32);
+
+    let mut scene = Scene::new();
+    let sphere_mesh = scene.add_mesh(Mesh::from_path(manifest_path("assets/sphere.obj").to_str().unwrap().to_string()).unwrap());
+    let monkey_mesh = scene.add_mesh(Mesh::from_path(manifest_path("assets/monkey2.obj").to_str().unwrap().to_string()).unwrap());
+    let sphere_material = scene.add_material(load_material("sphere", "assets/img2.png", 1_f32));
+    let monkey_material = scene.add_material(load_material("monkey", "assets/img.png", 1_f32));
+    let glass_material = scene.add_material(load_material("glass", "assets/img.png", 0.5_f32));
+
+    scene.add_node(Node::new("sphere").init_mesh(sphere_mesh).init_material(sphere_material), None);
+    let pivot = scene.add_node(Node::new("pivot").init_transform(Transform::new().init_rotation(Quaternion::from_axis_angle(&up, 0.4_f32))), None);
+    for monkey_idx in 0..3 {
+        let orbit = scene.add_node(Node::new("orbit").init_transform(Transform::new()
+            .init_rotation(Quaternion::from_axis_angle(&up, monkey_idx as f32 * 2.094_f32))), Some(pivot));
+        let material = if monkey_idx == 2 { glass_material } else { monkey_material };
+        scene.add_node(Node::new("monkey").init_mesh(monkey_mesh).init_material(material).init_transform(Transform::new()
+            .init_translation(0_f32, 0_f32, -2_f32)
+            .init_rotation(Quaternion::from_axis_angle(&up, PI))
+            .init_scale(0.6_f32, 0.6_f32, 0.6_f32)), Some(orbit));
+    }
+
+    scene.add_node(Node::new("ambient").init_light(Light::Ambient { color: Vector4f32::new(0.2_f32, 0.2_f32, 0.2_f32, 0_f32) }), None);
+    scene.add_node(Node::new("sun").init_light(Light::Directional { direction: Vector4f32::new(1_f32, -1_f32, 1_f32, 0_f32), color: Vector4f32::new(0.7_f32, 0.7_f32, 0.6_f32, 0_f32) }), None);
+    scene.add_node(Node::new("red").init_light(Light::Point { position: Vector4f32::new(0_f32, 0_f32, -1.5_f32, 1_f32), color: Vector4f32::new(1_f32, 0.2_f32, 0_f32, 0_f32), attenuation: Attenuation::new(0_f32, 0_f32, 0.5_f32) }), Some(pivot));
+
+    let mut camera = Camera::new(Vector4f32::new(0_f32, 2_f32, -5_f32, 1_f32), Projection::Perspective { fov: 70.0_f32.to_radians(), aspect_ratio: WIDTH as f32 / HEIGHT as f32, z_near: 0.1_f32, z_far: 1000_f32 });
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &up);
+
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.clear();
+    render_context.draw_scene(&scene, &camera);
+
+    render_context.into_target()
+}
+
+fn write_ppm(path: &PathBuf, width: i32, height: i32, pixels: &[u32]) {
+    let mut bytes = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for pixel in pixels {
+        bytes.push((pixel >> 16) as u8);
+        bytes.push((pixel >> 8) as u8);
+        bytes.push(*pixel as u8);
+    }
+
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    File::create(path).unwrap().write_all(&bytes).unwrap();
+}
+
+fn read_ppm(path: &PathBuf) -> Option<(i32, i32, Vec<u32>)> {
+    let mut bytes = Vec::new();
+    File::open(path).ok()?.read_to_end(&mut bytes).ok()?;
+
+    //NOTE(dustin): only the binary P6 header without comments as written by write_ppm
+    let mut fields = Vec::new();
+    let mut idx = 0;
+    while fields.len() < 4 {
+        while bytes[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+        let start = idx;
+        while !bytes[idx].is_ascii_whitespace() {
+            idx += 1;
+        }
+        fields.push(String::from_utf8_lossy(&bytes[start..idx]).into_owned());
+    }
+    idx += 1;
+
+    assert_eq!(fields[0], "P6", "unsupported reference image {:?}", path);
+    let width: i32 = fields[1].parse().unwrap();
+    let height: i32 = fields[2].parse().unwrap();
+
+    let pixels = bytes[idx..].chunks(3)
+        .map(|rgb| 0xFF000000 | ((rgb[0] as u32) << 16) | ((rgb[1] as u32) << 8) | rgb[2] as u32)
+        .collect();
+
+    Some((width, height, pixels))
+}
+
+fn channel_diff(a: u32, b: u32, shift: u32) -> i32 {
+    (((a >> shift) & 0xFF) as i32 - ((b >> shift) & 0xFF) as i32).abs()
+}
+
+fn check_golden(name: &str, target: &Framebuffer) {
+    let actual: Vec<u32> = target.color.iter().map(|color| color.data | 0xFF000000).collect();
+    let reference_path = manifest_path(&format!("tests/golden/{}.ppm", name));
+
+    if env::var("PIXELCANNON_BLESS").is_ok() {
+        write_ppm(&reference_path, target.width, target.height, &actual);
+        return;
+    }
+
+    let (width, height, expected) = read_ppm(&reference_path)
+        .unwrap_or_else(|| panic!("missing reference image {:?}, run with PIXELCANNON_BLESS=1 to create it", reference_path));
+    assert_eq!((width, height), (target.width, target.height), "reference image {} has a different size", name);
+
+    let mut mismatched = 0;
+    let mut diff = vec![0xFF000000_u32; actual.len()];
+    for idx in 0..actual.len() {
+        let max_diff = channel_diff(actual[idx], expected[idx], 16)
+            .max(channel_diff(actual[idx], expected[idx], 8))
+            .max(channel_diff(actual[idx], expected[idx], 0));
+
+        if max_diff > CHANNEL_TOLERANCE {
+            mismatched += 1;
+            diff[idx] = 0xFFFF0000;
+        } else if max_diff > 0 {
+            diff[idx] = 0xFFFFFF00;
+        }
+    }
+
+    if mismatched > MAX_MISMATCHED_PIXELS {
+        let out_dir = manifest_path("target/golden");
+        write_ppm(&out_dir.join(format!("{}.actual.ppm", name)), width, height, &actual);
+        write_ppm(&out_dir.join(format!("{}.diff.ppm", name)), width, height, &diff);
+
+        panic!("{}: {} pixels differ from the reference by more than {}, see {:?}", name, mismatched, CHANNEL_TOLERANCE, out_dir);
+    }
+}
+
+#[test]
+fn sphere_img2_front() {
+    let pose = Pose { trans: (0_f32, 0_f32, 4_f32), rot: (0_f32, -0.5_f32, 0_f32) };
+    check_golden("sphere_img2_front", &render("assets/sphere.obj", "assets/img2.png", &pose, 1));
+}
+
+#[test]
+fn sphere_img_tilted() {
+    let pose = Pose { trans: (0.5_f32, -0.3_f32, 3_f32), rot: (0.6_f32, 1.2_f32, 0.3_f32) };
+    check_golden("sphere_img_tilted", &render("assets/sphere.obj", "assets/img.png", &pose, 1));
+}
+
+#[test]
+fn sphere_img2_bilinear_close() {
+    let pose = Pose { trans: (0_f32, 0_f32, 1.3_f32), rot: (0_f32, -0.5_f32, 0_f32) };
+    check_golden("sphere_img2_bilinear_close", &render_filtered("assets/sphere.obj", "assets/img2.png", &pose, 1, Filter::Bilinear));
+}
+
+#[test]
+fn sphere_img2_trilinear_far() {
+    let pose = Pose { trans: (0_f32, 0_f32, 12_f32), rot: (0.4_f32, -0.5_f32, 0_f32) };
+    check_golden("sphere_img2_trilinear_far", &render_filtered("assets/sphere.obj", "assets/img2.png", &pose, 1, Filter::Trilinear));
+}
+
+#[test]
+fn monkey0_img_front() {
+    let pose = Pose { trans: (0_f32, 0_f32, 3_f32), rot: (0_f32, PI, 0_f32) };
+    check_golden("monkey0_img_front", &render("assets/monkey0.obj", "assets/img.png", &pose, 1));
+}
+
+#[test]
+fn monkey1_img2_side() {
+    let pose = Pose { trans: (0_f32, 0_f32, 3_f32), rot: (0.2_f32, 2.0_f32, 0_f32) };
+    check_golden("monkey1_img2_side", &render("assets/monkey1.obj", "assets/img2.png", &pose, 1));
+}
+
+#[test]
+fn monkey2_img_close() {
+    let pose = Pose { trans: (0_f32, 0_f32, 1.2_f32), rot: (0_f32, PI, 0_f32) };
+    check_golden("monkey2_img_close", &render("assets/monkey2.obj", "assets/img.png", &pose, 1));
+}
+
+#[test]
+fn monkey2_img_gouraud() {
+    let pose = Pose { trans: (0_f32, 0_f32, 3_f32), rot: (0_f32, PI, 0_f32) };
+    check_golden("monkey2_img_gouraud", &render_lit("assets/monkey2.obj", "assets/img.png", &pose, ShadingMode::Gouraud));
+}
+
+#[test]
+fn monkey2_img_blinn_phong() {
+    let pose = Pose { trans: (0_f32, 0_f32, 3_f32), rot: (0_f32, PI, 0_f32) };
+    check_golden("monkey2_img_blinn_phong", &render_lit("assets/monkey2.obj", "assets/img.png", &pose, ShadingMode::BlinnPhong));
+}
+
+#[test]
+fn cube_multi_material() {
+    let pose = Pose { trans: (0_f32, 0_f32, 4_f32), rot: (-0.5_f32, 2.3_f32, 0_f32) };
+    check_golden("cube_multi_material", &render_multi("assets/cube.obj", &pose));
+}
+
+#[test]
+fn transparent_pass() {
+    check_golden("transparent_pass", &render_transparent());
+}
+
+#[test]
+fn scene_orbiting_monkeys() {
+    check_golden("scene_orbiting_monkeys", &render_scene());
+}
+
+#[test]
+fn sphere_img_alpha_cutout() {
+    let mesh = Mesh::from_path(manifest_path("assets/sphere.obj").to_str().unwrap().to_string()).unwrap();
+    let texture = BitmapTexture::from_orbimage(&Image::from_path(manifest_path("assets/img.png")).unwrap());
+    let transform = Matrix4f32::new().init_perspective(70.0_f32.to_radians(), WIDTH as f32 / HEIGHT as f32, 0.1_f32, 1000_f32)
+        .mul(&Matrix4f32::new().init_translation(0_f32, 0_f32, 3_f32).mul(&Matrix4f32::new().init_rotation(0.6_f32, 1.2_f32, 0_f32)));
+
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.set_blend_state(BlendState { mode: BlendMode::Replace, alpha_threshold: Some(128) });
+    render_context.clear();
+    render_context.draw_mesh(&mesh, &transform, &texture);
+
+    check_golden("sphere_img_alpha_cutout", &render_context.into_target());
+}
+
+#[test]
+fn monkey2_img_orthographic() {
+    //NOTE(dustin): w is 1 for every vertex, so the depth test relies on z alone
+    let mesh = Mesh::from_path(manifest_path("assets/monkey2.obj").to_str().unwrap().to_string()).unwrap();
+    let texture = BitmapTexture::from_orbimage(&Image::from_path(manifest_path("assets/img.png")).unwrap());
+
+    let mut camera = Camera::new(Vector4f32::new(2_f32, 1.5_f32, 2_f32, 1_f32), Projection::orthographic(1.5_f32, WIDTH as f32 / HEIGHT as f32, 0.1_f32, 100_f32));
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32));
+    let transform = camera.get_view_projection().mul(&Matrix4f32::new().init_rotation(0_f32, PI, 0_f32));
+
+    let mut render_context = RenderContext::new(WIDTH as u32, HEIGHT as u32);
+    render_context.clear();
+    render_context.draw_mesh(&mesh, &transform, &texture);
+
+    check_golden("monkey2_img_orthographic", &render_context.into_target());
+}
+
+#[test]
+fn tiled_matches_single_threaded() {
+    let pose = Pose { trans: (0.2_f32, 0.1_f32, 1.5_f32), rot: (0.3_f32, 2.6_f32, 0_f32) };
+
+    for &(model, image) in [("assets/sphere.obj", "assets/img2.png"), ("assets/monkey2.obj", "assets/img.png")].iter() {
+        let single = render(model, image, &pose, 1);
+        for worker_count in 2..5 {
+            let tiled = render(model, image, &pose, worker_count);
+
+            assert!(single.color.iter().zip(tiled.color.iter()).all(|(a, b)| a.data == b.data),
+                "{} rendered with {} workers differs from the single threaded path", model, worker_count);
+            assert!(single.depth.data == tiled.depth.data,
+                "{} depth with {} workers differs from the single threaded path", model, worker_count);
+        }
+    }
+}
+
+#[test]
+fn halfspace_matches_scanline() {
+    let poses = [Pose { trans: (0.2_f32, 0.1_f32, 1.5_f32), rot: (0.3_f32, 2.6_f32, 0_f32) },
+                 Pose { trans: (-0.4_f32, 0.3_f32, 0.9_f32), rot: (1.1_f32, 0.4_f32, 0.2_f32) }];
+
+    for pose in poses.iter() {
+        for &(model, image) in [("assets/sphere.obj", "assets/img2.png"), ("assets/monkey2.obj", "assets/img.png"), ("assets/cube.obj", "assets/img.png")].iter() {
+            let scanline = render_with_algorithm(model, image, pose, 1, Filter::Trilinear, RasterAlgorithm::Scanline);
+            for &worker_count in [1, 3].iter() {
+                let halfspace = render_with_algorithm(model, image, pose, worker_count, Filter::Trilinear, RasterAlgorithm::HalfSpace);
+
+                assert!(scanline.color.iter().zip(halfspace.color.iter()).all(|(a, b)| a.data == b.data),
+                    "{} rendered with the half-space rasterizer and {} workers differs from the scanline path", model, worker_count);
+                assert!(scanline.depth.data == halfspace.depth.data,
+                    "{} depth with the half-space rasterizer and {} workers differs from the scanline path", model, worker_count);
+            }
+        }
+    }
+}
+
+#[test]
+fn monkey2_img_antialiased() {
+    let pose = Pose { trans: (0.2_f32, 0.1_f32, 1.5_f32), rot: (0.3_f32, 2.6_f32, 0_f32) };
+
+    let msaa = render_antialiased("assets/monkey2.obj", "assets/img.png", &pose, 1, Antialiasing::Msaa(SampleCount::X4));
+    let msaa_tiled = render_antialiased("assets/monkey2.obj", "assets/img.png", &pose, 3, Antialiasing::Msaa(SampleCount::X4));
+    assert!(msaa.color.iter().zip(msaa_tiled.color.iter()).all(|(a, b)| a.data == b.data), "multisampling differs with workers");
+    check_golden("monkey2_img_msaa4", &msaa);
+
+    let ssaa = render_antialiased("assets/monkey2.obj", "assets/img.png", &pose, 1, Antialiasing::Ssaa(ScaleFactor::X2));
+    assert_eq!((ssaa.width, ssaa.height), (WIDTH, HEIGHT));
+    check_golden("monkey2_img_ssaa2", &ssaa);
+}
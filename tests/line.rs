@@ -0,0 +1,179 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use std::path::PathBuf;
+
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::cull::Aabb;
+use pixelcannon::line::{self, LineColor, PolygonMode};
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::render::RenderContext;
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+const CLEAR: u32 = 0xFF000000;
+const RED: u32 = 0xFFFF0000;
+const BLUE: u32 = 0xFF0000FF;
+
+fn color(data: u32) -> orbclient::Color {
+    orbclient::Color { data }
+}
+
+//NOTE(dustin): with the identity transform pixel p of the 64x64 target is at p / 32 - 1
+fn pixel(x: i32, y: i32, z: f32) -> Vector4f32 {
+    Vector4f32::new(x as f32 / 32_f32 - 1_f32, 1_f32 - y as f32 / 32_f32, z, 1_f32)
+}
+
+fn identity() -> Matrix4f32 {
+    Matrix4f32::new().init_translation(0_f32, 0_f32, 0_f32)
+}
+
+fn context() -> RenderContext {
+    let mut render_context = RenderContext::new(64, 64);
+    render_context.get_target_mut().clear(color(CLEAR));
+    render_context
+}
+
+fn drawn_pixels(render_context: &RenderContext) -> Vec<(i32, i32, u32)> {
+    let target = render_context.get_target();
+    let mut pixels = Vec::new();
+    for y in 0..target.height {
+        for x in 0..target.width {
+            let data = target.get_pixel(x, y).data;
+            if data != CLEAR {
+                pixels.push((x, y, data));
+            }
+        }
+    }
+    pixels
+}
+
+#[test]
+fn clip_line_ranges() {
+    let inside = line::clip_line(&Vector4f32::new(-0.5_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(0.5_f32, 0_f32, 0_f32, 1_f32));
+    assert_eq!(inside, Some((0_f32, 1_f32)));
+
+    let right = line::clip_line(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(2_f32, 0_f32, 0_f32, 1_f32));
+    assert_eq!(right, Some((0_f32, 0.5_f32)));
+
+    let both = line::clip_line(&Vector4f32::new(-3_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(3_f32, 0_f32, 0_f32, 1_f32)).unwrap();
+    assert!((both.0 - 1_f32 / 3_f32).abs() < 1e-6_f32 && (both.1 - 2_f32 / 3_f32).abs() < 1e-6_f32, "{:?}", both);
+
+    assert_eq!(line::clip_line(&Vector4f32::new(2_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(3_f32, 0_f32, 0_f32, 1_f32)), None);
+    assert_eq!(line::clip_line(&Vector4f32::new(0_f32, 0_f32, -2_f32, 1_f32), &Vector4f32::new(0_f32, 0_f32, -3_f32, 1_f32)), None, "in front of the near plane");
+
+    //NOTE(dustin): crosses the right and the top plane but never is inside both
+    assert_eq!(line::clip_line(&Vector4f32::new(0_f32, 2.5_f32, 0_f32, 1_f32), &Vector4f32::new(2.5_f32, 0_f32, 0_f32, 1_f32)), None);
+}
+
+#[test]
+fn draw_line_pixels() {
+    let mut render_context = context();
+    render_context.draw_line(&pixel(8, 16, 0_f32), &pixel(40, 16, 0_f32), &identity(), LineColor::Constant(color(RED)), true);
+
+    let pixels = drawn_pixels(&render_context);
+    assert_eq!(pixels.len(), 33);
+    assert!(pixels.iter().all(|&(x, y, data)| y == 16 && (8..=40).contains(&x) && data == RED), "{:?}", pixels);
+
+    let mut render_context = context();
+    render_context.draw_line(&pixel(4, 4, 0_f32), &pixel(20, 36, 0_f32), &identity(), LineColor::Constant(color(RED)), true);
+
+    let pixels = drawn_pixels(&render_context);
+    assert_eq!(pixels.len(), 33, "one pixel per step along the major axis");
+    assert!(pixels.iter().any(|&(x, y, _)| (x, y) == (4, 4)) && pixels.iter().any(|&(x, y, _)| (x, y) == (20, 36)));
+}
+
+#[test]
+fn draw_line_interpolated_color() {
+    let mut render_context = context();
+    render_context.draw_line(&pixel(8, 16, 0_f32), &pixel(40, 16, 0_f32), &identity(), LineColor::Interpolated(color(RED), color(BLUE)), true);
+
+    let target = render_context.get_target();
+    assert_eq!(target.get_pixel(8, 16).data, RED);
+    assert_eq!(target.get_pixel(40, 16).data, BLUE);
+    assert_eq!(target.get_pixel(24, 16).data, 0xFF800080);
+
+    //NOTE(dustin): only the right half is on screen, the left end starts at the color halfway
+    let mut render_context = context();
+    let from = Vector4f32::new(-3_f32, 0.5_f32, 0_f32, 1_f32);
+    let to = Vector4f32::new(1_f32, 0.5_f32, 0_f32, 1_f32);
+    render_context.draw_line(&from, &to, &identity(), LineColor::Interpolated(color(RED), color(BLUE)), true);
+
+    let target = render_context.get_target();
+    assert_eq!(target.get_pixel(0, 16).data, 0xFF800080);
+    assert_eq!(target.get_pixel(63, 16).data & 0xFF, 0xFD);
+}
+
+#[test]
+fn draw_line_depth_test() {
+    for &(depth_test, line_depth, expected) in [(true, 0.8_f32, CLEAR), (true, 0.2_f32, RED), (false, 0.8_f32, RED)].iter() {
+        let mut render_context = context();
+        for depth in render_context.get_target_mut().depth.data.iter_mut() {
+            *depth = 0.5_f32;
+        }
+
+        render_context.draw_line(&pixel(8, 16, line_depth), &pixel(40, 16, line_depth), &identity(), LineColor::Constant(color(RED)), depth_test);
+        render_context.draw_point(&pixel(8, 40, line_depth), &identity(), color(RED), 1, depth_test);
+
+        let target = render_context.get_target();
+        assert_eq!(target.get_pixel(24, 16).data, expected, "line at {} with depth test {}", line_depth, depth_test);
+        assert_eq!(target.get_pixel(8, 40).data, expected, "point at {} with depth test {}", line_depth, depth_test);
+
+        let written = if depth_test && expected == RED { line_depth } else { 0.5_f32 };
+        assert_eq!(target.depth.get_depth(24, 16), written);
+    }
+}
+
+#[test]
+fn draw_point_size() {
+    let mut render_context = context();
+    render_context.draw_point(&pixel(16, 16, 0_f32), &identity(), color(RED), 3, true);
+    render_context.draw_point(&pixel(0, 0, 0_f32), &identity(), color(BLUE), 3, true);
+    render_context.draw_point(&Vector4f32::new(0_f32, 0_f32, 2_f32, 1_f32), &identity(), color(BLUE), 3, true);
+
+    let pixels = drawn_pixels(&render_context);
+    let red: Vec<(i32, i32)> = pixels.iter().filter(|&&(_, _, data)| data == RED).map(|&(x, y, _)| (x, y)).collect();
+    assert_eq!(red.len(), 9);
+    assert!(red.iter().all(|&(x, y)| (x - 16).abs() <= 1 && (y - 16).abs() <= 1));
+
+    assert_eq!(pixels.iter().filter(|&&(_, _, data)| data == BLUE).count(), 4, "clipped to the target and the far plane");
+}
+
+#[test]
+fn draw_aabb_edges() {
+    let mut render_context = context();
+    let aabb = Aabb { min: pixel(8, 40, 0_f32), max: pixel(40, 8, 0.5_f32) };
+    render_context.draw_aabb(&aabb, &identity(), color(RED), false);
+
+    let pixels = drawn_pixels(&render_context);
+    assert!(pixels.iter().all(|&(x, y, _)| x == 8 || x == 40 || y == 8 || y == 40), "only the outline without perspective");
+    assert_eq!(pixels.len(), 4 * 32);
+}
+
+#[test]
+fn polygon_modes() {
+    let mesh = Mesh::from_path(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/cube.obj").to_str().unwrap().to_string()).unwrap();
+    let camera = Camera::new(Vector4f32::new(1.5_f32, 2_f32, -4_f32, 1_f32), Projection::Perspective { fov: 70.0_f32.to_radians(), aspect_ratio: 1_f32, z_near: 0.1_f32, z_far: 100_f32 });
+    let mut camera = camera;
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32));
+
+    let mut texture = BitmapTexture::new(1, 1);
+    texture.set_pixel(0, 0, 255, 255, 0, 0);
+
+    let mut counts = Vec::new();
+    for &mode in [PolygonMode::Fill, PolygonMode::Line, PolygonMode::Point].iter() {
+        let mut render_context = context();
+        render_context.set_polygon_mode(mode);
+        render_context.set_wire_color(color(BLUE));
+        assert_eq!(render_context.get_polygon_mode(), mode);
+
+        render_context.draw_mesh(&mesh, &camera.get_view_projection(), &texture);
+        let pixels = drawn_pixels(&render_context);
+        let expected = if mode == PolygonMode::Fill { RED } else { BLUE };
+        assert!(pixels.iter().all(|&(_, _, data)| data == expected), "{:?} uses the wrong color", mode);
+        counts.push(pixels.len());
+    }
+
+    assert!(counts[0] > counts[1] && counts[1] > counts[2] && counts[2] > 0, "{:?}", counts);
+}
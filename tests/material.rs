@@ -0,0 +1,179 @@
+extern crate pixelcannon;
+
+use std::fs;
+use std::path::PathBuf;
+
+use pixelcannon::blend::BlendState;
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::model::material::MTLLibrary;
+use pixelcannon::model::multimesh::MultiMesh;
+use pixelcannon::model::obj::OBJErrorKind;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::render::RenderContext;
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+fn manifest_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+//NOTE(dustin): a fresh directory per test, the tests run in parallel
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pixelcannon_material_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn parses_values() {
+    let library = MTLLibrary::parse("# comment\nnewmtl glass pane\nKa 0.1 0.2 0.3\nKs 0.5\nNs 16\nillum 1\nTr 0.25\nmap_Bump -bm 0.5 bump.png\nnorm normal.png\nmap_d alpha.png\n", "test.mtl").unwrap();
+
+    assert_eq!(library.materials.len(), 1);
+    let material = &library.materials[0];
+    assert_eq!(material.name, "glass pane");
+    assert_eq!((material.ambient.x, material.ambient.y, material.ambient.z), (0.1_f32, 0.2_f32, 0.3_f32));
+    assert_eq!((material.specular.x, material.specular.y, material.specular.z), (0.5_f32, 0.5_f32, 0.5_f32));
+    assert_eq!(material.specular_exponent, 16_f32);
+    assert_eq!(material.illum, 1);
+    assert_eq!(material.dissolve, 0.75_f32);
+    assert_eq!(material.bump_multiplier, 0.5_f32);
+    assert_eq!(material.bump_map, Some("bump.png".to_string()));
+    assert_eq!(material.normal_map, Some("normal.png".to_string()));
+    assert_eq!(material.alpha_map, Some("alpha.png".to_string()));
+
+    //NOTE(dustin): parse does not load maps
+    assert!(material.bump_texture.is_none() && material.normal_texture.is_none() && material.alpha_texture.is_none());
+    assert!(library.warnings.is_empty());
+}
+
+#[test]
+fn invalid_number_reports_position() {
+    let err = MTLLibrary::parse("newmtl a\nKd 1 x 1\n", "test.mtl").err().unwrap();
+
+    assert_eq!((err.file.as_str(), err.line, err.column), ("test.mtl", 2, 6));
+    match err.kind {
+        OBJErrorKind::InvalidNumber(value) => assert_eq!(value, "x"),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn bump_multiplier_without_value_reports_position() {
+    for source in ["newmtl a\nbump -bm\n", "newmtl a\nmap_Bump -bm bump.png\n"].iter() {
+        let err = MTLLibrary::parse(source, "test.mtl").err().unwrap();
+
+        assert_eq!((err.line, err.column), (2, source.lines().nth(1).unwrap().find("-bm").unwrap() + 1));
+        match err.kind {
+            OBJErrorKind::MissingValues { ref statement, expected: 1, found: 0 } if statement == "-bm" => (),
+            kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+}
+
+#[test]
+fn unsupported_statement_is_a_warning() {
+    let library = MTLLibrary::parse("newmtl a\nKd 1 1 1\nNi 1.5\n", "test.mtl").unwrap();
+
+    assert_eq!(library.materials.len(), 1);
+    assert_eq!(library.warnings.len(), 1);
+    assert_eq!((library.warnings[0].file.as_str(), library.warnings[0].line), ("test.mtl", 3));
+    assert!(library.warnings[0].message.contains("'Ni'"));
+}
+
+#[test]
+fn statement_before_newmtl_is_an_error() {
+    let err = MTLLibrary::parse("Kd 1 1 1\nnewmtl a\n", "test.mtl").err().unwrap();
+
+    assert_eq!((err.line, err.column), (1, 1));
+    match err.kind {
+        OBJErrorKind::Material(_) => (),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn loads_maps_next_to_the_library() {
+    let library = MTLLibrary::from_path(manifest_path("assets/cube.mtl")).unwrap();
+
+    assert_eq!(library.materials.len(), 3);
+    assert!(library.materials[0].diffuse_texture.is_some());
+    assert_eq!(library.materials[1].illum, 1);
+    assert_eq!(library.materials[2].ambient.x, 0.2_f32);
+    assert!(library.warnings.is_empty());
+}
+
+#[test]
+fn missing_texture_is_a_warning() {
+    let dir = temp_dir("missing_texture");
+    let mtl_path = dir.join("missing.mtl");
+    fs::write(&mtl_path, "newmtl a\nKd 1 1 1\nmap_Kd nowhere.png\n").unwrap();
+
+    let library = MTLLibrary::from_path(&mtl_path).unwrap();
+    assert_eq!(library.materials[0].diffuse_map, Some("nowhere.png".to_string()));
+    assert!(library.materials[0].diffuse_texture.is_none());
+    assert_eq!(library.warnings.len(), 1);
+    assert_eq!(library.warnings[0].line, 3);
+    assert!(library.warnings[0].message.contains("nowhere.png"));
+}
+
+#[test]
+fn missing_material_library_is_a_warning() {
+    let dir = temp_dir("missing_library");
+    let obj_path = dir.join("triangle.obj");
+    fs::write(&obj_path, "mtllib nowhere.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nusemtl a\nf 1 2 3\n").unwrap();
+
+    let multi_mesh = MultiMesh::from_path(obj_path.to_str().unwrap().to_string()).unwrap();
+    assert_eq!(multi_mesh.sub_meshes.len(), 1);
+    assert!(multi_mesh.get_material(&multi_mesh.sub_meshes[0]).is_none());
+    assert_eq!(multi_mesh.warnings.len(), 1);
+    assert_eq!(multi_mesh.warnings[0].line, 1);
+    assert!(multi_mesh.warnings[0].message.contains("nowhere.mtl"));
+}
+
+//NOTE(dustin): the center pixel of the cube with a half covered alpha map on every material
+fn render_alpha_mapped_cube(blend: BlendState) -> u32 {
+    let mut multi_mesh = MultiMesh::from_path(manifest_path("assets/cube.obj").to_str().unwrap().to_string()).unwrap();
+    for material in multi_mesh.materials.iter_mut() {
+        let mut alpha_texture = BitmapTexture::new(1, 1);
+        alpha_texture.set_pixel(0, 0, 255, 128, 128, 128);
+        material.diffuse_texture = None;
+        material.alpha_texture = Some(alpha_texture);
+    }
+
+    let mut camera = Camera::new(Vector4f32::new(0_f32, 0_f32, -5_f32, 1_f32), Projection::Perspective { fov: 1.2_f32, aspect_ratio: 1_f32, z_near: 0.1_f32, z_far: 100_f32 });
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32));
+
+    let mut render_context = RenderContext::new(40, 40);
+    render_context.set_blend_state(blend);
+    render_context.clear();
+    let clear_color = render_context.get_target().get_pixel(20, 20).data;
+    render_context.draw_multi_mesh(&multi_mesh, &camera.get_view_projection());
+
+    let pixel = render_context.into_target().get_pixel(20, 20).data;
+    assert!(pixel != clear_color || blend.alpha_threshold.is_some(), "the cube has to cover the center");
+    pixel
+}
+
+#[test]
+fn alpha_map_cuts_out_with_threshold() {
+    let mut blend = BlendState::new();
+
+    blend.alpha_threshold = Some(200);
+    assert_eq!(render_alpha_mapped_cube(blend), 0xFF220CE8);
+
+    //NOTE(dustin): above the threshold the fragment replaces the target with the alpha of the map
+    blend.alpha_threshold = Some(100);
+    assert_eq!(render_alpha_mapped_cube(blend), 0x80FFFFFF);
+}
+
+#[test]
+fn alpha_map_blends_without_threshold() {
+    let pixel = render_alpha_mapped_cube(BlendState::new());
+
+    //NOTE(dustin): white over the clear color at half coverage
+    let red = (pixel >> 16) & 0xFF;
+    let blue = pixel & 0xFF;
+    assert_eq!(pixel >> 24, 0xFF);
+    assert!(red > 0x80 && red < 0xA0, "expected half white over 0x22, got {:x}", pixel);
+    assert!(blue > 0xF0, "expected half white over 0xE8, got {:x}", pixel);
+}
@@ -0,0 +1,107 @@
+extern crate pixelcannon;
+
+use pixelcannon::primitive::mat::{Mat3, Mat4};
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::scalar::Float;
+use pixelcannon::primitive::vec::{Vec2, Vec3, Vec4};
+use pixelcannon::primitive::vector::Vector4f32;
+
+const EPSILON: f32 = 1e-4;
+
+#[test]
+fn vector_operators() {
+    let a = Vec3::new(1_f32, 2_f32, 3_f32);
+    let b = Vec3::new(-2_f32, 0.5_f32, 4_f32);
+
+    assert_eq!(a + b, Vec3::new(-1_f32, 2.5_f32, 7_f32));
+    assert_eq!(a - b, Vec3::new(3_f32, 1.5_f32, -1_f32));
+    assert_eq!(a * 2_f32, Vec3::new(2_f32, 4_f32, 6_f32));
+    assert_eq!(-a, Vec3::new(-1_f32, -2_f32, -3_f32));
+    assert_eq!(a.mul_v(&b), Vec3::new(-2_f32, 1_f32, 12_f32));
+    assert_eq!(a.dot(&b), 11_f32);
+
+    //NOTE(dustin): integer vectors work too as long as nothing needs a square root
+    let mut pixel = Vec2::new(3, -4);
+    pixel[1] = 5;
+    assert_eq!(pixel[0] + pixel[1], 8);
+    assert_eq!(pixel.perp_dot(&Vec2::new(1, 0)), -5);
+}
+
+#[test]
+#[should_panic]
+fn vector_index_out_of_range() {
+    let vector = Vec3::new(1_f32, 2_f32, 3_f32);
+    let _ = vector[3];
+}
+
+#[test]
+fn vector_helpers() {
+    let a = Vec4::new(0_f32, 2_f32, -4_f32, 1_f32);
+    let b = Vec4::new(2_f32, -2_f32, 0_f32, 1_f32);
+
+    assert_eq!(a.lerp(&b, 0.25_f32), Vec4::new(0.5_f32, 1_f32, -3_f32, 1_f32));
+    assert_eq!(a.min(&b), Vec4::new(0_f32, -2_f32, -4_f32, 1_f32));
+    assert_eq!(a.max(&b), Vec4::new(2_f32, 2_f32, 0_f32, 1_f32));
+    assert_eq!(a.clamp(&Vec4::splat(-1_f32), &Vec4::splat(1_f32)), Vec4::new(0_f32, 1_f32, -1_f32, 1_f32));
+
+    assert!((Vec3::new(3_f32, 0_f32, 4_f32).length() - 5_f32).abs() < EPSILON);
+    assert!((Vec3::new(3_f32, 1_f32, 4_f32).normalized().length() - 1_f32).abs() < EPSILON);
+    assert!(Vec2::new(1_f32, 1_f32).approx_eq(&Vec2::new(1.00001_f32, 0.99999_f32), EPSILON));
+    assert!(!Vec2::new(1_f32, 1_f32).approx_eq(&Vec2::new(1.1_f32, 1_f32), EPSILON));
+    assert!(1.000001_f32.approx_eq(1_f32, f32::epsilon()) && !1.001_f32.approx_eq(1_f32, f32::epsilon()));
+}
+
+#[test]
+fn reflect_and_cross() {
+    //NOTE(dustin): a ray going down and right bounces off the floor going up and right
+    let reflected = Vec3::new(1_f32, -1_f32, 0_f32).reflect(&Vec3::new(0_f32, 1_f32, 0_f32));
+    assert_eq!(reflected, Vec3::new(1_f32, 1_f32, 0_f32));
+
+    let x = Vec3::new(1_f32, 0_f32, 0_f32);
+    let y = Vec3::new(0_f32, 1_f32, 0_f32);
+    assert_eq!(x.cross(&y), Vec3::new(0_f32, 0_f32, 1_f32));
+    assert_eq!(x.cross(&y), Vec3::from(Vector4f32::new(1_f32, 0_f32, 0_f32, 0_f32).cross(&Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32))));
+}
+
+#[test]
+fn matrix_operators() {
+    let a = Mat3::new([[1_f32, 2_f32, 0_f32], [0_f32, 1_f32, 3_f32], [4_f32, 0_f32, 1_f32]]);
+
+    assert_eq!(a * Mat3::identity(), a);
+    assert_eq!(a + a, a * 2_f32);
+    assert_eq!(a - a, Mat3::zero());
+    assert_eq!(-a + a, Mat3::zero());
+    assert_eq!(a[2][0], 4_f32);
+    assert_eq!(a.transpose()[0][2], 4_f32);
+    assert_eq!(a.row(1), Vec3::new(0_f32, 1_f32, 3_f32));
+    assert_eq!(a.col(1), Vec3::new(2_f32, 1_f32, 0_f32));
+    assert_eq!(a * Vec3::new(1_f32, 1_f32, 1_f32), Vec3::new(3_f32, 4_f32, 5_f32));
+    assert_eq!(a.determinant(), 25_f32);
+
+    let mut b = Mat3::identity();
+    b[0][1] = 2_f32;
+    assert_eq!((a * b)[2][1], 8_f32);
+}
+
+#[test]
+fn conversions_match_existing_types() {
+    let matrix = Matrix4f32::new().init_rotation(0.3_f32, -0.4_f32, 1_f32)
+        .mul(&Matrix4f32::new().init_translation(1_f32, 2_f32, 3_f32));
+    let other = Matrix4f32::new().init_perspective(1.2_f32, 1.5_f32, 0.1_f32, 100_f32);
+    let vector = Vector4f32::new(0.5_f32, -1_f32, 2_f32, 1_f32);
+
+    let product = Mat4::from(matrix) * Mat4::from(other);
+    assert!(product.approx_eq(&Mat4::from(matrix.mul(&other)), EPSILON));
+    assert!((Mat4::from(matrix) * Vec4::from(vector)).approx_eq(&Vec4::from(matrix.transform(&vector)), EPSILON));
+
+    let back = Matrix4f32::from(product);
+    assert_eq!(back.m, product.m);
+    let round_trip = Vector4f32::from(Vec4::from(vector));
+    assert_eq!((round_trip.x, round_trip.y, round_trip.z, round_trip.w), (vector.x, vector.y, vector.z, vector.w));
+
+    //NOTE(dustin): the upper 3x3 keeps the rotation and drops the translation
+    let rotation = Mat4::from(matrix).truncate();
+    assert!((rotation.determinant() - 1_f32).abs() < EPSILON);
+    assert_eq!(rotation.extend()[0][3], 0_f32);
+    assert!((rotation * Vec3::from(vector)).approx_eq(&(Mat4::from(matrix) * Vec3::from(vector).extend(0_f32)).truncate(), EPSILON));
+}
@@ -0,0 +1,128 @@
+extern crate pixelcannon;
+
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::vector::Vector4f32;
+
+const EPSILON: f32 = 1e-4;
+
+fn identity() -> Matrix4f32 {
+    Matrix4f32::new().init_translation(0_f32, 0_f32, 0_f32)
+}
+
+fn assert_matrix(actual: &Matrix4f32, expected: &Matrix4f32) {
+    for r_idx in 0..4 {
+        for c_idx in 0..4 {
+            assert!((actual.m[r_idx][c_idx] - expected.m[r_idx][c_idx]).abs() < EPSILON,
+                "m[{}][{}] expected {}, got {}", r_idx, c_idx, expected.m[r_idx][c_idx], actual.m[r_idx][c_idx]);
+        }
+    }
+}
+
+fn assert_vector(actual: &Vector4f32, expected: &Vector4f32) {
+    assert!(actual.sub_v(expected).length() < EPSILON, "expected {:?}, got {:?}", expected, actual);
+}
+
+fn trs(translation: (f32, f32, f32), rotation: (f32, f32, f32), scale: (f32, f32, f32)) -> Matrix4f32 {
+    Matrix4f32::new().init_translation(translation.0, translation.1, translation.2)
+        .mul(&Matrix4f32::new().init_rotation(rotation.0, rotation.1, rotation.2))
+        .mul(&Matrix4f32::new().init_scale(scale.0, scale.1, scale.2))
+}
+
+fn general() -> Matrix4f32 {
+    let mut matrix = Matrix4f32::new();
+    matrix.m = [[2_f32, -1_f32, 0.5_f32, 3_f32],
+                [0_f32, 4_f32, 1_f32, -2_f32],
+                [1_f32, 0.25_f32, -3_f32, 1_f32],
+                [0.5_f32, 2_f32, 1_f32, 1_f32]];
+    matrix
+}
+
+#[test]
+fn transpose() {
+    let transposed = general().transpose();
+
+    assert_eq!(transposed.m[0][3], 0.5_f32);
+    assert_eq!(transposed.m[3][0], 3_f32);
+    assert_matrix(&transposed.transpose(), &general());
+}
+
+#[test]
+fn determinant() {
+    assert!((identity().determinant() - 1_f32).abs() < EPSILON);
+    assert!((Matrix4f32::new().init_scale(2_f32, 3_f32, -4_f32).determinant() + 24_f32).abs() < EPSILON);
+    assert!((Matrix4f32::new().init_rotation(0.3_f32, 1.1_f32, -0.7_f32).determinant() - 1_f32).abs() < EPSILON);
+
+    //NOTE(dustin): by cofactor expansion by hand
+    assert!((general().determinant() - -37.5_f32).abs() < EPSILON);
+    assert!((general().transpose().determinant() - general().determinant()).abs() < EPSILON);
+}
+
+#[test]
+fn inverse() {
+    for matrix in [general(), trs((1_f32, -2_f32, 3_f32), (0.3_f32, 1.1_f32, -0.7_f32), (2_f32, 0.5_f32, 1.5_f32)),
+                   Matrix4f32::new().init_perspective(1.2_f32, 1.5_f32, 0.1_f32, 100_f32)].iter() {
+        let inverse = matrix.inverse().expect("matrix is invertible");
+
+        assert_matrix(&matrix.mul(&inverse), &identity());
+        assert_matrix(&inverse.mul(matrix), &identity());
+    }
+
+    //NOTE(dustin): picking, a point pushed through the view projection comes back through the inverse
+    let view_projection = Matrix4f32::new().init_perspective(1.2_f32, 1.5_f32, 0.1_f32, 100_f32)
+        .mul(&Matrix4f32::new().init_translation(0_f32, 0_f32, 4_f32));
+    let clip = view_projection.transform(&Vector4f32::new(0.5_f32, -0.25_f32, 1_f32, 1_f32));
+    let back = view_projection.inverse().unwrap().transform(&clip);
+    assert_vector(&back, &Vector4f32::new(0.5_f32, -0.25_f32, 1_f32, 1_f32));
+}
+
+#[test]
+fn singular_matrices_have_no_inverse() {
+    assert!(Matrix4f32::new().inverse().is_none());
+    assert!(Matrix4f32::new().init_scale(1_f32, 0_f32, 1_f32).inverse().is_none());
+
+    let mut repeated_row = general();
+    repeated_row.m[2] = repeated_row.m[0];
+    assert!(repeated_row.inverse().is_none() || repeated_row.determinant().abs() < EPSILON);
+}
+
+#[test]
+fn inverse_transpose_matches_normal_matrix() {
+    let model = trs((1_f32, 2_f32, 3_f32), (0.4_f32, -0.2_f32, 0.9_f32), (1_f32, 3_f32, 0.5_f32));
+    let inverse_transpose = model.inverse().unwrap().transpose();
+    let normal_matrix = model.normal_matrix();
+
+    for r_idx in 0..3 {
+        for c_idx in 0..3 {
+            assert!((inverse_transpose.m[r_idx][c_idx] - normal_matrix.m[r_idx][c_idx]).abs() < EPSILON);
+        }
+    }
+}
+
+#[test]
+fn decompose() {
+    let matrix = trs((1_f32, -2_f32, 3_f32), (0.3_f32, 1.1_f32, -0.7_f32), (2_f32, 0.5_f32, 1.5_f32));
+    let (translation, rotation, scale) = matrix.decompose().unwrap();
+
+    assert_vector(&translation, &Vector4f32::new(1_f32, -2_f32, 3_f32, 0_f32));
+    assert_vector(&scale, &Vector4f32::new(2_f32, 0.5_f32, 1.5_f32, 0_f32));
+    assert_matrix(&rotation.to_matrix(), &Matrix4f32::new().init_rotation(0.3_f32, 1.1_f32, -0.7_f32));
+
+    let recomposed = Matrix4f32::new().init_translation(translation.x, translation.y, translation.z)
+        .mul(&rotation.to_matrix())
+        .mul(&Matrix4f32::new().init_scale(scale.x, scale.y, scale.z));
+    assert_matrix(&recomposed, &matrix);
+}
+
+#[test]
+fn decompose_mirrored_and_degenerate() {
+    let mirrored = trs((0_f32, 0_f32, 0_f32), (0_f32, 0.5_f32, 0_f32), (1_f32, 2_f32, -3_f32));
+    let (_, rotation, scale) = mirrored.decompose().unwrap();
+
+    //NOTE(dustin): the mirroring moves to x, the rotation stays a proper rotation
+    assert!(scale.x < 0_f32 && scale.y > 0_f32 && scale.z > 0_f32);
+    assert!((rotation.length() - 1_f32).abs() < EPSILON);
+    let recomposed = rotation.to_matrix().mul(&Matrix4f32::new().init_scale(scale.x, scale.y, scale.z));
+    assert_matrix(&recomposed, &mirrored);
+
+    assert!(Matrix4f32::new().init_scale(1_f32, 0_f32, 1_f32).decompose().is_none());
+}
@@ -0,0 +1,234 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pixelcannon::blend::{BlendMode, BlendState};
+use pixelcannon::depth::DepthFunc;
+use pixelcannon::line::LineColor;
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::primitive::vertex::Vertex;
+use pixelcannon::render::RenderContext;
+use pixelcannon::shader::{Fragment, FragmentShader, TransformShader};
+use pixelcannon::target::framebuffer::Framebuffer;
+use pixelcannon::target::multisample::{self, Antialiasing, SampleCount, ScaleFactor};
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+const SIZE: u32 = 32;
+const CLEAR: u32 = 0xFF000000;
+const WHITE: u32 = 0xFFFFFFFF;
+
+fn identity() -> Matrix4f32 {
+    Matrix4f32::new().init_translation(0_f32, 0_f32, 0_f32)
+}
+
+fn context(antialiasing: Antialiasing) -> RenderContext {
+    let mut render_context = RenderContext::with_antialiasing(SIZE, SIZE, antialiasing);
+    render_context.get_target_mut().clear(orbclient::Color { data: CLEAR });
+    render_context
+}
+
+fn texture(a: u8, r: u8, g: u8, b: u8) -> BitmapTexture {
+    let mut texture = BitmapTexture::new(1, 1);
+    texture.set_pixel(0, 0, a, r, g, b);
+    texture
+}
+
+//NOTE(dustin): takes a position in pixels of the output, pixel centers are at whole numbers
+fn vertex(x: f32, y: f32, z: f32) -> Vertex {
+    let half_size = SIZE as f32 / 2_f32;
+    Vertex::new_with_pos_and_texcoords(Vector4f32::new(x / half_size - 1_f32, 1_f32 - y / half_size, z, 1_f32), Vector4f32::new(0.5_f32, 0.5_f32, 0_f32, 0_f32))
+}
+
+//NOTE(dustin): the top left half of the pixels 4..28, the diagonal goes through the pixel centers with x + y = 32 and the
+// other edges are between pixels
+fn draw_half_square(render_context: &mut RenderContext, texture: &BitmapTexture) {
+    render_context.draw_triangle(&vertex(3.5_f32, 3.5_f32, 0_f32), &vertex(28.5_f32, 3.5_f32, 0_f32), &vertex(3.5_f32, 28.5_f32, 0_f32), texture);
+}
+
+fn get_blue(framebuffer: &Framebuffer, x: i32, y: i32) -> u32 {
+    framebuffer.get_pixel(x, y).data & 0xFF
+}
+
+#[test]
+fn sample_positions() {
+    for &count in [SampleCount::X2, SampleCount::X4, SampleCount::X8].iter() {
+        let samples = count.get_positions();
+        let sample_count = count.get_count();
+        assert_eq!(samples.len(), sample_count);
+        assert_eq!(samples.iter().collect::<HashSet<_>>().len(), sample_count, "{} samples are not distinct", sample_count);
+        assert!(samples.iter().all(|&(x, y)| x.abs() < 8 && y.abs() < 8), "{} samples leave the pixel", sample_count);
+
+        //NOTE(dustin): no two samples share a row or a column, so every edge direction is resolved
+        assert_eq!(samples.iter().map(|&(x, _)| x).collect::<HashSet<_>>().len(), sample_count);
+        assert_eq!(samples.iter().map(|&(_, y)| y).collect::<HashSet<_>>().len(), sample_count);
+    }
+
+    assert_eq!(Framebuffer::new(1, 1).samples, vec![(0, 0)]);
+    assert_eq!(multisample::get_sample_reach(&SampleCount::X8.get_positions()), 7);
+}
+
+#[test]
+fn resolve_averages_samples() {
+    let mut source = Framebuffer::new_multisampled(2, 1, SampleCount::X4);
+    source.color = [0xFF000000, 0xFF000000, 0xFFFFFFFF, 0xFFFFFFFF, 0xFF102030, 0xFF102030, 0xFF102031, 0xFF102031].iter()
+        .map(|&data| orbclient::Color { data })
+        .collect();
+
+    let mut resolved = Framebuffer::new(2, 1);
+    source.resolve(&mut resolved);
+    assert_eq!(resolved.get_pixel(0, 0).data, 0xFF808080, "rounded to nearest");
+    assert_eq!(resolved.get_pixel(1, 0).data, 0xFF102031);
+
+    //NOTE(dustin): a larger source is averaged over squares
+    let mut source = Framebuffer::new(4, 2);
+    source.set_pixel(0, 0, orbclient::Color { data: WHITE });
+    source.set_pixel(3, 1, orbclient::Color { data: WHITE });
+    source.set_pixel(2, 1, orbclient::Color { data: WHITE });
+    source.resolve(&mut resolved);
+    assert_eq!(resolved.get_pixel(0, 0).data, 0x40404040);
+    assert_eq!(resolved.get_pixel(1, 0).data, 0x80808080);
+}
+
+#[test]
+fn msaa_smooths_edges() {
+    let white = texture(255, 255, 255, 255);
+
+    let mut reference = context(Antialiasing::None);
+    draw_half_square(&mut reference, &white);
+    let reference = reference.into_output();
+
+    for &sample_count in [SampleCount::X2, SampleCount::X4, SampleCount::X8].iter() {
+        for &worker_count in [1, 3].iter() {
+            let mut render_context = context(Antialiasing::Msaa(sample_count));
+            render_context.set_worker_count(worker_count);
+            draw_half_square(&mut render_context, &white);
+            let output = render_context.into_output();
+            assert_eq!((output.width, output.height), (SIZE as i32, SIZE as i32));
+
+            let mut partial_count = 0;
+            for y in 0..SIZE as i32 {
+                for x in 0..SIZE as i32 {
+                    let value = get_blue(&output, x, y);
+                    if value != 0 && value != 255 {
+                        partial_count += 1;
+                        assert_eq!(x + y, 32, "pixel {} {} is off the diagonal with {:?}", x, y, sample_count);
+                    } else {
+                        assert_eq!(value, get_blue(&reference, x, y), "pixel {} {} with {:?}", x, y, sample_count);
+                    }
+                }
+            }
+            assert!(partial_count >= 24, "only {} blended pixels with {:?}", partial_count, sample_count);
+        }
+    }
+}
+
+#[test]
+fn msaa_is_watertight() {
+    let counter = texture(255, 1, 1, 1);
+
+    for &count in [SampleCount::X2, SampleCount::X4, SampleCount::X8].iter() {
+        let sample_count = count.get_count();
+        let mut render_context = context(Antialiasing::Msaa(count));
+        render_context.set_depth_func(DepthFunc::Always);
+        render_context.set_blend_state(BlendState { mode: BlendMode::Additive, alpha_threshold: None });
+
+        //NOTE(dustin): two triangles that share the diagonal, every sample of 4..28 is covered exactly once
+        let corners = [vertex(4.1_f32, 3.9_f32, 0_f32), vertex(28.2_f32, 4.3_f32, 0_f32), vertex(27.8_f32, 28_f32, 0_f32), vertex(3.7_f32, 27.6_f32, 0_f32)];
+        render_context.draw_triangle(&corners[0], &corners[1], &corners[2], &counter);
+        render_context.draw_triangle(&corners[0], &corners[2], &corners[3], &counter);
+
+        let target = render_context.get_target();
+        assert!(target.color.iter().all(|color| color.data & 0xFF <= 1), "a sample is drawn twice with {} samples", sample_count);
+        for y in 6..26 {
+            for x in 6..26 {
+                let data_idx = (x + y * target.width) as usize * sample_count;
+                assert!(target.color[data_idx..data_idx + sample_count].iter().all(|color| color.data & 0xFF == 1), "pixel {} {} with {} samples", x, y, sample_count);
+            }
+        }
+    }
+}
+
+struct CountingShader {
+    count: AtomicUsize
+}
+
+impl FragmentShader for CountingShader {
+    fn shade(&self, _fragment: &Fragment) -> Option<orbclient::Color> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Some(orbclient::Color { data: WHITE })
+    }
+}
+
+#[test]
+fn msaa_shades_once_per_pixel() {
+    let identity = identity();
+    let vertex_shader = TransformShader::new(&identity);
+
+    for &antialiasing in [Antialiasing::None, Antialiasing::Msaa(SampleCount::X4), Antialiasing::Msaa(SampleCount::X8), Antialiasing::Ssaa(ScaleFactor::X2)].iter() {
+        let mut render_context = context(antialiasing);
+        let shader = CountingShader { count: AtomicUsize::new(0) };
+        render_context.draw_triangle_shaded(&vertex(3.5_f32, 3.5_f32, 0_f32), &vertex(28.5_f32, 3.5_f32, 0_f32), &vertex(3.5_f32, 28.5_f32, 0_f32), &vertex_shader, &shader);
+
+        let target = render_context.get_target();
+        let sample_count = target.samples.len();
+        let drawn_pixels = target.color.chunks(sample_count).filter(|samples| samples.iter().any(|color| color.data != CLEAR)).count();
+        let drawn_samples = target.color.iter().filter(|color| color.data != CLEAR).count();
+        assert_eq!(shader.count.load(Ordering::SeqCst), drawn_pixels, "{:?}", antialiasing);
+        assert_eq!(drawn_samples > drawn_pixels, sample_count > 1, "{:?}", antialiasing);
+    }
+}
+
+#[test]
+fn msaa_depth_per_sample() {
+    let red = texture(255, 255, 0, 0);
+    let blue = texture(255, 0, 0, 255);
+
+    //NOTE(dustin): a blue wall in front of the right half of a red one, the pixels on the border get both colors
+    let mut render_context = context(Antialiasing::Msaa(SampleCount::X4));
+    render_context.draw_triangle(&vertex(-1_f32, -1_f32, 0.5_f32), &vertex(40_f32, -1_f32, 0.5_f32), &vertex(-1_f32, 40_f32, 0.5_f32), &red);
+    render_context.draw_triangle(&vertex(40_f32, -1_f32, 0.5_f32), &vertex(40_f32, 40_f32, 0.5_f32), &vertex(-1_f32, 40_f32, 0.5_f32), &red);
+    render_context.draw_triangle(&vertex(16.1_f32, -1_f32, 0.2_f32), &vertex(40_f32, -1_f32, 0.2_f32), &vertex(16.1_f32, 40_f32, 0.2_f32), &blue);
+    render_context.draw_triangle(&vertex(40_f32, -1_f32, 0.2_f32), &vertex(40_f32, 40_f32, 0.2_f32), &vertex(16.1_f32, 40_f32, 0.2_f32), &blue);
+
+    //NOTE(dustin): behind everything, so it changes nothing
+    render_context.draw_triangle(&vertex(-1_f32, -1_f32, 0.8_f32), &vertex(40_f32, -1_f32, 0.8_f32), &vertex(-1_f32, 40_f32, 0.8_f32), &texture(255, 0, 255, 0));
+
+    let output = render_context.into_output();
+    assert_eq!(output.get_pixel(8, 8).data, 0xFFFF0000);
+    assert_eq!(output.get_pixel(24, 8).data, 0xFF0000FF);
+    assert_eq!(output.get_pixel(16, 8).data, 0xFF800080, "two samples of each wall");
+}
+
+#[test]
+fn ssaa_renders_larger() {
+    let mut render_context = context(Antialiasing::Ssaa(ScaleFactor::X2));
+    assert_eq!((render_context.get_width(), render_context.get_height()), (SIZE, SIZE));
+    assert_eq!((render_context.get_target().width, render_context.get_target().height), (2 * SIZE as i32, 2 * SIZE as i32));
+    assert_eq!(render_context.get_antialiasing(), Antialiasing::Ssaa(ScaleFactor::X2));
+    assert_eq!(context(Antialiasing::Ssaa(ScaleFactor::X3)).get_target().width, 3 * SIZE as i32);
+
+    draw_half_square(&mut render_context, &texture(255, 255, 255, 255));
+    render_context.draw_line(&vertex(4_f32, 30_f32, 0_f32).pos, &vertex(28_f32, 30_f32, 0_f32).pos, &identity(), LineColor::Constant(orbclient::Color { data: WHITE }), false);
+    render_context.resolve();
+
+    let output = render_context.get_output();
+    assert_eq!((output.width, output.height), (SIZE as i32, SIZE as i32));
+    assert_eq!(get_blue(output, 8, 8), 255);
+    assert_eq!(get_blue(output, 24, 24), 0);
+    let is_blended = |x: i32, y: i32| get_blue(output, x, y) != 0 && get_blue(output, x, y) != 255;
+    assert!((4..28).all(|x| is_blended(x, 31 - x) || is_blended(x, 32 - x)), "the diagonal is not blended");
+    assert_eq!(get_blue(output, 16, 30), 0x80, "a one pixel line of the larger target covers half a pixel");
+}
+
+#[test]
+fn without_antialiasing_output_is_target() {
+    let mut render_context = context(Antialiasing::None);
+    draw_half_square(&mut render_context, &texture(255, 255, 255, 255));
+    render_context.resolve();
+
+    assert_eq!(render_context.get_antialiasing(), Antialiasing::None);
+    assert!(render_context.get_output().color.iter().zip(render_context.get_target().color.iter()).all(|(a, b)| a.data == b.data));
+}
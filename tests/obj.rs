@@ -0,0 +1,129 @@
+extern crate pixelcannon;
+
+use std::fs;
+
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::model::obj::{OBJError, OBJErrorKind, OBJModel};
+
+fn parse(source: &str) -> Result<OBJModel, OBJError> {
+    OBJModel::from_reader(source.as_bytes(), "test.obj")
+}
+
+#[test]
+fn tabs_repeated_spaces_and_crlf() {
+    let model = parse("v 0 0 0\r\nv\t1  0   0\r\n  v 0 1 0  \r\nvt 0.5\r\nf 1/1 2/1\t3/1\r\n").unwrap();
+
+    assert_eq!(model.positions.len(), 3);
+    assert_eq!(model.positions[1].x, 1_f32);
+    assert_eq!(model.tex_coords[0].y, 1_f32);
+    assert_eq!(model.indices.len(), 3);
+    assert!(model.has_tex_coords);
+}
+
+#[test]
+fn line_continuation() {
+    let model = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 \\\n3 4\n").unwrap();
+
+    //NOTE(dustin): a quad is split into two triangles
+    assert_eq!(model.indices.len(), 6);
+    assert_eq!(model.indices[5].vertex_index, 3);
+}
+
+#[test]
+fn line_continuation_reports_physical_position() {
+    let err = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 \\\n  2 \\\n 5\n").err().unwrap();
+
+    assert_eq!((err.line, err.column), (6, 2));
+    match err.kind {
+        OBJErrorKind::IndexOutOfRange { index, count } => assert_eq!((index, count), (5, 3)),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn negative_indices() {
+    let model = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nvn 0 0 1\nf -3//-1 -2//-1 -1//-1\n").unwrap();
+
+    let vertex_indices: Vec<i32> = model.indices.iter().map(|index| index.vertex_index).collect();
+    assert_eq!(vertex_indices, vec![0, 1, 2]);
+    assert!(model.indices.iter().all(|index| index.normal_index == 0 && index.tex_coord_index == -1));
+}
+
+#[test]
+fn short_line_reports_position() {
+    let err = parse("# comment\nv 1 2 3\n  v 1 2\n").err().unwrap();
+
+    assert_eq!((err.file.as_str(), err.line, err.column), ("test.obj", 3, 3));
+    match err.kind {
+        OBJErrorKind::MissingValues { expected, found, .. } => assert_eq!((expected, found), (3, 2)),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn invalid_number_reports_column() {
+    let err = parse("v 1 2 3\nv 1 abc 3\n").err().unwrap();
+
+    assert_eq!((err.line, err.column), (2, 5));
+    match err.kind {
+        OBJErrorKind::InvalidNumber(ref token) => assert_eq!(token, "abc"),
+        ref kind => panic!("unexpected error {:?}", kind),
+    }
+    assert_eq!(err.to_string(), "test.obj:2:5: invalid number 'abc'");
+}
+
+#[test]
+fn out_of_range_index() {
+    let err = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 4\n").err().unwrap();
+
+    assert_eq!((err.line, err.column), (4, 7));
+    match err.kind {
+        OBJErrorKind::IndexOutOfRange { index, count } => assert_eq!((index, count), (4, 3)),
+        kind => panic!("unexpected error {:?}", kind),
+    }
+
+    assert!(parse("v 0 0 0\nf 0 1 1\n").is_err());
+    assert!(parse("v 0 0 0\nf -2 1 1\n").is_err());
+}
+
+#[test]
+fn unsupported_statements_warn() {
+    let model = parse("v 0 0 0\nv 1 0 0\nl 1 2\ncstype bspline\nf 1 2 1\n").unwrap();
+
+    let lines: Vec<usize> = model.warnings.iter().map(|warning| warning.line).collect();
+    assert_eq!(lines, vec![3, 4]);
+}
+
+#[test]
+fn indexed_model_without_tex_coords_or_normals() {
+    let model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+    let indexed = model.to_indexed_model();
+
+    assert_eq!(indexed.positions.len(), 3);
+    assert_eq!(indexed.normals.len(), 3);
+    assert_eq!(indexed.normals[0].z, 1_f32);
+}
+
+#[test]
+fn mesh_keeps_warnings() {
+    let path = std::env::temp_dir().join("pixelcannon_obj_mesh_warnings.obj");
+    fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\nl 1 2\nf 1 2 3\n").unwrap();
+
+    let mesh = Mesh::from_path(path.to_str().unwrap().to_string()).unwrap();
+    assert_eq!(mesh.get_triangle_count(), 1);
+    assert_eq!(mesh.warnings.len(), 1);
+    assert_eq!(mesh.warnings[0].line, 4);
+}
+
+#[test]
+fn mesh_from_indexed_model_without_normals() {
+    let mut indexed = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap().to_indexed_model();
+    indexed.tex_coords.clear();
+    indexed.normals.clear();
+
+    let mesh = Mesh::from_indexed_model(indexed);
+    assert_eq!(mesh.vertices.len(), 3);
+    let normal = mesh.vertices[0].normal;
+    assert_eq!((normal.x, normal.y, normal.z), (0_f32, 0_f32, 0_f32));
+    assert!(mesh.warnings.is_empty());
+}
@@ -0,0 +1,307 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use pixelcannon::postprocess::blur::{Blur, BlurKernel};
+use pixelcannon::postprocess::color::{ColorLut, GammaCorrection, ToneMapOperator, ToneMapping, TransferCurve, Vignette};
+use pixelcannon::postprocess::fxaa::Fxaa;
+use pixelcannon::postprocess::outline::Outline;
+use pixelcannon::postprocess::{self, PostChain, PostPass};
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::primitive::vertex::Vertex;
+use pixelcannon::render::RenderContext;
+use pixelcannon::target::framebuffer::Framebuffer;
+use pixelcannon::target::multisample::{Antialiasing, SampleCount, ScaleFactor};
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+const BLACK: u32 = 0xFF000000;
+const WHITE: u32 = 0xFFFFFFFF;
+const RED: u32 = 0xFFFF0000;
+
+fn color(data: u32) -> orbclient::Color {
+    orbclient::Color { data }
+}
+
+fn gray(value: u32) -> orbclient::Color {
+    color(0xFF000000 | value << 16 | value << 8 | value)
+}
+
+fn framebuffer(width: i32, height: i32, data: u32) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(width, height);
+    framebuffer.clear(color(data));
+    framebuffer
+}
+
+fn apply<P: PostPass + 'static>(framebuffer: &mut Framebuffer, pass: P) {
+    let mut chain = PostChain::new();
+    chain.add(pass);
+    chain.apply(framebuffer);
+}
+
+fn get_blue(framebuffer: &Framebuffer, x: i32, y: i32) -> u32 {
+    framebuffer.get_pixel(x, y).data & 0xFF
+}
+
+struct Invert;
+
+impl PostPass for Invert {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        postprocess::map_pixels(input, output, |_, _, argb| [argb[0], 1_f32 - argb[1], 1_f32 - argb[2], 1_f32 - argb[3]]);
+    }
+}
+
+//NOTE(dustin): red where something was drawn, keeps the color elsewhere
+struct DepthMask;
+
+impl PostPass for DepthMask {
+    fn apply(&self, input: &Framebuffer, output: &mut [orbclient::Color]) {
+        for idx_y in 0..input.height {
+            for idx_x in 0..input.width {
+                let is_drawn = input.depth.get_depth(idx_x, idx_y) < 1_f32;
+                output[(idx_x + idx_y * input.width) as usize] = if is_drawn { color(RED) } else { input.get_pixel(idx_x, idx_y) };
+            }
+        }
+    }
+}
+
+#[test]
+fn chain_runs_in_order() {
+    let mut target = framebuffer(4, 4, BLACK);
+    target.depth.data[5] = 0.5_f32;
+
+    let mut chain = PostChain::new();
+    assert!(chain.is_empty());
+    chain.add(DepthMask);
+    chain.add(Invert);
+    assert_eq!(chain.len(), 2);
+    chain.apply(&mut target);
+
+    assert_eq!(target.get_pixel(1, 1).data, 0xFF00FFFF, "masked and then inverted");
+    assert_eq!(target.get_pixel(0, 0).data, WHITE);
+
+    chain.clear();
+    chain.apply(&mut target);
+    assert_eq!(target.get_pixel(0, 0).data, WHITE);
+}
+
+#[test]
+fn render_context_post_passes() {
+    let mut texture = BitmapTexture::new(1, 1);
+    texture.set_pixel(0, 0, 255, 255, 255, 255);
+    let vertex = |x: f32, y: f32| Vertex::new_with_pos_and_texcoords(Vector4f32::new(x, y, 0_f32, 1_f32), Vector4f32::new(0.5_f32, 0.5_f32, 0_f32, 0_f32));
+
+    for &antialiasing in [Antialiasing::None, Antialiasing::Msaa(SampleCount::X4), Antialiasing::Ssaa(ScaleFactor::X2)].iter() {
+        let mut render_context = RenderContext::with_antialiasing(16, 16, antialiasing);
+        render_context.get_target_mut().clear(color(BLACK));
+        render_context.add_post_pass(DepthMask);
+        render_context.add_post_pass(Invert);
+        assert_eq!(render_context.get_post_pass_count(), 2);
+
+        render_context.draw_triangle(&vertex(-0.9_f32, 0.9_f32), &vertex(0.9_f32, 0.9_f32), &vertex(-0.9_f32, -0.9_f32), &texture);
+        render_context.finish_frame();
+
+        let output = render_context.get_output();
+        assert_eq!(output.get_pixel(2, 2).data, 0xFF00FFFF, "{:?} drawn pixel reads the depth of the frame", antialiasing);
+        assert_eq!(output.get_pixel(14, 14).data, WHITE, "{:?} background", antialiasing);
+    }
+
+    let mut render_context = RenderContext::new(4, 4);
+    render_context.get_target_mut().clear(color(BLACK));
+    render_context.add_post_pass(Invert);
+    render_context.clear_post_passes();
+    assert_eq!(render_context.into_output().get_pixel(0, 0).data, BLACK);
+}
+
+#[test]
+fn render_context_finishes_frame_once() {
+    for &antialiasing in [Antialiasing::None, Antialiasing::Msaa(SampleCount::X4)].iter() {
+        let mut render_context = RenderContext::with_antialiasing(4, 4, antialiasing);
+        render_context.get_target_mut().clear(color(BLACK));
+        render_context.add_post_pass(Invert);
+
+        render_context.finish_frame();
+        render_context.finish_frame();
+        assert_eq!(render_context.get_output().get_pixel(0, 0).data, WHITE, "{:?} finish_frame twice", antialiasing);
+        assert_eq!(render_context.into_output().get_pixel(0, 0).data, WHITE, "{:?} into_output after finish_frame", antialiasing);
+    }
+
+    let mut render_context = RenderContext::new(4, 4);
+    render_context.add_post_pass(Invert);
+    render_context.finish_frame();
+    render_context.clear();
+    render_context.finish_frame();
+    assert_eq!(render_context.get_output().get_pixel(0, 0).data, 0xFFDDF317, "clear starts a new frame");
+}
+
+#[test]
+fn gamma_correction() {
+    let cases = [
+        (TransferCurve::LinearToSrgb, 128, 188),
+        (TransferCurve::SrgbToLinear, 188, 128),
+        (TransferCurve::Gamma(2.2_f32), 128, 186),
+        (TransferCurve::Gamma(1_f32), 77, 77),
+    ];
+
+    for &(curve, input, expected) in cases.iter() {
+        let mut target = framebuffer(2, 2, gray(input).data);
+        apply(&mut target, GammaCorrection::new(curve));
+        assert_eq!(target.get_pixel(1, 1).data, gray(expected).data, "{:?} of {}", curve, input);
+    }
+
+    for &value in [0_f32, 0.002_f32, 0.2_f32, 0.9_f32, 1_f32].iter() {
+        let round_trip = TransferCurve::SrgbToLinear.apply(TransferCurve::LinearToSrgb.apply(value));
+        assert!((round_trip - value).abs() < 1e-5_f32, "{} came back as {}", value, round_trip);
+    }
+}
+
+#[test]
+fn tone_mapping() {
+    let mut target = framebuffer(2, 2, WHITE);
+    apply(&mut target, ToneMapping::new(ToneMapOperator::Reinhard));
+    assert_eq!(target.get_pixel(0, 0).data, gray(128).data);
+
+    let mut target = framebuffer(2, 2, WHITE);
+    apply(&mut target, ToneMapping::new(ToneMapOperator::Aces).init_exposure(4_f32));
+    assert_eq!(target.get_pixel(0, 0).data, gray(248).data);
+
+    for &operator in [ToneMapOperator::Reinhard, ToneMapOperator::Aces].iter() {
+        assert_eq!(operator.apply(0_f32), 0_f32);
+        let values: Vec<f32> = (0..20).map(|idx| operator.apply(idx as f32 * 0.5_f32)).collect();
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1] && pair[1] <= 1_f32), "{:?} is not increasing in 0..1", operator);
+        assert!(values[1] > values[0] && values[2] > values[1]);
+    }
+}
+
+#[test]
+fn vignette() {
+    let mut target = framebuffer(33, 21, WHITE);
+    apply(&mut target, Vignette::new());
+
+    assert_eq!(target.get_pixel(16, 10).data, WHITE);
+    assert_eq!(target.get_pixel(0, 0).data, gray(128).data, "darkened by strength in the corners");
+    assert!(get_blue(&target, 4, 10) < 255 && get_blue(&target, 4, 10) > 128);
+    assert_eq!(target.get_pixel(32, 20).data, target.get_pixel(0, 0).data);
+}
+
+#[test]
+fn color_lut() {
+    let lut = ColorLut::identity(5);
+    for &rgb in [[0_f32, 0_f32, 0_f32], [0.3_f32, 0.7_f32, 0.1_f32], [1_f32, 1_f32, 1_f32]].iter() {
+        let result = lut.lookup(rgb);
+        assert!((0..3).all(|idx| (result[idx] - rgb[idx]).abs() < 1e-5_f32), "{:?} became {:?}", rgb, result);
+    }
+
+    //NOTE(dustin): swaps red and blue and inverts green
+    let source = "TITLE \"swap\"\n# comment\nLUT_3D_SIZE 2\n\n0 1 0\n0 1 1\n0 0 0\n0 0 1\n1 1 0\n1 1 1\n1 0 0\n1 0 1\n";
+    let lut = ColorLut::parse_cube(source).unwrap();
+    assert_eq!(lut.size, 2);
+
+    let mut target = framebuffer(2, 2, 0xFF4080C0);
+    apply(&mut target, lut);
+    assert_eq!(target.get_pixel(0, 0).data, 0xFFC07F40);
+
+    assert!(ColorLut::parse_cube("0 0 0\n").is_err(), "missing size");
+    assert!(ColorLut::parse_cube("LUT_3D_SIZE 2\n0 0 0\n").is_err(), "missing entries");
+    assert!(ColorLut::parse_cube("LUT_3D_SIZE 2\n0 0\n").is_err(), "short entry");
+    assert!(ColorLut::parse_cube("LUT_1D_SIZE 2\n").is_err());
+    assert!(ColorLut::parse_cube(&format!("LUT_3D_SIZE {}\n0 0 0\n", usize::MAX / 2)).is_err(), "size overflows");
+}
+
+#[test]
+fn blur() {
+    let mut target = framebuffer(9, 9, BLACK);
+    target.set_pixel(4, 4, color(WHITE));
+    apply(&mut target, Blur::new(BlurKernel::Box, 1));
+
+    for y in 0..9 {
+        for x in 0..9 {
+            let expected = if (x - 4_i32).abs() <= 1 && (y - 4_i32).abs() <= 1 { 28 } else { 0 };
+            assert_eq!(get_blue(&target, x, y), expected, "pixel {} {}", x, y);
+        }
+    }
+
+    let weights = Blur::new(BlurKernel::Gaussian(1_f32), 3).calc_weights();
+    assert_eq!(weights.len(), 7);
+    assert!((weights.iter().sum::<f32>() - 1_f32).abs() < 1e-5_f32);
+    assert!(weights[3] > weights[2] && weights[2] > weights[1] && weights[2] == weights[4]);
+
+    let mut target = framebuffer(9, 9, BLACK);
+    target.set_pixel(4, 4, color(WHITE));
+    apply(&mut target, Blur::new(BlurKernel::Gaussian(1_f32), 3));
+    assert!(get_blue(&target, 4, 4) > get_blue(&target, 5, 4) && get_blue(&target, 5, 4) > get_blue(&target, 5, 5) && get_blue(&target, 5, 5) > 0);
+    assert_eq!(get_blue(&target, 3, 4), get_blue(&target, 5, 4));
+
+    //NOTE(dustin): a sigma of 0 or below is no blur instead of nan weights
+    for &sigma in [0_f32, -1_f32, f32::NAN].iter() {
+        assert_eq!(Blur::new(BlurKernel::Gaussian(sigma), 2).calc_weights(), vec![0_f32, 0_f32, 1_f32, 0_f32, 0_f32]);
+
+        let mut target = framebuffer(9, 9, BLACK);
+        target.set_pixel(4, 4, color(WHITE));
+        apply(&mut target, Blur::new(BlurKernel::Gaussian(sigma), 2));
+        assert_eq!((get_blue(&target, 4, 4), get_blue(&target, 5, 4)), (255, 0), "sigma {}", sigma);
+    }
+
+    //NOTE(dustin): a flat color stays the same, also at the borders
+    let mut target = framebuffer(5, 5, 0xFF336699);
+    apply(&mut target, Blur::new(BlurKernel::Gaussian(2_f32), 4));
+    assert!(target.color.iter().all(|color| color.data == 0xFF336699));
+}
+
+#[test]
+fn fxaa() {
+    //NOTE(dustin): a hard stair step edge between black and white
+    let mut target = framebuffer(16, 16, BLACK);
+    for y in 0..16 {
+        for x in 0..16 {
+            if x > y / 2 + 4 {
+                target.set_pixel(x, y, color(WHITE));
+            }
+        }
+    }
+    let source: Vec<u32> = target.color.iter().map(|color| color.data).collect();
+    apply(&mut target, Fxaa::new());
+
+    let blended = target.color.iter().filter(|color| color.data != BLACK && color.data != WHITE).count();
+    assert!(blended > 8, "only {} pixels blended", blended);
+    for y in 0..16 {
+        for x in 0..16 {
+            let is_near_edge = (x - (y / 2 + 4_i32)).abs() <= 2;
+            if !is_near_edge {
+                assert_eq!(target.get_pixel(x, y).data, source[(x + y * 16) as usize], "pixel {} {} is away from the edge", x, y);
+            }
+        }
+    }
+
+    //NOTE(dustin): low contrast is below the threshold
+    let mut target = framebuffer(8, 8, gray(100).data);
+    target.set_pixel(3, 3, gray(102));
+    apply(&mut target, Fxaa::new());
+    assert_eq!(target.get_pixel(3, 3).data, gray(102).data);
+}
+
+#[test]
+fn outline() {
+    let mut target = framebuffer(12, 12, BLACK);
+    for y in 3..9 {
+        for x in 3..9 {
+            target.set_pixel(x, y, color(WHITE));
+            target.depth.data[(x + y * 12) as usize] = 0.5_f32;
+        }
+    }
+    apply(&mut target, Outline::new(color(RED)));
+
+    for y in 0..12 {
+        for x in 0..12 {
+            let is_border = (2..=9).contains(&x) && (2..=9).contains(&y) && !((4..=7).contains(&x) && (4..=7).contains(&y));
+            let expected = if is_border { RED } else if (3..9).contains(&x) && (3..9).contains(&y) { WHITE } else { BLACK };
+            assert_eq!(target.get_pixel(x, y).data, expected, "pixel {} {}", x, y);
+        }
+    }
+
+    //NOTE(dustin): only the luma, the depth is flat
+    let mut target = framebuffer(8, 8, BLACK);
+    target.set_pixel(4, 4, color(WHITE));
+    apply(&mut target, Outline::new(color(RED)).init_depth_threshold(None).init_luma_threshold(Some(0.5_f32)));
+    assert_eq!(target.get_pixel(3, 4).data, RED);
+    assert_eq!(target.get_pixel(4, 4).data, WHITE, "the center has no gradient");
+    assert_eq!(target.get_pixel(0, 0).data, BLACK);
+}
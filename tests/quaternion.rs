@@ -0,0 +1,117 @@
+extern crate pixelcannon;
+
+use std::f32::consts::PI;
+
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::quaternion::Quaternion;
+use pixelcannon::primitive::vector::Vector4f32;
+
+const EPSILON: f32 = 1e-4;
+
+fn axis(x: f32, y: f32, z: f32) -> Vector4f32 {
+    Vector4f32::new(x, y, z, 0_f32).normalized()
+}
+
+fn assert_vector(actual: &Vector4f32, expected: &Vector4f32) {
+    assert!(actual.sub_v(expected).length() < EPSILON, "expected {:?}, got {:?}", expected, actual);
+}
+
+fn assert_matrix(actual: &Matrix4f32, expected: &Matrix4f32) {
+    for r_idx in 0..4 {
+        for c_idx in 0..4 {
+            assert!((actual.m[r_idx][c_idx] - expected.m[r_idx][c_idx]).abs() < EPSILON,
+                "m[{}][{}] expected {}, got {}", r_idx, c_idx, expected.m[r_idx][c_idx], actual.m[r_idx][c_idx]);
+        }
+    }
+}
+
+//NOTE(dustin): q and -q are the same rotation
+fn assert_same_rotation(actual: &Quaternion, expected: &Quaternion) {
+    assert!((actual.dot(expected).abs() - 1_f32).abs() < EPSILON, "expected {:?}, got {:?}", expected, actual);
+}
+
+#[test]
+fn axis_angle_matches_matrix() {
+    let axis = axis(1_f32, -2_f32, 0.5_f32);
+    let quaternion = Quaternion::from_axis_angle(&axis, 1.3_f32);
+
+    assert_matrix(&quaternion.to_matrix(), &Matrix4f32::new().init_axis_rotation(&axis, 1.3_f32));
+
+    let vector = Vector4f32::new(0.3_f32, 2_f32, -1_f32, 1_f32);
+    assert_vector(&quaternion.rotate(&vector), &Matrix4f32::new().init_axis_rotation(&axis, 1.3_f32).transform(&vector));
+}
+
+#[test]
+fn euler_matches_init_rotation() {
+    for &(x, y, z) in [(0.3_f32, -0.5_f32, 1.2_f32), (PI / 2_f32, 0.7_f32, -2_f32), (-1_f32, PI, 0.1_f32)].iter() {
+        assert_matrix(&Quaternion::from_euler(x, y, z).to_matrix(), &Matrix4f32::new().init_rotation(x, y, z));
+    }
+}
+
+#[test]
+fn matrix_round_trip() {
+    //NOTE(dustin): covers the trace and each of the diagonal branches of from_matrix
+    let rotations = [
+        Quaternion::from_axis_angle(&axis(0.2_f32, 0.3_f32, 0.9_f32), 0.4_f32),
+        Quaternion::from_axis_angle(&axis(1_f32, 0.1_f32, 0.1_f32), 3_f32),
+        Quaternion::from_axis_angle(&axis(0.1_f32, 1_f32, 0.1_f32), 3_f32),
+        Quaternion::from_axis_angle(&axis(0.1_f32, 0.1_f32, 1_f32), 3_f32)
+    ];
+
+    for rotation in rotations.iter() {
+        assert_same_rotation(&Quaternion::from_matrix(&rotation.to_matrix()), rotation);
+    }
+}
+
+#[test]
+fn mul_composes_like_matrices() {
+    let a = Quaternion::from_axis_angle(&axis(0_f32, 1_f32, 0_f32), 0.8_f32);
+    let b = Quaternion::from_axis_angle(&axis(1_f32, 0_f32, 1_f32), -1.1_f32);
+
+    assert_matrix(&a.mul(&b).to_matrix(), &a.to_matrix().mul(&b.to_matrix()));
+    assert_same_rotation(&a.mul(&a.conjugate()), &Quaternion::identity());
+}
+
+#[test]
+fn no_gimbal_lock_at_90_degree_pitch() {
+    //NOTE(dustin): with euler angles yaw and roll do the same thing at 90 degrees pitch, with quaternions they stay independent
+    let pitched = Quaternion::from_axis_angle(&axis(1_f32, 0_f32, 0_f32), PI / 2_f32);
+    let yawed = pitched.mul(&Quaternion::from_axis_angle(&axis(0_f32, 1_f32, 0_f32), 0.5_f32));
+    let rolled = pitched.mul(&Quaternion::from_axis_angle(&axis(0_f32, 0_f32, 1_f32), 0.5_f32));
+
+    let forward = Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32);
+    assert!(yawed.rotate(&forward).sub_v(&rolled.rotate(&forward)).length() > 0.1_f32);
+}
+
+#[test]
+fn slerp_and_nlerp() {
+    let from = Quaternion::identity();
+    let to = Quaternion::from_axis_angle(&axis(0_f32, 0_f32, 1_f32), PI / 2_f32);
+
+    assert_same_rotation(&from.slerp(&to, 0_f32), &from);
+    assert_same_rotation(&from.slerp(&to, 1_f32), &to);
+    assert_same_rotation(&from.slerp(&to, 0.5_f32), &Quaternion::from_axis_angle(&axis(0_f32, 0_f32, 1_f32), PI / 4_f32));
+    assert_same_rotation(&from.nlerp(&to, 0.5_f32), &Quaternion::from_axis_angle(&axis(0_f32, 0_f32, 1_f32), PI / 4_f32));
+
+    //NOTE(dustin): the negated target is the same rotation, slerp has to take the short way
+    let negated = Quaternion::new(-to.x, -to.y, -to.z, -to.w);
+    assert_same_rotation(&from.slerp(&negated, 0.5_f32), &Quaternion::from_axis_angle(&axis(0_f32, 0_f32, 1_f32), PI / 4_f32));
+    assert!((from.slerp(&to, 0.3_f32).length() - 1_f32).abs() < EPSILON);
+}
+
+#[test]
+fn camera_rotation_round_trip() {
+    let projection = Projection::Perspective { fov: 1_f32, aspect_ratio: 1_f32, z_near: 0.1_f32, z_far: 100_f32 };
+    let mut camera = Camera::new(Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), projection);
+    camera.look_at(&Vector4f32::new(1_f32, 2_f32, 3_f32, 1_f32), &axis(0_f32, 1_f32, 0_f32));
+
+    let rotation = camera.get_rotation();
+    assert_vector(&rotation.rotate(&axis(0_f32, 0_f32, 1_f32)), &camera.forward);
+    assert_vector(&rotation.rotate(&axis(0_f32, 1_f32, 0_f32)), &camera.up);
+
+    let mut other = Camera::new(Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), projection);
+    other.set_rotation(&rotation);
+    assert_vector(&other.forward, &camera.forward);
+    assert_vector(&other.up, &camera.up);
+}
@@ -0,0 +1,290 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use pixelcannon::blend::{BlendMode, BlendState};
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::depth::DepthFunc;
+use pixelcannon::halfspace::Lanes;
+use pixelcannon::model::material::Material;
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::primitive::vertex::Vertex;
+use pixelcannon::raster::{CullMode, FrontFace, RasterAlgorithm, RasterState};
+use pixelcannon::render::RenderContext;
+use pixelcannon::scene::{Node, Scene};
+use pixelcannon::shader::{Fragment, FragmentShader, TransformShader};
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+const SIZE: u32 = 64;
+
+//NOTE(dustin): every triangle adds 1 to the pixels it covers, so a pixel that is drawn twice shows up as 2
+fn context(worker_count: usize) -> RenderContext {
+    context_with_algorithm(worker_count, RasterAlgorithm::Scanline)
+}
+
+fn context_with_algorithm(worker_count: usize, algorithm: RasterAlgorithm) -> RenderContext {
+    let mut render_context = RenderContext::new(SIZE, SIZE);
+    render_context.get_target_mut().clear(orbclient::Color { data: 0xFF000000 });
+    render_context.set_depth_func(DepthFunc::Always);
+    render_context.set_blend_state(BlendState { mode: BlendMode::Additive, alpha_threshold: None });
+    render_context.set_worker_count(worker_count);
+    render_context.set_raster_algorithm(algorithm);
+    render_context
+}
+
+fn counter_texture() -> BitmapTexture {
+    let mut texture = BitmapTexture::new(1, 1);
+    texture.set_pixel(0, 0, 255, 1, 1, 1);
+    texture
+}
+
+//NOTE(dustin): takes a position in pixels, pixel centers are at whole numbers
+fn vertex(x: f32, y: f32) -> Vertex {
+    let half_size = SIZE as f32 / 2_f32;
+    Vertex::new_with_pos_and_texcoords(Vector4f32::new(x / half_size - 1_f32, 1_f32 - y / half_size, 0_f32, 1_f32), Vector4f32::new(0.5_f32, 0.5_f32, 0_f32, 0_f32))
+}
+
+fn get_count(render_context: &RenderContext, x: i32, y: i32) -> u32 {
+    render_context.get_target().get_pixel(x, y).data & 0xFF
+}
+
+//NOTE(dustin): a grid over 8.3..56.3 whose inner vertices are moved by up to a fifth of a cell, the diagonal alternates per cell
+fn jittered_grid(cells: i32) -> Vec<Vec<(f32, f32)>> {
+    let cell_size = 48_f32 / cells as f32;
+    (0..cells + 1).map(|row| (0..cells + 1).map(|col| {
+        let is_border = row == 0 || col == 0 || row == cells || col == cells;
+        let jitter = |seed: i32| if is_border { 0_f32 } else { ((seed % 17) as f32 / 16_f32 - 0.5_f32) * 0.4_f32 * cell_size };
+        (8.3_f32 + col as f32 * cell_size + jitter(col * 7 + row * 13), 8.3_f32 + row as f32 * cell_size + jitter(col * 11 + row * 5))
+    }).collect()).collect()
+}
+
+fn draw_grid(render_context: &mut RenderContext, grid: &[Vec<(f32, f32)>], texture: &BitmapTexture) {
+    let corner = |row: usize, col: usize| vertex(grid[row][col].0, grid[row][col].1);
+
+    for row in 0..grid.len() - 1 {
+        for col in 0..grid[0].len() - 1 {
+            if (row + col) % 2 == 0 {
+                render_context.draw_triangle(&corner(row, col), &corner(row, col + 1), &corner(row + 1, col + 1), texture);
+                render_context.draw_triangle(&corner(row, col), &corner(row + 1, col + 1), &corner(row + 1, col), texture);
+            } else {
+                render_context.draw_triangle(&corner(row, col), &corner(row, col + 1), &corner(row + 1, col), texture);
+                render_context.draw_triangle(&corner(row, col + 1), &corner(row + 1, col + 1), &corner(row + 1, col), texture);
+            }
+        }
+    }
+}
+
+#[test]
+fn tessellated_quad_is_watertight() {
+    let texture = counter_texture();
+
+    for &cells in [1, 3, 8, 24].iter() {
+        for &(worker_count, algorithm) in [(1, RasterAlgorithm::Scanline), (4, RasterAlgorithm::Scanline), (1, RasterAlgorithm::HalfSpace), (4, RasterAlgorithm::HalfSpace)].iter() {
+            let mut render_context = context_with_algorithm(worker_count, algorithm);
+            draw_grid(&mut render_context, &jittered_grid(cells), &texture);
+
+            for y in 0..SIZE as i32 {
+                for x in 0..SIZE as i32 {
+                    let is_inside = (9..=56).contains(&x) && (9..=56).contains(&y);
+                    let expected = if is_inside { 1 } else { 0 };
+                    assert_eq!(get_count(&render_context, x, y), expected, "pixel {} {} of {} cells with {} workers and {:?}", x, y, cells, worker_count, algorithm);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn triangle_fan_is_watertight() {
+    let texture = counter_texture();
+    let center = (31.7_f32, 32.2_f32);
+    let corners = [(8_f32, 8_f32), (32_f32, 8_f32), (56_f32, 8_f32), (56_f32, 32.5_f32), (56_f32, 56_f32), (20.25_f32, 56_f32), (8_f32, 56_f32), (8_f32, 31_f32)];
+
+    for &algorithm in [RasterAlgorithm::Scanline, RasterAlgorithm::HalfSpace].iter() {
+        let mut render_context = context_with_algorithm(1, algorithm);
+        for idx in 0..corners.len() {
+            let from = corners[idx];
+            let to = corners[(idx + 1) % corners.len()];
+            render_context.draw_triangle(&vertex(center.0, center.1), &vertex(from.0, from.1), &vertex(to.0, to.1), &texture);
+        }
+
+        //NOTE(dustin): the top and left border is on pixel centers and drawn, the right and bottom border is not
+        for y in 0..SIZE as i32 {
+            for x in 0..SIZE as i32 {
+                let expected = if (8..56).contains(&x) && (8..56).contains(&y) { 1 } else { 0 };
+                assert_eq!(get_count(&render_context, x, y), expected, "pixel {} {} with {:?}", x, y, algorithm);
+            }
+        }
+    }
+}
+
+#[test]
+fn small_triangles_are_kept() {
+    let texture = counter_texture();
+    let mut render_context = context(1);
+
+    //NOTE(dustin): less than a pixel in size but covers the center of pixel 20 20
+    render_context.draw_triangle(&vertex(19.7_f32, 19.8_f32), &vertex(20.4_f32, 19.9_f32), &vertex(19.9_f32, 20.3_f32), &texture);
+    assert_eq!(get_count(&render_context, 20, 20), 1);
+
+    //NOTE(dustin): back facing after snapping to the sub pixel grid
+    render_context.draw_triangle(&vertex(30.2_f32, 29.7_f32), &vertex(29.9_f32, 30.4_f32), &vertex(30.6_f32, 30.3_f32), &texture);
+    assert_eq!(get_count(&render_context, 30, 30), 0);
+}
+
+//NOTE(dustin): red for the front and blue for the back of a triangle
+struct FacingShader;
+
+impl FragmentShader for FacingShader {
+    fn shade(&self, fragment: &Fragment) -> Option<orbclient::Color> {
+        Some(orbclient::Color { data: if fragment.front_facing { 0xFFFF0000 } else { 0xFF0000FF } })
+    }
+}
+
+//NOTE(dustin): the left triangle is clockwise on screen and the right one counter clockwise
+fn draw_facing_triangles(render_context: &mut RenderContext) {
+    let identity = Matrix4f32::new().init_translation(0_f32, 0_f32, 0_f32);
+    let vertex_shader = TransformShader::new(&identity);
+
+    render_context.draw_triangle_shaded(&vertex(4_f32, 4_f32), &vertex(28_f32, 4_f32), &vertex(4_f32, 28_f32), &vertex_shader, &FacingShader);
+    render_context.draw_triangle_shaded(&vertex(36_f32, 4_f32), &vertex(36_f32, 28_f32), &vertex(60_f32, 4_f32), &vertex_shader, &FacingShader);
+}
+
+#[test]
+fn cull_mode_and_front_face() {
+    const NONE: u32 = 0xFF000000;
+    const FRONT: u32 = 0xFFFF0000;
+    const BACK: u32 = 0xFF0000FF;
+
+    let cases = [
+        (CullMode::Back, FrontFace::Clockwise, FRONT, NONE),
+        (CullMode::Front, FrontFace::Clockwise, NONE, BACK),
+        (CullMode::None, FrontFace::Clockwise, FRONT, BACK),
+        (CullMode::Back, FrontFace::CounterClockwise, NONE, FRONT),
+        (CullMode::Front, FrontFace::CounterClockwise, BACK, NONE),
+        (CullMode::None, FrontFace::CounterClockwise, BACK, FRONT),
+    ];
+
+    for &(cull_mode, front_face, left, right) in cases.iter() {
+        let mut render_context = context(1);
+        render_context.set_blend_state(BlendState::new());
+        render_context.set_raster_state(RasterState::new().init_cull_mode(cull_mode).init_front_face(front_face));
+        draw_facing_triangles(&mut render_context);
+
+        let target = render_context.get_target();
+        assert_eq!(target.get_pixel(10, 10).data, left, "clockwise triangle with {:?} {:?}", cull_mode, front_face);
+        assert_eq!(target.get_pixel(42, 10).data, right, "counter clockwise triangle with {:?} {:?}", cull_mode, front_face);
+    }
+
+    let default_state = RasterState::new();
+    assert_eq!((default_state.cull_mode, default_state.front_face), (CullMode::Back, FrontFace::Clockwise));
+}
+
+#[test]
+fn material_raster_state() {
+    //NOTE(dustin): from inside the cube only the back faces are visible
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/cube.obj");
+    let mut scene = Scene::new();
+    scene.shading = None;
+    let mesh = scene.add_mesh(Mesh::from_path(path.to_str().unwrap().to_string()).unwrap());
+    let material = scene.add_material(Material::new("inside"));
+    scene.add_node(Node::new("cube").init_mesh(mesh).init_material(material), None);
+
+    let camera = Camera::new(Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), Projection::Perspective { fov: 70.0_f32.to_radians(), aspect_ratio: 1_f32, z_near: 0.1_f32, z_far: 100_f32 });
+    let mut drawn_counts = Vec::new();
+    for &double_sided in [false, true].iter() {
+        scene.materials[material].raster_state = if double_sided { Some(RasterState::new().init_cull_mode(CullMode::None)) } else { None };
+
+        let mut render_context = context(1);
+        render_context.set_blend_state(BlendState::new());
+        render_context.draw_scene(&scene, &camera);
+        assert_eq!(render_context.get_raster_state(), RasterState::new(), "the material state only applies to its own draws");
+
+        drawn_counts.push(render_context.get_target().color.iter().filter(|color| color.data != 0xFF000000).count());
+    }
+
+    assert_eq!(drawn_counts, vec![0, (SIZE * SIZE) as usize]);
+}
+
+#[test]
+fn halfspace_lanes() {
+    let lanes = Lanes::ramp(-2, 1);
+    assert_eq!(lanes.values, [-2, -1, 0, 1, 2, 3, 4, 5]);
+    assert_eq!(lanes.get_inside_mask(), 0b11111100);
+    assert_eq!(lanes.add(&Lanes::splat(1)).get_inside_mask(), 0b11111110);
+    assert_eq!(lanes.or(&Lanes::ramp(5, -1)).get_inside_mask(), 0b00111100);
+}
+
+//NOTE(dustin): a target that is not a multiple of the tile size in either direction, drawn over several frames while
+// the pool is reused and resized
+#[test]
+fn tiles_match_single_threaded() {
+    let texture = counter_texture();
+    let clip_vertex = |x: f32, y: f32| Vertex::new_with_pos_and_texcoords(Vector4f32::new(x, y, 0_f32, 1_f32), Vector4f32::new(0.5_f32, 0.5_f32, 0_f32, 0_f32));
+    let draw = |render_context: &mut RenderContext| {
+        render_context.get_target_mut().clear(orbclient::Color { data: 0xFF000000 });
+        for idx in 0..12 {
+            let angle = idx as f32 * 0.53_f32;
+            let (sin, cos) = angle.sin_cos();
+            render_context.draw_triangle(&clip_vertex(0.1_f32, -0.05_f32), &clip_vertex(cos * 1.3_f32, sin * 0.9_f32), &clip_vertex(-sin * 0.4_f32, cos * 1.1_f32), &texture);
+        }
+        render_context.get_target().color.iter().map(|color| color.data).collect::<Vec<u32>>()
+    };
+
+    let context = || {
+        let mut render_context = RenderContext::new(300, 170);
+        render_context.set_depth_func(DepthFunc::Always);
+        render_context.set_raster_state(RasterState::new().init_cull_mode(CullMode::None));
+        render_context.set_blend_state(BlendState { mode: BlendMode::Additive, alpha_threshold: None });
+        render_context
+    };
+
+    let expected = draw(&mut context());
+    assert!(expected.iter().filter(|&&data| data & 0xFF > 1).count() > 1000, "the triangles have to overlap");
+
+    let mut tiled = context();
+    for &worker_count in [3, 3, 2, 1, 4].iter() {
+        tiled.set_worker_count(worker_count);
+        assert_eq!(tiled.get_worker_count(), worker_count);
+        assert!(draw(&mut tiled) == expected, "{} workers differ from the single threaded path", worker_count);
+    }
+}
+
+//NOTE(dustin): prints the time per frame of both algorithms, run it with cargo test --release -- --ignored --nocapture.
+// the monkey fills most of an 800x600 target and FacingShader keeps the cost of shading low. no assert, the result depends
+// on the machine and both shade the same pixels through Rasterizer::shade_pixel
+#[test]
+#[ignore]
+fn halfspace_against_scanline() {
+    const FRAMES: u32 = 20;
+    const ROUNDS: u32 = 5;
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/monkey2.obj");
+    let mesh = Mesh::from_path(path.to_str().unwrap().to_string()).unwrap();
+    let mut camera = Camera::new(Vector4f32::new(0_f32, 0_f32, -2.2_f32, 1_f32), Projection::Perspective { fov: 1.2_f32, aspect_ratio: 800_f32 / 600_f32, z_near: 0.1_f32, z_far: 100_f32 });
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32));
+    let transform = camera.get_view_projection();
+    let vertex_shader = TransformShader::new(&transform);
+
+    //NOTE(dustin): the algorithms take turns and the fastest round of each counts, so other load on the machine hurts both
+    let mut durations = [Duration::from_secs(1); 2];
+    for _ in 0..ROUNDS {
+        for (idx, &algorithm) in [RasterAlgorithm::Scanline, RasterAlgorithm::HalfSpace].iter().enumerate() {
+            let mut render_context = RenderContext::new(800, 600);
+            render_context.set_raster_algorithm(algorithm);
+            render_context.set_raster_state(RasterState::new().init_cull_mode(CullMode::None));
+
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                render_context.clear();
+                render_context.draw_mesh_shaded(&mesh, &vertex_shader, &FacingShader);
+            }
+            durations[idx] = durations[idx].min(start.elapsed() / FRAMES);
+        }
+    }
+    println!("Scanline: {:?} per frame, HalfSpace: {:?} per frame", durations[0], durations[1]);
+}
@@ -0,0 +1,105 @@
+extern crate orbclient;
+extern crate pixelcannon;
+
+use std::f32;
+
+use pixelcannon::texture::bitmap::BitmapTexture;
+use pixelcannon::texture::sampler::{Filter, Sampler, TexCoords, Wrap};
+
+const BORDER: u32 = 0xFF00FF00;
+
+//NOTE(dustin): 4x1 texture with the texel index in the red channel
+fn ramp_texture() -> BitmapTexture {
+    let mut texture = BitmapTexture::new(4, 1);
+    for x in 0..4 {
+        texture.set_pixel(x, 0, 255, x as u8, 0, 0);
+    }
+    texture.generate_mipmaps();
+    texture
+}
+
+fn sample_red(sampler: &Sampler, texture: &BitmapTexture, u: f32) -> u32 {
+    (sampler.sample_lod(texture, u, 0_f32, 0_f32).data >> 16) & 0xFF
+}
+
+#[test]
+fn wrap_modes_nearest() {
+    let texture = ramp_texture();
+    let sampler = |wrap| Sampler::new().init_wrap(wrap, Wrap::ClampToEdge).init_border_color(orbclient::Color { data: BORDER });
+
+    //NOTE(dustin): texel idx covers idx / 4 to (idx + 1) / 4, the coordinates are texel centers
+    let repeat = sampler(Wrap::Repeat);
+    assert_eq!(sample_red(&repeat, &texture, 0.125_f32), 0);
+    assert_eq!(sample_red(&repeat, &texture, 0.625_f32), 2);
+    assert_eq!(sample_red(&repeat, &texture, 1_f32 + 0.625_f32), 2);
+    assert_eq!(sample_red(&repeat, &texture, -0.375_f32), 2);
+    assert_eq!(sample_red(&repeat, &texture, 1_f32), 0);
+
+    let mirrored = sampler(Wrap::MirroredRepeat);
+    assert_eq!(sample_red(&mirrored, &texture, 0.375_f32), 1);
+    assert_eq!(sample_red(&mirrored, &texture, 1_f32 + 0.375_f32), 2);
+    assert_eq!(sample_red(&mirrored, &texture, -0.375_f32), 1);
+    assert_eq!(sample_red(&mirrored, &texture, 2_f32 + 0.375_f32), 1);
+
+    let clamp = sampler(Wrap::ClampToEdge);
+    assert_eq!(sample_red(&clamp, &texture, -5_f32), 0);
+    assert_eq!(sample_red(&clamp, &texture, 7_f32), 3);
+
+    let border = sampler(Wrap::ClampToBorder);
+    assert_eq!(border.sample_lod(&texture, -0.5_f32, 0_f32, 0_f32).data, BORDER);
+    assert_eq!(border.sample_lod(&texture, 1.5_f32, 0_f32, 0_f32).data, BORDER);
+    assert_eq!(sample_red(&border, &texture, 0.875_f32), 3);
+}
+
+#[test]
+fn bilinear_blends_into_border() {
+    let texture = ramp_texture();
+    let sampler = Sampler::with_filter(Filter::Bilinear)
+        .init_wrap(Wrap::ClampToBorder, Wrap::ClampToEdge)
+        .init_border_color(orbclient::Color { data: BORDER });
+
+    //NOTE(dustin): u = 1 is halfway between the center of texel 3 and the one of the border texel after it
+    let color = sampler.sample_lod(&texture, 1_f32, 0_f32, 0_f32).data;
+    assert_eq!((color >> 16) & 0xFF, 2);
+    assert_eq!((color >> 8) & 0xFF, 128);
+}
+
+//NOTE(dustin): a tiled texture has no seam, across u = 1 the last texel blends into the first like any two neighbours
+#[test]
+fn bilinear_repeat_blends_across_seam() {
+    let mut texture = BitmapTexture::new(4, 1);
+    for x in 0..4 {
+        texture.set_pixel(x, 0, 255, (x * 60) as u8, 0, 0);
+    }
+    let sampler = Sampler::with_filter(Filter::Bilinear).init_wrap(Wrap::Repeat, Wrap::ClampToEdge);
+
+    for &u in [0_f32, 1_f32, 3_f32, -2_f32].iter() {
+        assert_eq!(sample_red(&sampler, &texture, u), 90, "no blend of the first and last texel at u = {}", u);
+    }
+
+    //NOTE(dustin): a quarter texel on either side of the seam
+    assert_eq!(sample_red(&sampler, &texture, 1_f32 - 0.0625_f32), 135);
+    assert_eq!(sample_red(&sampler, &texture, 1_f32 + 0.0625_f32), 45);
+}
+
+#[test]
+fn sampling_never_panics() {
+    let texture = ramp_texture();
+    let coords = [0_f32, 1_f32, -1_f32, 0.999_f32, 1e-7_f32, -1e-7_f32, 123456.7_f32, -98765.4_f32,
+        f32::MAX, f32::MIN, f32::INFINITY, f32::NEG_INFINITY, f32::NAN];
+    let wraps = [Wrap::Repeat, Wrap::MirroredRepeat, Wrap::ClampToEdge, Wrap::ClampToBorder];
+    let filters = [Filter::Nearest, Filter::Bilinear, Filter::Trilinear];
+
+    for &filter in filters.iter() {
+        for &wrap_u in wraps.iter() {
+            for &wrap_v in wraps.iter() {
+                let sampler = Sampler::with_filter(filter).init_wrap(wrap_u, wrap_v);
+                for &u in coords.iter() {
+                    for &v in coords.iter() {
+                        sampler.sample_grad(&texture, &TexCoords::new(u, v).init_derivatives(u, v, v, u));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+extern crate orbimage;
+extern crate pixelcannon;
+
+use std::path::PathBuf;
+
+use orbimage::Image;
+
+use pixelcannon::camera::{Camera, Projection};
+use pixelcannon::light::{Attenuation, Light, Lighting, ShadingMode};
+use pixelcannon::model::material::Material;
+use pixelcannon::model::mesh::Mesh;
+use pixelcannon::primitive::matrix::Matrix4f32;
+use pixelcannon::primitive::quaternion::Quaternion;
+use pixelcannon::primitive::vector::Vector4f32;
+use pixelcannon::render::RenderContext;
+use pixelcannon::scene::{Node, Scene, Transform};
+use pixelcannon::texture::bitmap::BitmapTexture;
+
+const EPSILON: f32 = 1e-4;
+
+fn manifest_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)
+}
+
+fn up() -> Vector4f32 {
+    Vector4f32::new(0_f32, 1_f32, 0_f32, 0_f32)
+}
+
+fn assert_vector(actual: &Vector4f32, expected: &Vector4f32) {
+    assert!(actual.sub_v(expected).length() < EPSILON, "expected {:?}, got {:?}", expected, actual);
+}
+
+fn assert_matrix(actual: &Matrix4f32, expected: &Matrix4f32) {
+    for r_idx in 0..4 {
+        for c_idx in 0..4 {
+            assert!((actual.m[r_idx][c_idx] - expected.m[r_idx][c_idx]).abs() < EPSILON,
+                "m[{}][{}] expected {}, got {}", r_idx, c_idx, expected.m[r_idx][c_idx], actual.m[r_idx][c_idx]);
+        }
+    }
+}
+
+//NOTE(dustin): a pivot turned by 90 degrees around y with a child 2 units along +z
+fn orbit_scene() -> (Scene, usize, usize) {
+    let mut scene = Scene::new();
+    let pivot = scene.add_node(Node::new("pivot").init_transform(Transform::new()
+        .init_translation(1_f32, 0_f32, 0_f32)
+        .init_rotation(Quaternion::from_axis_angle(&up(), std::f32::consts::PI / 2_f32))), None);
+    let child = scene.add_node(Node::new("child").init_transform(Transform::new()
+        .init_translation(0_f32, 0_f32, 2_f32)
+        .init_scale(0.5_f32, 0.5_f32, 0.5_f32)), Some(pivot));
+
+    (scene, pivot, child)
+}
+
+#[test]
+fn transform_matches_matrices() {
+    let rotation = Quaternion::from_euler(0.3_f32, -0.2_f32, 1_f32);
+    let transform = Transform::new().init_translation(1_f32, 2_f32, 3_f32).init_rotation(rotation).init_scale(2_f32, 1_f32, 0.5_f32);
+    let expected = Matrix4f32::new().init_translation(1_f32, 2_f32, 3_f32)
+        .mul(&rotation.to_matrix())
+        .mul(&Matrix4f32::new().init_scale(2_f32, 1_f32, 0.5_f32));
+
+    assert_matrix(&transform.to_matrix(), &expected);
+    assert_matrix(&Transform::from_matrix(&expected).unwrap().to_matrix(), &expected);
+    assert_matrix(&Transform::new().to_matrix(), &Matrix4f32::new().init_translation(0_f32, 0_f32, 0_f32));
+}
+
+#[test]
+fn world_matrices_follow_the_hierarchy() {
+    let (scene, pivot, child) = orbit_scene();
+    let world = scene.calc_world_matrices();
+
+    assert_eq!(scene.get_node(pivot).children, vec![child]);
+    assert_eq!(scene.get_node(child).parent, Some(pivot));
+    assert_eq!(scene.find_node("child"), Some(child));
+
+    //NOTE(dustin): +z turned by 90 degrees around y ends up on +x, then the pivot moves it one more unit
+    assert_vector(&world[child].transform(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32)), &Vector4f32::new(3_f32, 0_f32, 0_f32, 1_f32));
+    assert_matrix(&world[child], &scene.get_node(pivot).transform.to_matrix().mul(&scene.get_node(child).transform.to_matrix()));
+    assert_matrix(&scene.calc_world_matrix(child), &world[child]);
+}
+
+#[test]
+#[should_panic]
+fn parent_has_to_exist() {
+    let mut scene = Scene::new();
+    scene.add_node(Node::new("orphan"), Some(0));
+}
+
+#[test]
+fn lights_move_with_their_node() {
+    let (mut scene, _, child) = orbit_scene();
+    scene.get_node_mut(child).light = Some(Light::Point {
+        position: Vector4f32::new(0_f32, 1_f32, 0_f32, 1_f32),
+        color: Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32),
+        attenuation: Attenuation::new(1_f32, 0_f32, 0_f32)
+    });
+    scene.add_node(Node::new("sun").init_light(Light::Directional { direction: Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32), color: up() }), Some(0));
+
+    let lights = scene.collect_lights(&scene.calc_world_matrices());
+    assert_eq!(lights.len(), 2);
+    match lights[0] {
+        Light::Point { position, .. } => assert_vector(&position, &Vector4f32::new(3_f32, 0.5_f32, 0_f32, 1_f32)),
+        _ => panic!("expected a point light, got {:?}", lights[0]),
+    }
+    match lights[1] {
+        Light::Directional { direction, .. } => assert_vector(&direction, &Vector4f32::new(1_f32, 0_f32, 0_f32, 0_f32)),
+        _ => panic!("expected a directional light, got {:?}", lights[1]),
+    }
+}
+
+//NOTE(dustin): a direction with w = 1 is still a direction, the translation of the node must not change it
+#[test]
+fn light_directions_ignore_translation() {
+    let spot = Light::Spot {
+        position: Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32),
+        direction: Vector4f32::new(0_f32, 0_f32, 1_f32, 1_f32),
+        color: up(),
+        attenuation: Attenuation::new(1_f32, 0_f32, 0_f32),
+        inner_angle: 0.2_f32,
+        outer_angle: 0.4_f32
+    };
+
+    match spot.transform(&Matrix4f32::new().init_translation(5_f32, 6_f32, 7_f32)) {
+        Light::Spot { position, direction, .. } => {
+            assert_vector(&position, &Vector4f32::new(5_f32, 6_f32, 7_f32, 1_f32));
+            assert_vector(&direction, &Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32));
+        },
+        light => panic!("expected a spot light, got {:?}", light),
+    }
+}
+
+#[test]
+fn zero_attenuation_is_finite() {
+    let mut lighting = Lighting::new(Vector4f32::new(0_f32, 0_f32, -1_f32, 1_f32));
+    lighting.add_light(Light::Point {
+        position: Vector4f32::new(0_f32, 0_f32, -1_f32, 1_f32),
+        color: Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32),
+        attenuation: Attenuation::new(0_f32, 0_f32, 0_f32)
+    });
+
+    let light = lighting.calc_light(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &Vector4f32::new(0_f32, 0_f32, -1_f32, 0_f32), true, &lighting.get_reflectance());
+    assert!(light.x.is_finite() && light.y.is_finite() && light.z.is_finite(), "expected a finite color, got {:?}", light);
+    assert!(light.x > 1_f32);
+}
+
+#[test]
+fn unlit_scene_matches_draw_mesh() {
+    let mesh_path = manifest_path("assets/monkey2.obj").to_str().unwrap().to_string();
+    let load_texture = || BitmapTexture::from_orbimage(&Image::from_path(manifest_path("assets/img.png")).unwrap());
+
+    let mut camera = Camera::new(Vector4f32::new(1_f32, 1_f32, -3_f32, 1_f32), Projection::Perspective { fov: 1.2_f32, aspect_ratio: 4_f32 / 3_f32, z_near: 0.1_f32, z_far: 100_f32 });
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &up());
+    let transform = Transform::new().init_rotation(Quaternion::from_euler(0.2_f32, 2.8_f32, 0_f32));
+
+    let mut material = Material::new("monkey");
+    material.diffuse_texture = Some(load_texture());
+    let mut scene = Scene::new();
+    scene.shading = None;
+    let mesh = scene.add_mesh(Mesh::from_path(mesh_path.clone()).unwrap());
+    let material = scene.add_material(material);
+    scene.add_node(Node::new("monkey").init_mesh(mesh).init_material(material).init_transform(transform), None);
+
+    let mut scene_context = RenderContext::new(80, 60);
+    scene_context.clear();
+    scene_context.draw_scene(&scene, &camera);
+
+    let mut mesh_context = RenderContext::new(80, 60);
+    mesh_context.clear();
+    mesh_context.draw_mesh(&Mesh::from_path(mesh_path).unwrap(), &camera.get_view_projection().mul(&transform.to_matrix()), &load_texture());
+
+    let scene_target = scene_context.into_target();
+    let mesh_target = mesh_context.into_target();
+    let differing = scene_target.color.iter().zip(mesh_target.color.iter()).filter(|&(a, b)| a.data != b.data).count();
+    assert_eq!(differing, 0);
+}
+
+#[test]
+fn material_specular_lights_scene() {
+    let mut camera = Camera::new(Vector4f32::new(0_f32, 0_f32, -3_f32, 1_f32), Projection::Perspective { fov: 1.2_f32, aspect_ratio: 1_f32, z_near: 0.1_f32, z_far: 100_f32 });
+    camera.look_at(&Vector4f32::new(0_f32, 0_f32, 0_f32, 1_f32), &up());
+
+    let render = |mode: ShadingMode, specular: f32| {
+        let mut material = Material::new("white");
+        material.diffuse = Vector4f32::new(0.5_f32, 0.5_f32, 0.5_f32, 0_f32);
+        material.specular = Vector4f32::new(specular, specular, specular, 0_f32);
+        material.specular_exponent = 8_f32;
+
+        let mut scene = Scene::new();
+        scene.shading = Some(mode);
+        let mesh = scene.add_mesh(Mesh::from_path(manifest_path("assets/sphere.obj").to_str().unwrap().to_string()).unwrap());
+        let material = scene.add_material(material);
+        scene.add_node(Node::new("sphere").init_mesh(mesh).init_material(material), None);
+        scene.add_node(Node::new("sun").init_light(Light::Directional { direction: Vector4f32::new(0_f32, 0_f32, 1_f32, 0_f32), color: Vector4f32::new(1_f32, 1_f32, 1_f32, 0_f32) }), None);
+
+        let mut render_context = RenderContext::new(40, 40);
+        render_context.clear();
+        render_context.draw_scene(&scene, &camera);
+        render_context.into_target().get_pixel(20, 20).data & 0xFF
+    };
+
+    for &mode in [ShadingMode::Gouraud, ShadingMode::Phong, ShadingMode::BlinnPhong].iter() {
+        let matte = render(mode, 0_f32);
+        let shiny = render(mode, 1_f32);
+        assert!((120..129).contains(&matte), "{:?} the matte sphere is only diffuse, got {}", mode, matte);
+        assert!(shiny > 240, "{:?} Ks of the material adds a highlight, got {}", mode, shiny);
+    }
+}